@@ -0,0 +1,28 @@
+#![no_main]
+
+// Run with `cargo +nightly fuzz run markdown_render` from the repository root.
+use chat_app::services::markdown;
+use libfuzzer_sys::fuzz_target;
+use scraper::Html;
+
+fuzz_target!(|source: &str| {
+    let html = markdown::render(source);
+    assert!(!html.to_ascii_lowercase().contains("<script"), "script tag in {:?}", html);
+
+    for node in Html::parse_fragment(&html).tree.nodes() {
+        let Some(element) = node.value().as_element() else {
+            continue;
+        };
+        for (name, value) in element.attrs() {
+            assert!(!name.to_ascii_lowercase().starts_with("on"), "event handler in {:?}", html);
+            if name == "href" {
+                let scheme = value.trim_start().to_ascii_lowercase();
+                assert!(
+                    !scheme.starts_with("javascript:") && !scheme.starts_with("data:"),
+                    "unsafe link in {:?}",
+                    html
+                );
+            }
+        }
+    }
+});
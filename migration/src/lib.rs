@@ -1,10 +1,48 @@
 pub use sea_orm_migration::prelude::*;
 
-mod m20220101_000001_create_table;
 mod m20251111_040042_create_users;
 mod m20251111_040123_create_rooms;
 mod m20251111_040129_create_messages;
 mod m20251111_040134_create_room_members;
+mod m20251201_090000_create_webhook_endpoints;
+mod m20251201_090100_create_notifications;
+mod m20251201_090200_add_notify_settings_to_room_members;
+mod m20251201_090300_add_is_direct_to_rooms;
+mod m20251208_100000_create_bots;
+mod m20251208_100100_create_incoming_webhooks;
+mod m20251208_100200_create_outgoing_webhooks;
+mod m20251215_080000_add_topic_to_rooms;
+mod m20251215_080100_create_bot_commands;
+mod m20251222_090000_add_format_to_messages;
+mod m20251229_100000_create_link_previews;
+mod m20251229_100100_create_message_link_previews;
+mod m20260105_090000_add_email_verified_at_to_users;
+mod m20260105_090100_create_auth_tokens;
+mod m20260112_090000_create_user_totp;
+mod m20260112_090100_create_recovery_codes;
+mod m20260119_090000_create_user_identities;
+mod m20260119_090100_create_oidc_login_states;
+mod m20260126_090000_add_role_and_status_to_users;
+mod m20260126_090100_create_audit_logs;
+mod m20260202_090000_add_role_to_room_members;
+mod m20260202_090100_add_slow_mode_to_rooms;
+mod m20260202_090200_create_room_bans;
+mod m20260202_090300_create_room_mutes;
+mod m20260209_090000_create_message_reports;
+mod m20260216_090000_create_room_filter_settings;
+mod m20260223_090000_create_user_blocks;
+mod m20260302_090000_create_room_pins;
+mod m20260302_090100_create_saved_messages;
+mod m20260309_090000_create_scheduled_jobs;
+mod m20260316_090000_create_polls;
+mod m20260316_090100_create_poll_options;
+mod m20260316_090200_create_poll_votes;
+mod m20260323_090000_add_retention_to_rooms;
+mod m20260323_090100_create_server_settings;
+mod m20260330_090000_add_thread_id_to_messages;
+mod m20260330_090100_create_import_mappings;
+mod m20260406_090000_add_delete_after_to_users;
+mod m20260406_090100_restrict_message_sender_delete;
 
 pub struct Migrator;
 
@@ -16,6 +54,45 @@ impl MigratorTrait for Migrator {
             Box::new(m20251111_040123_create_rooms::Migration),
             Box::new(m20251111_040129_create_messages::Migration),
             Box::new(m20251111_040134_create_room_members::Migration),
+            Box::new(m20251201_090000_create_webhook_endpoints::Migration),
+            Box::new(m20251201_090100_create_notifications::Migration),
+            Box::new(m20251201_090200_add_notify_settings_to_room_members::Migration),
+            Box::new(m20251201_090300_add_is_direct_to_rooms::Migration),
+            Box::new(m20251208_100000_create_bots::Migration),
+            Box::new(m20251208_100100_create_incoming_webhooks::Migration),
+            Box::new(m20251208_100200_create_outgoing_webhooks::Migration),
+            Box::new(m20251215_080000_add_topic_to_rooms::Migration),
+            Box::new(m20251215_080100_create_bot_commands::Migration),
+            Box::new(m20251222_090000_add_format_to_messages::Migration),
+            Box::new(m20251229_100000_create_link_previews::Migration),
+            Box::new(m20251229_100100_create_message_link_previews::Migration),
+            Box::new(m20260105_090000_add_email_verified_at_to_users::Migration),
+            Box::new(m20260105_090100_create_auth_tokens::Migration),
+            Box::new(m20260112_090000_create_user_totp::Migration),
+            Box::new(m20260112_090100_create_recovery_codes::Migration),
+            Box::new(m20260119_090000_create_user_identities::Migration),
+            Box::new(m20260119_090100_create_oidc_login_states::Migration),
+            Box::new(m20260126_090000_add_role_and_status_to_users::Migration),
+            Box::new(m20260126_090100_create_audit_logs::Migration),
+            Box::new(m20260202_090000_add_role_to_room_members::Migration),
+            Box::new(m20260202_090100_add_slow_mode_to_rooms::Migration),
+            Box::new(m20260202_090200_create_room_bans::Migration),
+            Box::new(m20260202_090300_create_room_mutes::Migration),
+            Box::new(m20260209_090000_create_message_reports::Migration),
+            Box::new(m20260216_090000_create_room_filter_settings::Migration),
+            Box::new(m20260223_090000_create_user_blocks::Migration),
+            Box::new(m20260302_090000_create_room_pins::Migration),
+            Box::new(m20260302_090100_create_saved_messages::Migration),
+            Box::new(m20260309_090000_create_scheduled_jobs::Migration),
+            Box::new(m20260316_090000_create_polls::Migration),
+            Box::new(m20260316_090100_create_poll_options::Migration),
+            Box::new(m20260316_090200_create_poll_votes::Migration),
+            Box::new(m20260323_090000_add_retention_to_rooms::Migration),
+            Box::new(m20260323_090100_create_server_settings::Migration),
+            Box::new(m20260330_090000_add_thread_id_to_messages::Migration),
+            Box::new(m20260330_090100_create_import_mappings::Migration),
+            Box::new(m20260406_090000_add_delete_after_to_users::Migration),
+            Box::new(m20260406_090100_restrict_message_sender_delete::Migration),
         ]
     }
 }
@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RoomMembers::Table)
+                    .add_column(
+                        ColumnDef::new(RoomMembers::NotifyLevel)
+                            .string_len(16)
+                            .not_null()
+                            .default("mentions"),
+                    )
+                    .add_column(
+                        ColumnDef::new(RoomMembers::NotificationsMuted)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RoomMembers::Table)
+                    .drop_column(RoomMembers::NotifyLevel)
+                    .drop_column(RoomMembers::NotificationsMuted)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RoomMembers {
+    Table,
+    NotifyLevel,
+    NotificationsMuted,
+}
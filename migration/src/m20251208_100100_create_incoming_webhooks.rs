@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IncomingWebhooks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IncomingWebhooks::Id)
+                            .string_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(IncomingWebhooks::RoomId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IncomingWebhooks::BotId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IncomingWebhooks::TokenHash)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IncomingWebhooks::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incoming_webhooks_room_id")
+                            .from(IncomingWebhooks::Table, IncomingWebhooks::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incoming_webhooks_bot_id")
+                            .from(IncomingWebhooks::Table, IncomingWebhooks::BotId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_incoming_webhooks_room_id")
+                            .table(IncomingWebhooks::Table)
+                            .col(IncomingWebhooks::RoomId),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_incoming_webhooks_token_hash")
+                            .table(IncomingWebhooks::Table)
+                            .col(IncomingWebhooks::TokenHash)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IncomingWebhooks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IncomingWebhooks {
+    Table,
+    Id,
+    RoomId,
+    BotId,
+    TokenHash,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BotCommands::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BotCommands::Id)
+                            .string_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BotCommands::BotId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BotCommands::Name)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BotCommands::Description)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BotCommands::Url)
+                            .string_len(1024)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BotCommands::Secret)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BotCommands::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_bot_commands_bot_id")
+                            .from(BotCommands::Table, BotCommands::BotId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_bot_commands_bot_id_name")
+                            .table(BotCommands::Table)
+                            .col(BotCommands::BotId)
+                            .col(BotCommands::Name)
+                            .unique(),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_bot_commands_name")
+                            .table(BotCommands::Table)
+                            .col(BotCommands::Name),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BotCommands::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BotCommands {
+    Table,
+    Id,
+    BotId,
+    Name,
+    Description,
+    Url,
+    Secret,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LinkPreviews::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LinkPreviews::Id)
+                            .string_len(64)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LinkPreviews::Url).text().not_null())
+                    .col(
+                        ColumnDef::new(LinkPreviews::Title)
+                            .string_len(512)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(LinkPreviews::Description)
+                            .string_len(1024)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(LinkPreviews::ImageUrl).text().null())
+                    .col(
+                        ColumnDef::new(LinkPreviews::SiteName)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(LinkPreviews::Status)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LinkPreviews::FetchedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LinkPreviews::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LinkPreviews {
+    Table,
+    Id,
+    Url,
+    Title,
+    Description,
+    ImageUrl,
+    SiteName,
+    Status,
+    FetchedAt,
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MessageLinkPreviews::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MessageLinkPreviews::MessageId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageLinkPreviews::PreviewId)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageLinkPreviews::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_message_link_previews")
+                            .col(MessageLinkPreviews::MessageId)
+                            .col(MessageLinkPreviews::PreviewId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_link_previews_message_id")
+                            .from(MessageLinkPreviews::Table, MessageLinkPreviews::MessageId)
+                            .to(Messages::Table, Messages::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_link_previews_preview_id")
+                            .from(MessageLinkPreviews::Table, MessageLinkPreviews::PreviewId)
+                            .to(LinkPreviews::Table, LinkPreviews::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MessageLinkPreviews::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MessageLinkPreviews {
+    Table,
+    MessageId,
+    PreviewId,
+    Position,
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum LinkPreviews {
+    Table,
+    Id,
+}
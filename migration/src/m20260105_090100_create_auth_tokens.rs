@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuthTokens::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuthTokens::Id)
+                            .string_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AuthTokens::UserId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AuthTokens::Purpose)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AuthTokens::TokenHash)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AuthTokens::ExpiresAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuthTokens::UsedAt).date_time().null())
+                    .col(
+                        ColumnDef::new(AuthTokens::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_auth_tokens_user_id")
+                            .from(AuthTokens::Table, AuthTokens::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_auth_tokens_token_hash")
+                            .table(AuthTokens::Table)
+                            .col(AuthTokens::TokenHash)
+                            .unique(),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_auth_tokens_user_id_purpose")
+                            .table(AuthTokens::Table)
+                            .col(AuthTokens::UserId)
+                            .col(AuthTokens::Purpose),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuthTokens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuthTokens {
+    Table,
+    Id,
+    UserId,
+    Purpose,
+    TokenHash,
+    ExpiresAt,
+    UsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserTotp::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserTotp::UserId)
+                            .string_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        // Room for the encrypted form, not just the base32 secret.
+                        ColumnDef::new(UserTotp::Secret)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserTotp::ConfirmedAt).date_time().null())
+                    .col(
+                        ColumnDef::new(UserTotp::LastUsedStep)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserTotp::FailedAttempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(UserTotp::LastFailedAt).date_time().null())
+                    .col(
+                        ColumnDef::new(UserTotp::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_totp_user_id")
+                            .from(UserTotp::Table, UserTotp::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserTotp::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserTotp {
+    Table,
+    UserId,
+    Secret,
+    ConfirmedAt,
+    LastUsedStep,
+    FailedAttempts,
+    LastFailedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
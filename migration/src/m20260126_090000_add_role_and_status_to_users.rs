@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::Role)
+                            .string_len(16)
+                            .not_null()
+                            .default("user"),
+                    )
+                    .add_column(
+                        ColumnDef::new(Users::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("active"),
+                    )
+                    .add_column(ColumnDef::new(Users::SuspendedUntil).date_time().null())
+                    .add_column(ColumnDef::new(Users::StatusReason).string_len(255).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Role)
+                    .drop_column(Users::Status)
+                    .drop_column(Users::SuspendedUntil)
+                    .drop_column(Users::StatusReason)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Role,
+    Status,
+    SuspendedUntil,
+    StatusReason,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RoomMembers::Table)
+                    .add_column(
+                        ColumnDef::new(RoomMembers::Role)
+                            .string_len(16)
+                            .not_null()
+                            .default("member"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Rooms never recorded their creator; the earliest member is the best guess for the owner.
+        // Members who joined in the same instant are told apart by user id, so each room gets one.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE room_members rm \
+                 JOIN (SELECT m.room_id, MIN(m.user_id) AS user_id FROM room_members m \
+                       JOIN (SELECT room_id, MIN(joined_at) AS joined_at FROM room_members GROUP BY room_id) earliest \
+                       ON m.room_id = earliest.room_id AND m.joined_at = earliest.joined_at \
+                       GROUP BY m.room_id) first \
+                 ON rm.room_id = first.room_id AND rm.user_id = first.user_id \
+                 SET rm.role = 'owner'",
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RoomMembers::Table)
+                    .drop_column(RoomMembers::Role)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RoomMembers {
+    Table,
+    Role,
+}
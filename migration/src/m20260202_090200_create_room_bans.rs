@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoomBans::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RoomBans::RoomId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoomBans::UserId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoomBans::CreatedBy)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoomBans::Reason)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(RoomBans::ExpiresAt).date_time().null())
+                    .col(
+                        ColumnDef::new(RoomBans::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_room_bans")
+                            .col(RoomBans::RoomId)
+                            .col(RoomBans::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_bans_room_id")
+                            .from(RoomBans::Table, RoomBans::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_bans_user_id")
+                            .from(RoomBans::Table, RoomBans::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_bans_created_by")
+                            .from(RoomBans::Table, RoomBans::CreatedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RoomBans::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RoomBans {
+    Table,
+    RoomId,
+    UserId,
+    CreatedBy,
+    Reason,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
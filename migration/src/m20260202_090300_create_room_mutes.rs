@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoomMutes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RoomMutes::RoomId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoomMutes::UserId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoomMutes::CreatedBy)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(RoomMutes::Reason)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(RoomMutes::ExpiresAt).date_time().null())
+                    .col(
+                        ColumnDef::new(RoomMutes::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_room_mutes")
+                            .col(RoomMutes::RoomId)
+                            .col(RoomMutes::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_mutes_room_id")
+                            .from(RoomMutes::Table, RoomMutes::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_mutes_user_id")
+                            .from(RoomMutes::Table, RoomMutes::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_room_mutes_created_by")
+                            .from(RoomMutes::Table, RoomMutes::CreatedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RoomMutes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RoomMutes {
+    Table,
+    RoomId,
+    UserId,
+    CreatedBy,
+    Reason,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
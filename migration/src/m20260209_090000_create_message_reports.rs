@@ -0,0 +1,174 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Reports are a moderation record and outlive their room and the reporter's account: the room id
+// is kept without a foreign key, and a deleted reporter leaves the report behind.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MessageReports::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MessageReports::Id)
+                            .string_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::RoomId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::MessageId)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::ReporterId)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::AuthorId)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::Reason)
+                            .string_len(500)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::ContentSnapshot)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::FormatSnapshot)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::MessageCreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("open"),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::Resolution)
+                            .string_len(32)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(MessageReports::ResolvedBy)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(MessageReports::ResolvedAt).date_time().null())
+                    .col(
+                        ColumnDef::new(MessageReports::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_reports_message_id")
+                            .from(MessageReports::Table, MessageReports::MessageId)
+                            .to(Messages::Table, Messages::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_reports_reporter_id")
+                            .from(MessageReports::Table, MessageReports::ReporterId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_reports_author_id")
+                            .from(MessageReports::Table, MessageReports::AuthorId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_message_reports_resolved_by")
+                            .from(MessageReports::Table, MessageReports::ResolvedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_message_reports_message_reporter")
+                            .table(MessageReports::Table)
+                            .col(MessageReports::MessageId)
+                            .col(MessageReports::ReporterId)
+                            .unique(),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_message_reports_room_status")
+                            .table(MessageReports::Table)
+                            .col(MessageReports::RoomId)
+                            .col(MessageReports::Status),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MessageReports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MessageReports {
+    Table,
+    Id,
+    RoomId,
+    MessageId,
+    ReporterId,
+    AuthorId,
+    Reason,
+    ContentSnapshot,
+    FormatSnapshot,
+    MessageCreatedAt,
+    Status,
+    Resolution,
+    ResolvedBy,
+    ResolvedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
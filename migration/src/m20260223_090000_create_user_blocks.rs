@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserBlocks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserBlocks::BlockerId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserBlocks::BlockedId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserBlocks::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_user_blocks")
+                            .col(UserBlocks::BlockerId)
+                            .col(UserBlocks::BlockedId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_blocks_blocker_id")
+                            .from(UserBlocks::Table, UserBlocks::BlockerId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_blocks_blocked_id")
+                            .from(UserBlocks::Table, UserBlocks::BlockedId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserBlocks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserBlocks {
+    Table,
+    BlockerId,
+    BlockedId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavedMessages::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SavedMessages::UserId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SavedMessages::MessageId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SavedMessages::Note)
+                            .string_len(500)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SavedMessages::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_saved_messages")
+                            .col(SavedMessages::UserId)
+                            .col(SavedMessages::MessageId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_saved_messages_user_id")
+                            .from(SavedMessages::Table, SavedMessages::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_saved_messages_message_id")
+                            .from(SavedMessages::Table, SavedMessages::MessageId)
+                            .to(Messages::Table, Messages::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SavedMessages::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SavedMessages {
+    Table,
+    UserId,
+    MessageId,
+    Note,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    Id,
+}
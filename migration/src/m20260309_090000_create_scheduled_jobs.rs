@@ -0,0 +1,171 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScheduledJobs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Id)
+                            .string_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Kind)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::UserId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::RoomId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::MessageId)
+                            .string_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Content)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Format)
+                            .string_len(16)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Note)
+                            .string_len(500)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::RunAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ScheduledJobs::LockedUntil).date_time().null())
+                    .col(
+                        ColumnDef::new(ScheduledJobs::LastError)
+                            .string_len(500)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(ScheduledJobs::CompletedAt).date_time().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_scheduled_jobs_user_id")
+                            .from(ScheduledJobs::Table, ScheduledJobs::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_scheduled_jobs_room_id")
+                            .from(ScheduledJobs::Table, ScheduledJobs::RoomId)
+                            .to(Rooms::Table, Rooms::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_scheduled_jobs_message_id")
+                            .from(ScheduledJobs::Table, ScheduledJobs::MessageId)
+                            .to(Messages::Table, Messages::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_scheduled_jobs_status_run_at")
+                            .table(ScheduledJobs::Table)
+                            .col(ScheduledJobs::Status)
+                            .col(ScheduledJobs::RunAt),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_scheduled_jobs_user_id")
+                            .table(ScheduledJobs::Table)
+                            .col(ScheduledJobs::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScheduledJobs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScheduledJobs {
+    Table,
+    Id,
+    Kind,
+    UserId,
+    RoomId,
+    MessageId,
+    Content,
+    Format,
+    Note,
+    RunAt,
+    Status,
+    Attempts,
+    LockedUntil,
+    LastError,
+    CreatedAt,
+    CompletedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    Id,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PollOptions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PollOptions::Id)
+                            .string_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PollOptions::PollId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PollOptions::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PollOptions::Text)
+                            .string_len(200)
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_options_poll_id")
+                            .from(PollOptions::Table, PollOptions::PollId)
+                            .to(Polls::Table, Polls::MessageId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_poll_options_poll_position")
+                            .table(PollOptions::Table)
+                            .col(PollOptions::PollId)
+                            .col(PollOptions::Position)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PollOptions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PollOptions {
+    Table,
+    Id,
+    PollId,
+    Position,
+    Text,
+}
+
+#[derive(DeriveIden)]
+enum Polls {
+    Table,
+    MessageId,
+}
@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PollVotes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PollVotes::PollId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PollVotes::UserId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    // 0 for every vote in a single-choice poll, the option's position otherwise,
+                    // so the primary key allows one vote per user or one per option respectively.
+                    .col(
+                        ColumnDef::new(PollVotes::Slot)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PollVotes::OptionId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PollVotes::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_poll_votes")
+                            .col(PollVotes::PollId)
+                            .col(PollVotes::UserId)
+                            .col(PollVotes::Slot),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_votes_poll_id")
+                            .from(PollVotes::Table, PollVotes::PollId)
+                            .to(Polls::Table, Polls::MessageId)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_votes_user_id")
+                            .from(PollVotes::Table, PollVotes::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_poll_votes_option_id")
+                            .from(PollVotes::Table, PollVotes::OptionId)
+                            .to(PollOptions::Table, PollOptions::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PollVotes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PollVotes {
+    Table,
+    PollId,
+    UserId,
+    Slot,
+    OptionId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Polls {
+    Table,
+    MessageId,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum PollOptions {
+    Table,
+    Id,
+}
@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rooms::Table)
+                    .add_column(ColumnDef::new(Rooms::RetentionMaxAgeDays).integer().null())
+                    .add_column(ColumnDef::new(Rooms::RetentionMaxMessages).integer().null())
+                    .add_column(
+                        ColumnDef::new(Rooms::LegalHold)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Rooms::Table)
+                    .drop_column(Rooms::RetentionMaxAgeDays)
+                    .drop_column(Rooms::RetentionMaxMessages)
+                    .drop_column(Rooms::LegalHold)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    RetentionMaxAgeDays,
+    RetentionMaxMessages,
+    LegalHold,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportMappings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportMappings::Source)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportMappings::Kind)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportMappings::ExternalId)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportMappings::LocalId)
+                            .string_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportMappings::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk_import_mappings")
+                            .col(ImportMappings::Source)
+                            .col(ImportMappings::Kind)
+                            .col(ImportMappings::ExternalId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportMappings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportMappings {
+    Table,
+    Source,
+    Kind,
+    ExternalId,
+    LocalId,
+    CreatedAt,
+}
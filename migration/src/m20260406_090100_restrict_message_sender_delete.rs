@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Deleting a user used to take their messages with it. Account deletion now reassigns or
+// removes them first, and the database refuses a user delete that would still cascade.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        replace_sender_foreign_key(manager, ForeignKeyAction::Restrict).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        replace_sender_foreign_key(manager, ForeignKeyAction::Cascade).await
+    }
+}
+
+async fn replace_sender_foreign_key(manager: &SchemaManager<'_>, on_delete: ForeignKeyAction) -> Result<(), DbErr> {
+    manager
+        .drop_foreign_key(
+            ForeignKey::drop()
+                .name("fk_messages_sender_id")
+                .table(Messages::Table)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_foreign_key(
+            ForeignKey::create()
+                .name("fk_messages_sender_id")
+                .from(Messages::Table, Messages::SenderId)
+                .to(Users::Table, Users::Id)
+                .on_delete(on_delete)
+                .on_update(ForeignKeyAction::Cascade)
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+enum Messages {
+    Table,
+    SenderId,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
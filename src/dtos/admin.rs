@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// Global role of an account; admins can use the `/admin` API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    User,
+    Admin,
+}
+
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::User => "user",
+            UserRole::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(UserRole::User),
+            "admin" => Some(UserRole::Admin),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+    Active,
+    // Locked out until `suspended_until`, or until lifted when that is empty.
+    Suspended,
+    Banned,
+    // The owner asked to delete it; removed for good once `delete_after` passes unless restored.
+    #[serde(rename = "pending_deletion")]
+    PendingDeletion,
+}
+
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Active => "active",
+            AccountStatus::Suspended => "suspended",
+            AccountStatus::Banned => "banned",
+            AccountStatus::PendingDeletion => "pending_deletion",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "active" => Some(AccountStatus::Active),
+            "suspended" => Some(AccountStatus::Suspended),
+            "banned" => Some(AccountStatus::Banned),
+            "pending_deletion" => Some(AccountStatus::PendingDeletion),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminListUsersQuery {
+    // Matches username or email.
+    pub q: Option<String>,
+    pub role: Option<UserRole>,
+    pub status: Option<AccountStatus>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminUserResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub role: UserRole,
+    pub status: AccountStatus,
+    pub suspended_until: Option<DateTime<Utc>>,
+    pub status_reason: Option<String>,
+    pub email_verified: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserStatusRequest {
+    pub status: AccountStatus,
+    // Only used for suspensions.
+    pub until: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRoleRequest {
+    pub role: UserRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListAuditLogQuery {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditLogResponse {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerStatsResponse {
+    pub users: u64,
+    pub suspended_users: u64,
+    pub banned_users: u64,
+    pub rooms: u64,
+    pub messages: u64,
+    pub messages_last_24h: u64,
+    pub online_users: usize,
+    pub open_connections: usize,
+}
+
+// What happens to the messages of a deleted account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeletedMessagePolicy {
+    // Kept in place, credited to a shared "deleted user" account.
+    #[default]
+    Anonymize,
+    // Removed, except in rooms under legal hold where they are anonymized instead.
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Validate)]
+pub struct AccountDeletionPolicy {
+    // Days an account can still be restored; 0 deletes right away.
+    #[validate(range(max = 365, message = "Grace period must be at most 365 days"))]
+    pub grace_days: u32,
+    pub messages: DeletedMessagePolicy,
+}
+
+impl Default for AccountDeletionPolicy {
+    fn default() -> Self {
+        AccountDeletionPolicy { grace_days: 30, messages: DeletedMessagePolicy::default() }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountDeletionPolicyResponse {
+    pub policy: AccountDeletionPolicy,
+    // `None` while the defaults apply.
+    pub updated_at: Option<DateTime<Utc>>,
+}
@@ -21,20 +21,46 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+// Cancels a pending account deletion and signs in.
+#[derive(Deserialize)]
+pub struct RestoreAccountRequest {
+    pub email: String,
+    pub password: String,
+}
+
+// Either a session `token`, or `mfa_required` with an `mfa_token` to exchange at `/auth/login/mfa`.
 #[derive(Serialize)]
-pub struct AuthResponse {
-    pub token: String,
-    pub user: UserResponse,
+pub struct LoginResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    pub email_verified: bool,
+    pub mfa_required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mfa_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MfaLoginRequest {
+    pub mfa_token: String,
+    // A current TOTP code or one of the recovery codes.
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct TotpCodeRequest {
+    pub code: String,
 }
 
 #[derive(Serialize)]
-pub struct RegisterResponse {
-    pub user: UserResponse,
+pub struct TotpEnrollmentResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
 }
 
 #[derive(Serialize)]
-pub struct LoginResponse {
-    pub token: String,
+pub struct RecoveryCodesResponse {
+    // Shown only once; only their hashes are stored.
+    pub recovery_codes: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -42,5 +68,32 @@ pub struct UserResponse {
     pub id: Uuid,
     pub username: String,
     pub email: String,
+    pub email_verified: bool,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ForgotPasswordRequest {
+    #[validate(email(message = "Email must be a valid email address"))]
+    pub email: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    #[validate(length(min = 3, max = 100, message = "Password must be between 3 and 100 characters"))]
+    pub password: String,
+}
+
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
@@ -1,10 +1,41 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use validator::Validate;
+
+use crate::dtos::{poll::{PollDto, PollUpdatedDto}, room::RoomResponse};
+
+// How `content` should be interpreted by clients.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFormat {
+    #[default]
+    Plain,
+    Markdown,
+}
+
+impl MessageFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageFormat::Plain => "plain",
+            MessageFormat::Markdown => "markdown",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "plain" => Some(MessageFormat::Plain),
+            "markdown" => Some(MessageFormat::Markdown),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SendMessageRequest {
     pub content: String,
+    #[serde(default)]
+    pub format: MessageFormat,
 }
 
 pub type WsInboundMessage = SendMessageRequest;
@@ -15,13 +46,158 @@ pub struct MessageDto {
     pub room_id: Uuid,
     pub sender_id: Uuid,
     pub content: String,
+    pub format: MessageFormat,
+    // Sanitized rendering of markdown content; `None` for plain messages.
+    pub html: Option<String>,
+    // First message of the thread this one replies in.
+    pub thread_id: Option<Uuid>,
+    pub previews: Vec<LinkPreviewDto>,
+    // Present when the message is a poll.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<PollDto>,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkPreviewDto {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub site_name: Option<String>,
+}
+
 pub type MessageResponse = MessageDto;
-pub type WsOutboundMessage = MessageDto;
+
+// Reply to a slash command submitted over REST.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandReplyDto {
+    pub command: String,
+    pub reply: Option<String>,
+    pub message: Option<MessageDto>,
+}
+
+// A plain message keeps the original `MessageDto` shape; commands answer with `CommandReplyDto`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum SendMessageResponse {
+    Message(MessageDto),
+    Command(CommandReplyDto),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemberEventDto {
+    pub room_id: Uuid,
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageDeletedDto {
+    pub id: Uuid,
+    pub room_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomDeletedDto {
+    pub room_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PinnedMessageDto {
+    pub room_id: Uuid,
+    pub message: MessageDto,
+    pub pinned_by: Option<Uuid>,
+    pub pinned_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageUnpinnedDto {
+    pub room_id: Uuid,
+    pub message_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct SaveMessageRequest {
+    pub message_id: Uuid,
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListSavedQuery {
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedMessageDto {
+    pub message: MessageDto,
+    pub note: Option<String>,
+    pub saved_at: DateTime<Utc>,
+}
+
+// Only delivered to sockets belonging to `user_id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EphemeralDto {
+    pub room_id: Uuid,
+    pub user_id: Uuid,
+    pub content: String,
+}
+
+// Events fanned out over `chat_tx`; serialized with a `type` tag next to the payload fields.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum WsOutboundMessage {
+    #[serde(rename = "message.created")]
+    MessageCreated(MessageDto),
+    #[serde(rename = "message.updated")]
+    MessageUpdated(MessageDto),
+    #[serde(rename = "message.deleted")]
+    MessageDeleted(MessageDeletedDto),
+    #[serde(rename = "message.pinned")]
+    MessagePinned(PinnedMessageDto),
+    #[serde(rename = "message.unpinned")]
+    MessageUnpinned(MessageUnpinnedDto),
+    #[serde(rename = "poll.updated")]
+    PollUpdated(PollUpdatedDto),
+    #[serde(rename = "room.updated")]
+    RoomUpdated(RoomResponse),
+    #[serde(rename = "room.deleted")]
+    RoomDeleted(RoomDeletedDto),
+    #[serde(rename = "member.removed")]
+    MemberRemoved(MemberEventDto),
+    #[serde(rename = "ephemeral")]
+    Ephemeral(EphemeralDto),
+}
+
+impl WsOutboundMessage {
+    pub fn room_id(&self) -> Uuid {
+        match self {
+            WsOutboundMessage::MessageCreated(message) => message.room_id,
+            WsOutboundMessage::MessageUpdated(message) => message.room_id,
+            WsOutboundMessage::MessageDeleted(event) => event.room_id,
+            WsOutboundMessage::MessagePinned(pin) => pin.room_id,
+            WsOutboundMessage::MessageUnpinned(event) => event.room_id,
+            WsOutboundMessage::PollUpdated(event) => event.room_id,
+            WsOutboundMessage::RoomUpdated(room) => room.id,
+            WsOutboundMessage::RoomDeleted(event) => event.room_id,
+            WsOutboundMessage::MemberRemoved(event) => event.room_id,
+            WsOutboundMessage::Ephemeral(event) => event.room_id,
+        }
+    }
+
+    // Whether a socket of `user_id` subscribed to `room_id` should receive this event.
+    pub fn is_visible_to(&self, room_id: Uuid, user_id: Uuid) -> bool {
+        if self.room_id() != room_id {
+            return false;
+        }
+        match self {
+            WsOutboundMessage::Ephemeral(event) => event.user_id == user_id,
+            _ => true,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ListMessagesQuery {
     pub limit: Option<u64>,
-}
\ No newline at end of file
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dtos::{chat::MessageDto, room::RoomResponse};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomExportFormat {
+    // One JSON record per line: the room first, then every message oldest to newest.
+    #[default]
+    Ndjson,
+    // A zip holding a single self-contained `index.html`.
+    Html,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoomExportQuery {
+    #[serde(default)]
+    pub format: RoomExportFormat,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedMessage {
+    #[serde(flatten)]
+    pub message: MessageDto,
+    // `None` once the sender's account is gone.
+    pub sender_username: Option<String>,
+}
+
+// Lines of a room NDJSON export, tagged by `type`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum RoomExportRecord {
+    Room {
+        room: RoomResponse,
+        exported_by: Uuid,
+        exported_at: DateTime<Utc>,
+    },
+    Message(ExportedMessage),
+}
+
+// Account data as it appears in `GET /users/me/export`. Credentials and secrets are never included.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedProfile {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub role: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedMembership {
+    pub room_id: Uuid,
+    pub room_name: Option<String>,
+    pub role: String,
+    pub notify_level: String,
+    pub notifications_muted: bool,
+    pub joined_at: DateTime<Utc>,
+}
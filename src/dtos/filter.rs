@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// What a filter does with content it matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    // Replace the match (with asterisks unless a replacement is given) and let the message through.
+    #[default]
+    Mask,
+    Reject,
+}
+
+// Patterns are checked when the config is saved (length, syntax and compiled size).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexRule {
+    pub pattern: String,
+    #[serde(default)]
+    pub action: FilterAction,
+    pub replacement: Option<String>,
+}
+
+// Per-room content rules. Everything is off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct RoomFilterConfig {
+    #[serde(default)]
+    #[validate(length(max = 500, message = "At most 500 blocked words"))]
+    pub blocked_words: Vec<String>,
+    #[serde(default)]
+    pub blocked_words_action: FilterAction,
+    #[serde(default)]
+    #[validate(length(max = 50, message = "At most 50 regex rules"))]
+    pub regex_rules: Vec<RegexRule>,
+    // When non-empty, only links to these hosts (and their subdomains) are allowed.
+    #[serde(default)]
+    #[validate(length(max = 200, message = "At most 200 allowed hosts"))]
+    pub link_allowlist: Vec<String>,
+    #[serde(default)]
+    #[validate(length(max = 200, message = "At most 200 denied hosts"))]
+    pub link_denylist: Vec<String>,
+    // Maximum share of upper-case letters, checked on messages with enough letters to matter.
+    #[validate(range(min = 0.1, max = 1.0, message = "Caps ratio must be between 0.1 and 1"))]
+    pub max_caps_ratio: Option<f32>,
+    // Longer runs of the same character are shortened to this length.
+    #[validate(range(min = 2, max = 100, message = "Repeated character limit must be between 2 and 100"))]
+    pub max_repeated_chars: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomFilterResponse {
+    #[serde(flatten)]
+    pub config: RoomFilterConfig,
+    pub updated_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+// Outcome of one import run. Re-running the same archive only adds what is new,
+// so a second run normally reports everything as matched or skipped.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub users_created: u32,
+    pub users_matched: u32,
+    pub rooms_created: u32,
+    pub rooms_matched: u32,
+    pub memberships_added: u32,
+    pub messages_imported: u32,
+    // Already imported by an earlier run.
+    pub messages_skipped: u32,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+// Something the importer could not map cleanly; the record is either adjusted or left out.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportConflict {
+    pub kind: String,
+    pub external_id: String,
+    pub detail: String,
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::dtos::chat::MessageDto;
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateBotRequest {
+    #[validate(length(min = 3, max = 50, message = "Bot name must be between 3 and 50 characters"))]
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BotResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// The API key is only ever returned here; the server keeps a digest.
+#[derive(Debug, Clone, Serialize)]
+pub struct BotCreatedResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub api_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateIncomingWebhookRequest {
+    pub bot_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IncomingWebhookResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub bot_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IncomingWebhookCreatedResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub bot_id: Uuid,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateOutgoingWebhookRequest {
+    pub bot_id: Uuid,
+    #[validate(url(message = "Webhook url must be a valid URL"))]
+    #[validate(length(max = 1024, message = "Webhook url must be at most 1024 characters"))]
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutgoingWebhookResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub bot_id: Uuid,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutgoingWebhookCreatedResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub bot_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomHooksResponse {
+    pub incoming: Vec<IncomingWebhookResponse>,
+    pub outgoing: Vec<OutgoingWebhookResponse>,
+}
+
+// Body POSTed to an outgoing webhook for each new room message.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutgoingWebhookPayload {
+    pub hook_id: Uuid,
+    pub message: MessageDto,
+}
+
+// Optional synchronous reply from an outgoing webhook, posted back as the bot.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutgoingWebhookReply {
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateBotCommandRequest {
+    #[validate(length(min = 1, max = 32, message = "Command name must be between 1 and 32 characters"))]
+    pub name: String,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters"))]
+    pub description: Option<String>,
+    #[validate(url(message = "Command url must be a valid URL"))]
+    #[validate(length(max = 1024, message = "Command url must be at most 1024 characters"))]
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BotCommandResponse {
+    pub id: Uuid,
+    pub bot_id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BotCommandCreatedResponse {
+    pub id: Uuid,
+    pub bot_id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// Body POSTed to a bot command's url when a member invokes it.
+#[derive(Debug, Clone, Serialize)]
+pub struct BotCommandPayload {
+    pub command: String,
+    pub args: String,
+    pub room_id: Uuid,
+    pub user_id: Uuid,
+}
+
+// `ephemeral` replies go only to the caller; otherwise the bot posts the content in the room.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BotCommandReply {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub ephemeral: bool,
+}
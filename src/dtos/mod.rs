@@ -1,4 +1,14 @@
+pub mod admin;
 pub mod auth;
 pub mod chat;
+pub mod export;
+pub mod filter;
+pub mod import;
+pub mod integration;
+pub mod notification;
+pub mod poll;
+pub mod report;
+pub mod retention;
 pub mod room;
-
+pub mod schedule;
+pub mod user;
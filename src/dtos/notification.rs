@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::dtos::chat::MessageDto;
+
+// Per-room notification level chosen by a member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyLevel {
+    All,
+    Mentions,
+    None,
+}
+
+impl NotifyLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyLevel::All => "all",
+            NotifyLevel::Mentions => "mentions",
+            NotifyLevel::None => "none",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "all" => Some(NotifyLevel::All),
+            "mentions" => Some(NotifyLevel::Mentions),
+            "none" => Some(NotifyLevel::None),
+            _ => None,
+        }
+    }
+
+    // Whether a notification of the given kind passes this level.
+    pub fn allows(&self, kind: NotificationKind) -> bool {
+        match self {
+            NotifyLevel::All => true,
+            NotifyLevel::Mentions => kind != NotificationKind::Message,
+            NotifyLevel::None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Dm,
+    Mention,
+    Message,
+    Reminder,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Dm => "dm",
+            NotificationKind::Mention => "mention",
+            NotificationKind::Message => "message",
+            NotificationKind::Reminder => "reminder",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationStatus {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+impl NotificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationStatus::Pending => "pending",
+            NotificationStatus::Delivered => "delivered",
+            NotificationStatus::Dead => "dead",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateWebhookRequest {
+    #[validate(url(message = "Webhook url must be a valid URL"))]
+    #[validate(length(max = 1024, message = "Webhook url must be at most 1024 characters"))]
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// Returned once on creation so the receiver can verify signatures.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookCreatedResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateNotifySettingsRequest {
+    pub level: Option<NotifyLevel>,
+    pub muted: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotifySettingsResponse {
+    pub room_id: Uuid,
+    pub level: NotifyLevel,
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub message_id: Uuid,
+    pub kind: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListNotificationsQuery {
+    pub limit: Option<u64>,
+}
+
+// Body POSTed to a user's webhook endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub id: Uuid,
+    pub kind: NotificationKind,
+    pub user_id: Uuid,
+    pub message: MessageDto,
+}
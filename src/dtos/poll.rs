@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// The question becomes the message content; options are checked one by one in the service.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreatePollRequest {
+    #[validate(length(min = 1, max = 300, message = "Question must be between 1 and 300 characters"))]
+    pub question: String,
+    #[validate(length(min = 2, max = 10, message = "A poll needs between 2 and 10 options"))]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub multiple_choice: bool,
+    #[serde(default)]
+    pub anonymous: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+// Replaces the caller's previous votes on the poll.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct VotePollRequest {
+    #[validate(length(min = 1, message = "Pick at least one option"))]
+    pub option_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollOptionDto {
+    pub id: Uuid,
+    pub text: String,
+    pub votes: u32,
+    // `None` for anonymous polls.
+    pub voters: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollDto {
+    pub options: Vec<PollOptionDto>,
+    pub multiple_choice: bool,
+    pub anonymous: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub closed: bool,
+    pub total_voters: u32,
+    // Options the requesting user picked; left out of broadcasts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_votes: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollUpdatedDto {
+    pub room_id: Uuid,
+    pub message_id: Uuid,
+    pub poll: PollDto,
+}
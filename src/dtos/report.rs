@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::dtos::chat::MessageFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportStatus {
+    Open,
+    Dismissed,
+    // A moderator acted on the report (deleted the message, muted or banned the author).
+    Actioned,
+}
+
+impl ReportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportStatus::Open => "open",
+            ReportStatus::Dismissed => "dismissed",
+            ReportStatus::Actioned => "actioned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(ReportStatus::Open),
+            "dismissed" => Some(ReportStatus::Dismissed),
+            "actioned" => Some(ReportStatus::Actioned),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportAction {
+    Dismiss,
+    DeleteMessage,
+    MuteAuthor,
+    BanAuthor,
+}
+
+impl ReportAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportAction::Dismiss => "dismiss",
+            ReportAction::DeleteMessage => "delete_message",
+            ReportAction::MuteAuthor => "mute_author",
+            ReportAction::BanAuthor => "ban_author",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "dismiss" => Some(ReportAction::Dismiss),
+            "delete_message" => Some(ReportAction::DeleteMessage),
+            "mute_author" => Some(ReportAction::MuteAuthor),
+            "ban_author" => Some(ReportAction::BanAuthor),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateReportRequest {
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1 and 500 characters"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListReportsQuery {
+    // Defaults to open reports.
+    pub status: Option<ReportStatus>,
+    pub limit: Option<u64>,
+}
+
+// `duration_seconds` and `reason` only apply to the mute and ban actions.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ResolveReportRequest {
+    pub action: ReportAction,
+    #[validate(range(min = 1, max = 31536000, message = "Duration must be between 1 second and 1 year"))]
+    pub duration_seconds: Option<i64>,
+    #[validate(length(max = 255, message = "Reason must be at most 255 characters"))]
+    pub reason: Option<String>,
+}
+
+// The message as it was when the report was filed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedMessageDto {
+    pub id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub content: String,
+    pub format: MessageFormat,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportResponse {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    // None once the reporter's account is deleted.
+    pub reporter_id: Option<Uuid>,
+    pub reason: String,
+    pub message: ReportedMessageDto,
+    pub status: ReportStatus,
+    pub resolution: Option<ReportAction>,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// Messages older than `max_age_days`, or beyond the newest `max_messages` of a room, are pruned.
+// `None` means no limit for the server-wide policy, and "use the server-wide value" for a room.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Validate)]
+pub struct RetentionPolicy {
+    #[validate(range(min = 1, max = 36500, message = "Max age must be between 1 and 36500 days"))]
+    pub max_age_days: Option<u32>,
+    #[validate(range(min = 1, max = 10000000, message = "Max messages must be between 1 and 10000000"))]
+    pub max_messages: Option<u32>,
+}
+
+impl RetentionPolicy {
+    // Room values win; whatever the room leaves unset comes from the server-wide policy.
+    pub fn or(self, fallback: RetentionPolicy) -> RetentionPolicy {
+        RetentionPolicy {
+            max_age_days: self.max_age_days.or(fallback.max_age_days),
+            max_messages: self.max_messages.or(fallback.max_messages),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_age_days.is_none() && self.max_messages.is_none()
+    }
+}
+
+// The room's own settings, as shown on `RoomResponse`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomRetentionDto {
+    pub max_age_days: Option<u32>,
+    pub max_messages: Option<u32>,
+    // Nothing is pruned from a room under legal hold.
+    pub legal_hold: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpdateLegalHoldRequest {
+    pub enabled: bool,
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalRetentionResponse {
+    pub policy: RetentionPolicy,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RetentionPreviewQuery {
+    pub room_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomRetentionPreview {
+    pub room_id: Uuid,
+    pub name: String,
+    pub legal_hold: bool,
+    // Room settings merged with the server-wide policy.
+    pub policy: RetentionPolicy,
+    // Unpinned messages created before this are pruned.
+    pub cutoff: Option<DateTime<Utc>>,
+    pub would_delete: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionPreviewResponse {
+    pub global: RetentionPolicy,
+    pub rooms: Vec<RoomRetentionPreview>,
+    pub total: u64,
+}
@@ -3,10 +3,18 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::dtos::retention::RoomRetentionDto;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct RoomResponse {
     pub id: Uuid,
     pub name: String,
+    pub topic: Option<String>,
+    // Minimum seconds between two messages of the same member; 0 disables slow mode.
+    pub slow_mode_seconds: u32,
+    pub retention: RoomRetentionDto,
+    // A direct conversation between two people; it never takes a third member.
+    pub is_direct: bool,
     pub created_at: DateTime<Utc>,
 }
 
@@ -14,13 +22,8 @@ pub struct RoomResponse {
 pub struct CreateRoomRequest {
     #[validate(length(min = 1, max = 100, message = "Room name must be between 1 and 100 characters"))]
     pub name: String,
-}
-
-#[derive(Debug, Clone, Serialize)]
-pub struct RoomMemberResponse {
-    pub room_id: Uuid,
-    pub user_id: Uuid,
-    pub joined_at: DateTime<Utc>,
+    #[serde(default)]
+    pub is_direct: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -35,10 +38,77 @@ pub struct UserInfo {
     pub email: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomMemberInfo {
+    #[serde(flatten)]
+    pub user: UserInfo,
+    pub role: RoomRole,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct RoomDetailResponse {
     pub id: Uuid,
     pub name: String,
+    pub topic: Option<String>,
     pub created_at: DateTime<Utc>,
-    pub members: Vec<UserInfo>,
-}
\ No newline at end of file
+    pub members: Vec<RoomMemberInfo>,
+}
+
+// Role of a member inside one room. Owners and moderators can kick, ban and mute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomRole {
+    Member,
+    Moderator,
+    Owner,
+}
+
+impl RoomRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoomRole::Member => "member",
+            RoomRole::Moderator => "moderator",
+            RoomRole::Owner => "owner",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "member" => Some(RoomRole::Member),
+            "moderator" => Some(RoomRole::Moderator),
+            "owner" => Some(RoomRole::Owner),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateMemberRoleRequest {
+    pub role: RoomRole,
+}
+
+// Body of both ban and mute requests; without a duration the sanction lasts until lifted.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct RoomSanctionRequest {
+    pub user_id: Uuid,
+    #[validate(range(min = 1, max = 31536000, message = "Duration must be between 1 second and 1 year"))]
+    pub duration_seconds: Option<i64>,
+    #[validate(length(max = 255, message = "Reason must be at most 255 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomSanctionResponse {
+    pub room_id: Uuid,
+    pub user_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpdateSlowModeRequest {
+    #[validate(range(max = 21600, message = "Slow mode can be at most 6 hours"))]
+    pub seconds: u32,
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::dtos::chat::MessageFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobKind {
+    // Post `content` to the room at `run_at`.
+    Message,
+    // Remind the user of an existing message at `run_at`.
+    Reminder,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::Message => "message",
+            JobKind::Reminder => "reminder",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "message" => Some(JobKind::Message),
+            "reminder" => Some(JobKind::Reminder),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,
+    // Claimed by a scheduler instance until `locked_until`.
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Done => "done",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(JobStatus::Pending),
+            "running" => Some(JobStatus::Running),
+            "done" => Some(JobStatus::Done),
+            "failed" => Some(JobStatus::Failed),
+            "cancelled" => Some(JobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ScheduleMessageRequest {
+    #[validate(length(min = 1, max = 1024, message = "Message content must be between 1 and 1024 characters"))]
+    pub content: String,
+    #[serde(default)]
+    pub format: MessageFormat,
+    pub send_at: DateTime<Utc>,
+}
+
+// Either an absolute `remind_at` or a delay such as 7200 seconds ("remind me in 2h").
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct CreateReminderRequest {
+    pub remind_at: Option<DateTime<Utc>>,
+    #[validate(range(min = 60, max = 31536000, message = "Delay must be between 1 minute and 1 year"))]
+    pub in_seconds: Option<i64>,
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct UpdateScheduledJobRequest {
+    pub run_at: Option<DateTime<Utc>>,
+    // Only for scheduled messages.
+    #[validate(length(min = 1, max = 1024, message = "Message content must be between 1 and 1024 characters"))]
+    pub content: Option<String>,
+    // Only for reminders.
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListScheduledJobsQuery {
+    pub kind: Option<JobKind>,
+    // Defaults to pending jobs.
+    pub status: Option<JobStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledJobResponse {
+    pub id: Uuid,
+    pub kind: JobKind,
+    pub room_id: Uuid,
+    pub message_id: Option<Uuid>,
+    pub content: Option<String>,
+    pub format: Option<MessageFormat>,
+    pub note: Option<String>,
+    pub run_at: DateTime<Utc>,
+    pub status: JobStatus,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
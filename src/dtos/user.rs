@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dtos::room::UserInfo;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockUserRequest {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockedUserResponse {
+    #[serde(flatten)]
+    pub user: UserInfo,
+    pub blocked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeleteAccountRequest {
+    // Re-entered to confirm; SSO-only accounts set one through a password reset first.
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountDeletionResponse {
+    // Until then, signing in through `/auth/restore` cancels the deletion.
+    pub delete_after: DateTime<Utc>,
+}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "import_mappings")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub source: String,
+
+    #[sea_orm(primary_key)]
+    pub kind: String,
+
+    #[sea_orm(primary_key)]
+    pub external_id: String,
+
+    pub local_id: String,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
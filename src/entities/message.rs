@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "messages")]
 pub struct Model {
-    #[sea_orm(primary_key)]
+    #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
     
     pub room_id: String,
@@ -12,6 +12,12 @@ pub struct Model {
     pub sender_id: String,
     
     pub content: String,
+
+    pub format: String,
+
+    pub rendered_html: Option<String>,
+
+    pub thread_id: Option<String>,
     
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
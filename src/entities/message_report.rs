@@ -0,0 +1,41 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "message_reports")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+
+    // Not a foreign key: reports outlive their room.
+    pub room_id: String,
+
+    pub message_id: Option<String>,
+
+    pub reporter_id: Option<String>,
+
+    pub author_id: Option<String>,
+
+    pub reason: String,
+
+    pub content_snapshot: String,
+
+    pub format_snapshot: String,
+
+    pub message_created_at: chrono::DateTime<chrono::Utc>,
+
+    pub status: String,
+
+    pub resolution: Option<String>,
+
+    pub resolved_by: Option<String>,
+
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
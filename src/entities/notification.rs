@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "notifications")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+
+    pub user_id: String,
+
+    pub endpoint_id: String,
+
+    pub room_id: String,
+
+    pub message_id: String,
+
+    pub kind: String,
+
+    pub payload: String,
+
+    pub status: String,
+
+    pub attempts: i32,
+
+    pub next_attempt_at: chrono::DateTime<chrono::Utc>,
+
+    pub last_error: Option<String>,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    pub delivered_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "poll_votes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub poll_id: String,
+
+    #[sea_orm(primary_key)]
+    pub user_id: String,
+
+    #[sea_orm(primary_key)]
+    pub slot: i32,
+
+    pub option_id: String,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,4 +1,33 @@
+#![allow(unused_imports)]
+
 pub use super::user::{Entity as UserEntity, Model as UserModel, ActiveModel as UserActiveModel};
 pub use super::room::{Entity as RoomEntity, Model as RoomModel, ActiveModel as RoomActiveModel};
 pub use super::message::{Entity as MessageEntity, Model as MessageModel, ActiveModel as MessageActiveModel};
-pub use super::room_member::{Entity as RoomMemberEntity, Model as RoomMemberModel, ActiveModel as RoomMemberActiveModel};
\ No newline at end of file
+pub use super::room_member::{Entity as RoomMemberEntity, Model as RoomMemberModel, ActiveModel as RoomMemberActiveModel};
+pub use super::webhook_endpoint::{Entity as WebhookEndpointEntity, Model as WebhookEndpointModel, ActiveModel as WebhookEndpointActiveModel};
+pub use super::notification::{Entity as NotificationEntity, Model as NotificationModel, ActiveModel as NotificationActiveModel};
+pub use super::bot::{Entity as BotEntity, Model as BotModel, ActiveModel as BotActiveModel};
+pub use super::incoming_webhook::{Entity as IncomingWebhookEntity, Model as IncomingWebhookModel, ActiveModel as IncomingWebhookActiveModel};
+pub use super::outgoing_webhook::{Entity as OutgoingWebhookEntity, Model as OutgoingWebhookModel, ActiveModel as OutgoingWebhookActiveModel};
+pub use super::bot_command::{Entity as BotCommandEntity, Model as BotCommandModel, ActiveModel as BotCommandActiveModel};
+pub use super::link_preview::{Entity as LinkPreviewEntity, Model as LinkPreviewModel, ActiveModel as LinkPreviewActiveModel};
+pub use super::message_link_preview::{Entity as MessageLinkPreviewEntity, Model as MessageLinkPreviewModel, ActiveModel as MessageLinkPreviewActiveModel};
+pub use super::auth_token::{Entity as AuthTokenEntity, Model as AuthTokenModel, ActiveModel as AuthTokenActiveModel};
+pub use super::user_totp::{Entity as UserTotpEntity, Model as UserTotpModel, ActiveModel as UserTotpActiveModel};
+pub use super::recovery_code::{Entity as RecoveryCodeEntity, Model as RecoveryCodeModel, ActiveModel as RecoveryCodeActiveModel};
+pub use super::user_identity::{Entity as UserIdentityEntity, Model as UserIdentityModel, ActiveModel as UserIdentityActiveModel};
+pub use super::oidc_login_state::{Entity as OidcLoginStateEntity, Model as OidcLoginStateModel, ActiveModel as OidcLoginStateActiveModel};
+pub use super::audit_log::{Entity as AuditLogEntity, Model as AuditLogModel, ActiveModel as AuditLogActiveModel};
+pub use super::room_ban::{Entity as RoomBanEntity, Model as RoomBanModel, ActiveModel as RoomBanActiveModel};
+pub use super::room_mute::{Entity as RoomMuteEntity, Model as RoomMuteModel, ActiveModel as RoomMuteActiveModel};
+pub use super::message_report::{Entity as MessageReportEntity, Model as MessageReportModel, ActiveModel as MessageReportActiveModel};
+pub use super::room_filter_setting::{Entity as RoomFilterSettingEntity, Model as RoomFilterSettingModel, ActiveModel as RoomFilterSettingActiveModel};
+pub use super::user_block::{Entity as UserBlockEntity, Model as UserBlockModel, ActiveModel as UserBlockActiveModel};
+pub use super::room_pin::{Entity as RoomPinEntity, Model as RoomPinModel, ActiveModel as RoomPinActiveModel};
+pub use super::saved_message::{Entity as SavedMessageEntity, Model as SavedMessageModel, ActiveModel as SavedMessageActiveModel};
+pub use super::scheduled_job::{Entity as ScheduledJobEntity, Model as ScheduledJobModel, ActiveModel as ScheduledJobActiveModel};
+pub use super::poll::{Entity as PollEntity, Model as PollModel, ActiveModel as PollActiveModel};
+pub use super::poll_option::{Entity as PollOptionEntity, Model as PollOptionModel, ActiveModel as PollOptionActiveModel};
+pub use super::poll_vote::{Entity as PollVoteEntity, Model as PollVoteModel, ActiveModel as PollVoteActiveModel};
+pub use super::server_setting::{Entity as ServerSettingEntity, Model as ServerSettingModel, ActiveModel as ServerSettingActiveModel};
+pub use super::import_mapping::{Entity as ImportMappingEntity, Model as ImportMappingModel, ActiveModel as ImportMappingActiveModel};
\ No newline at end of file
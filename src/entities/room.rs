@@ -4,10 +4,22 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "rooms")]
 pub struct Model {
-    #[sea_orm(primary_key)]
+    #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
     
     pub name: String,
+
+    pub topic: Option<String>,
+
+    pub slow_mode_seconds: i32,
+
+    pub retention_max_age_days: Option<i32>,
+
+    pub retention_max_messages: Option<i32>,
+
+    pub legal_hold: bool,
+
+    pub is_direct: bool,
     
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
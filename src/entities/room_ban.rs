@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "room_bans")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub room_id: String,
+
+    #[sea_orm(primary_key)]
+    pub user_id: String,
+
+    pub created_by: Option<String>,
+
+    pub reason: Option<String>,
+
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
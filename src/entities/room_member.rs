@@ -11,6 +11,12 @@ pub struct Model {
     pub user_id: String,
     
     pub joined_at: chrono::DateTime<chrono::Utc>,
+
+    pub notify_level: String,
+
+    pub notifications_muted: bool,
+
+    pub role: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "scheduled_jobs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+
+    pub kind: String,
+
+    pub user_id: String,
+
+    pub room_id: String,
+
+    pub message_id: Option<String>,
+
+    pub content: Option<String>,
+
+    pub format: Option<String>,
+
+    pub note: Option<String>,
+
+    pub run_at: chrono::DateTime<chrono::Utc>,
+
+    pub status: String,
+
+    pub attempts: i32,
+
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub last_error: Option<String>,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "users")]
 pub struct Model {
-    #[sea_orm(primary_key)]
+    #[sea_orm(primary_key, auto_increment = false)]
     pub id: String, 
     
     pub username: String,
@@ -15,6 +15,18 @@ pub struct Model {
     pub password: String,
     
     pub created_at: chrono::DateTime<chrono::Utc>,
+
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub role: String,
+
+    pub status: String,
+
+    pub suspended_until: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub status_reason: Option<String>,
+
+    pub delete_after: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
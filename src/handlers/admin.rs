@@ -0,0 +1,222 @@
+use axum::{
+  extract::{Path, Query, State},
+  http::HeaderMap,
+  Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+  database::{AppState, SharedState},
+  dtos::{
+    admin::{
+      AccountDeletionPolicy, AccountDeletionPolicyResponse, AdminListUsersQuery, AdminUserResponse, AuditLogResponse,
+      ListAuditLogQuery, ServerStatsResponse, UpdateUserRoleRequest, UpdateUserStatusRequest,
+    },
+    report::{ListReportsQuery, ReportResponse},
+    retention::{GlobalRetentionResponse, RetentionPolicy, RetentionPreviewQuery, RetentionPreviewResponse, UpdateLegalHoldRequest},
+    room::RoomResponse,
+  },
+  response::{ApiError, ApiResponse},
+  services::{account, admin, report, retention},
+};
+
+fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
+  let auth_header = headers
+      .get("authorization")
+      .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".into()))?
+      .to_str()
+      .map_err(|_| ApiError::Unauthorized("Invalid authorization header".into()))?;
+
+  if !auth_header.starts_with("Bearer ") {
+      return Err(ApiError::Unauthorized("Invalid authorization format".into()));
+  }
+
+  Ok(&auth_header[7..])
+}
+
+// Every `/admin` route requires a valid token of an active administrator.
+async fn authorize_admin(state: &AppState, headers: &HeaderMap) -> Result<Uuid, ApiError> {
+  let token = extract_token(headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  admin::ensure_admin(state, user_id).await?;
+  Ok(user_id)
+}
+
+pub async fn list_users(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Query(params): Query<AdminListUsersQuery>,
+) -> Result<ApiResponse<Vec<AdminUserResponse>>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let users = admin::list_users(state.as_ref(), params).await?;
+  Ok(ApiResponse::success(users))
+}
+
+pub async fn get_user(
+  State(state): State<SharedState>,
+  Path(user_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<AdminUserResponse>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let user = admin::get_user(state.as_ref(), user_id).await?;
+  Ok(ApiResponse::success(user))
+}
+
+pub async fn update_user_status(
+  State(state): State<SharedState>,
+  Path(user_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<UpdateUserStatusRequest>,
+) -> Result<ApiResponse<AdminUserResponse>, ApiError> {
+  let admin_id = authorize_admin(state.as_ref(), &headers).await?;
+
+  let user = admin::update_user_status(state.as_ref(), admin_id, user_id, payload).await?;
+  Ok(ApiResponse::success(user))
+}
+
+pub async fn update_user_role(
+  State(state): State<SharedState>,
+  Path(user_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<UpdateUserRoleRequest>,
+) -> Result<ApiResponse<AdminUserResponse>, ApiError> {
+  let admin_id = authorize_admin(state.as_ref(), &headers).await?;
+
+  let user = admin::update_user_role(state.as_ref(), admin_id, user_id, payload).await?;
+  Ok(ApiResponse::success(user))
+}
+
+pub async fn delete_room(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let admin_id = authorize_admin(state.as_ref(), &headers).await?;
+
+  admin::delete_room(state.as_ref(), admin_id, room_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn delete_message(
+  State(state): State<SharedState>,
+  Path(message_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let admin_id = authorize_admin(state.as_ref(), &headers).await?;
+
+  admin::delete_message(state.as_ref(), admin_id, message_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn server_stats(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<ServerStatsResponse>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let stats = admin::server_stats(state.as_ref()).await?;
+  Ok(ApiResponse::success(stats))
+}
+
+pub async fn list_audit_log(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Query(params): Query<ListAuditLogQuery>,
+) -> Result<ApiResponse<Vec<AuditLogResponse>>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let entries = admin::list_audit_log(state.as_ref(), params).await?;
+  Ok(ApiResponse::success(entries))
+}
+
+pub async fn list_reports(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Query(params): Query<ListReportsQuery>,
+) -> Result<ApiResponse<Vec<ReportResponse>>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let reports = report::list_all_reports(state.as_ref(), params).await?;
+  Ok(ApiResponse::success(reports))
+}
+
+pub async fn get_retention(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<GlobalRetentionResponse>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let retention = retention::get_global_policy(state.as_ref()).await?;
+  Ok(ApiResponse::success(retention))
+}
+
+pub async fn update_retention(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Json(payload): Json<RetentionPolicy>,
+) -> Result<ApiResponse<GlobalRetentionResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let admin_id = authorize_admin(state.as_ref(), &headers).await?;
+
+  let retention = retention::update_global_policy(state.as_ref(), admin_id, payload).await?;
+  Ok(ApiResponse::success(retention))
+}
+
+pub async fn get_account_deletion(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<AccountDeletionPolicyResponse>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let policy = account::get_deletion_policy(state.as_ref()).await?;
+  Ok(ApiResponse::success(policy))
+}
+
+pub async fn update_account_deletion(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Json(payload): Json<AccountDeletionPolicy>,
+) -> Result<ApiResponse<AccountDeletionPolicyResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let admin_id = authorize_admin(state.as_ref(), &headers).await?;
+
+  let policy = account::update_deletion_policy(state.as_ref(), admin_id, payload).await?;
+  Ok(ApiResponse::success(policy))
+}
+
+pub async fn preview_retention(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Query(params): Query<RetentionPreviewQuery>,
+) -> Result<ApiResponse<RetentionPreviewResponse>, ApiError> {
+  authorize_admin(state.as_ref(), &headers).await?;
+
+  let preview = retention::preview(state.as_ref(), params).await?;
+  Ok(ApiResponse::success(preview))
+}
+
+pub async fn update_legal_hold(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<UpdateLegalHoldRequest>,
+) -> Result<ApiResponse<RoomResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let admin_id = authorize_admin(state.as_ref(), &headers).await?;
+
+  let room = retention::update_legal_hold(state.as_ref(), admin_id, room_id, payload).await?;
+  Ok(ApiResponse::success(room))
+}
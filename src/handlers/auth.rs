@@ -1,12 +1,29 @@
-use axum::{extract::State, Json};
+use axum::{extract::{Query, State}, http::HeaderMap, response::Redirect, Json};
 use validator::Validate;
 use crate::{
     database::SharedState,
-    dtos::auth::{LoginRequest, RegisterRequest, AuthResponse, LoginResponse, UserResponse},
+    dtos::auth::{
+        ForgotPasswordRequest, LoginRequest, LoginResponse, MfaLoginRequest, OidcCallbackQuery, RecoveryCodesResponse, RegisterRequest,
+        ResetPasswordRequest, RestoreAccountRequest, TotpCodeRequest, TotpEnrollmentResponse, UserResponse, VerifyEmailRequest,
+    },
     response::{ApiError, ApiResponse},
-    services::auth,
+    services::{account, auth, mfa, oidc},
 };
 
+fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
+    let auth_header = headers
+        .get("authorization")
+        .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".into()))?
+        .to_str()
+        .map_err(|_| ApiError::Unauthorized("Invalid authorization header".into()))?;
+
+    if !auth_header.starts_with("Bearer ") {
+        return Err(ApiError::Unauthorized("Invalid authorization format".into()));
+    }
+
+    Ok(&auth_header[7..])
+}
+
 pub async fn register(
     State(state): State<SharedState>,
     Json(payload): Json<RegisterRequest>,
@@ -23,4 +40,123 @@ pub async fn login(
     payload.validate()
         .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
     auth::login(&state, payload).await
-}
\ No newline at end of file
+}
+
+pub async fn restore_account(
+    State(state): State<SharedState>,
+    Json(payload): Json<RestoreAccountRequest>,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    let login = account::restore(&state, payload).await?;
+    Ok(ApiResponse::success(login))
+}
+
+pub async fn verify_email(
+    State(state): State<SharedState>,
+    Json(payload): Json<VerifyEmailRequest>,
+) -> Result<ApiResponse<()>, ApiError> {
+    auth::verify_email(&state, payload).await
+}
+
+pub async fn resend_verification(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+    let token = extract_token(&headers)?;
+    let user_id = state
+        .jwt
+        .validate(token)
+        .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+    auth::resend_verification(&state, user_id).await
+}
+
+pub async fn forgot_password(
+    State(state): State<SharedState>,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<ApiResponse<()>, ApiError> {
+    payload.validate()
+        .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+    auth::forgot_password(&state, payload).await
+}
+
+pub async fn reset_password(
+    State(state): State<SharedState>,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<ApiResponse<()>, ApiError> {
+    payload.validate()
+        .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+    auth::reset_password(&state, payload).await
+}
+
+pub async fn login_mfa(
+    State(state): State<SharedState>,
+    Json(payload): Json<MfaLoginRequest>,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    let response = mfa::complete_login(&state, payload).await?;
+    Ok(ApiResponse::success(response))
+}
+
+pub async fn enroll_totp(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<ApiResponse<TotpEnrollmentResponse>, ApiError> {
+    let token = extract_token(&headers)?;
+    let user_id = state
+        .jwt
+        .validate(token)
+        .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+    let enrollment = mfa::enroll(&state, user_id).await?;
+    Ok(ApiResponse::success(enrollment))
+}
+
+pub async fn confirm_totp(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<ApiResponse<RecoveryCodesResponse>, ApiError> {
+    let token = extract_token(&headers)?;
+    let user_id = state
+        .jwt
+        .validate(token)
+        .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+    let codes = mfa::confirm(&state, user_id, payload).await?;
+    Ok(ApiResponse::success(codes))
+}
+
+pub async fn disable_totp(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<ApiResponse<()>, ApiError> {
+    let token = extract_token(&headers)?;
+    let user_id = state
+        .jwt
+        .validate(token)
+        .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+    mfa::disable(&state, user_id, payload).await?;
+    Ok(ApiResponse::success(()))
+}
+
+pub async fn oidc_login(
+    State(state): State<SharedState>,
+) -> Result<Redirect, ApiError> {
+    let url = oidc::start_login(&state).await?;
+    Ok(Redirect::to(&url))
+}
+
+pub async fn oidc_callback(
+    State(state): State<SharedState>,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<ApiResponse<LoginResponse>, ApiError> {
+    let response = oidc::complete_login(&state, query).await?;
+    Ok(ApiResponse::success(response))
+}
+
+pub async fn jwks(
+    State(state): State<SharedState>,
+) -> Json<serde_json::Value> {
+    Json(state.jwt.jwks().clone())
+}
@@ -1,29 +1,42 @@
 use axum::{
   extract::{Path, Query, State},
-  http::{HeaderMap, StatusCode},
+  http::HeaderMap,
   Json,
 };
 use uuid::Uuid;
+use validator::Validate;
 
 use crate::{
-  database::SharedState,
-  dtos::chat::{ListMessagesQuery, MessageResponse, SendMessageRequest},
+  database::{AppState, SharedState},
+  dtos::{
+    chat::{ListMessagesQuery, MessageResponse, PinnedMessageDto, SendMessageRequest, SendMessageResponse},
+    poll::{CreatePollRequest, PollDto, VotePollRequest},
+    schedule::{CreateReminderRequest, ScheduleMessageRequest, ScheduledJobResponse},
+  },
   response::{ApiError, ApiResponse},
-  services::chat,
+  services::{chat, command, integration, pin, poll, schedule},
 };
 
-fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
+// Users authenticate with "Bearer <jwt>", bots with "Bot <api key>".
+async fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<Uuid, ApiError> {
   let auth_header = headers
       .get("authorization")
       .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".into()))?
       .to_str()
       .map_err(|_| ApiError::Unauthorized("Invalid authorization header".into()))?;
 
-  if !auth_header.starts_with("Bearer ") {
-      return Err(ApiError::Unauthorized("Invalid authorization format".into()));
+  if let Some(api_key) = auth_header.strip_prefix("Bot ") {
+      return integration::authenticate_api_key(state, api_key).await;
   }
 
-  Ok(&auth_header[7..]) // Skip "Bearer "
+  let token = auth_header
+      .strip_prefix("Bearer ")
+      .ok_or_else(|| ApiError::Unauthorized("Invalid authorization format".into()))?;
+
+  state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))
 }
 
 pub async fn list_messages(
@@ -32,11 +45,7 @@ pub async fn list_messages(
   headers: HeaderMap,
   Query(params): Query<ListMessagesQuery>,
 ) -> Result<ApiResponse<Vec<MessageResponse>>, ApiError> {
-  let token = extract_token(&headers)?;
-  let user_id = state
-      .jwt
-      .validate(token)
-      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+  let user_id = authenticate(state.as_ref(), &headers).await?;
 
   let messages = chat::list_messages(state.as_ref(), room_id, user_id, params).await?;
   Ok(ApiResponse::success(messages))
@@ -47,17 +56,117 @@ pub async fn send_message(
   Path(room_id): Path<Uuid>,
   headers: HeaderMap,
   Json(payload): Json<SendMessageRequest>,
-) -> Result<ApiResponse<MessageResponse>, ApiError> {
-  let token = extract_token(&headers)?;
-  let user_id = state
-      .jwt
-      .validate(token)
-      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+) -> Result<ApiResponse<SendMessageResponse>, ApiError> {
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  let outcome = command::submit(state.as_ref(), room_id, user_id, payload).await?;
+  let response = outcome.to_response();
+
+  command::publish(state.as_ref(), room_id, user_id, outcome);
+
+  Ok(ApiResponse::success(response))
+}
+
+pub async fn list_pins(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<Vec<PinnedMessageDto>>, ApiError> {
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  let pins = pin::list_pins(state.as_ref(), room_id, user_id).await?;
+  Ok(ApiResponse::success(pins))
+}
+
+pub async fn pin_message(
+  State(state): State<SharedState>,
+  Path((room_id, message_id)): Path<(Uuid, Uuid)>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<PinnedMessageDto>, ApiError> {
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  let pinned = pin::pin_message(state.as_ref(), room_id, message_id, user_id).await?;
+  Ok(ApiResponse::success(pinned))
+}
+
+pub async fn unpin_message(
+  State(state): State<SharedState>,
+  Path((room_id, message_id)): Path<(Uuid, Uuid)>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  pin::unpin_message(state.as_ref(), room_id, message_id, user_id).await?;
+  Ok(ApiResponse::success(()))
+}
 
-  let message =
-      chat::send_message(state.as_ref(), room_id, user_id, payload.content).await?;
+pub async fn schedule_message(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<ScheduleMessageRequest>,
+) -> Result<ApiResponse<ScheduledJobResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  let job = schedule::schedule_message(state.as_ref(), room_id, user_id, payload).await?;
+  Ok(ApiResponse::success(job))
+}
+
+pub async fn create_reminder(
+  State(state): State<SharedState>,
+  Path(message_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<CreateReminderRequest>,
+) -> Result<ApiResponse<ScheduledJobResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  let job = schedule::create_reminder(state.as_ref(), message_id, user_id, payload).await?;
+  Ok(ApiResponse::success(job))
+}
+
+pub async fn create_poll(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<CreatePollRequest>,
+) -> Result<ApiResponse<MessageResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
 
-  let _ = state.chat_tx.send(message.clone());
+  let user_id = authenticate(state.as_ref(), &headers).await?;
 
+  let message = poll::create_poll(state.as_ref(), room_id, user_id, payload).await?;
   Ok(ApiResponse::success(message))
 }
+
+pub async fn vote_poll(
+  State(state): State<SharedState>,
+  Path(message_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<VotePollRequest>,
+) -> Result<ApiResponse<PollDto>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  let poll = poll::vote(state.as_ref(), message_id, user_id, payload).await?;
+  Ok(ApiResponse::success(poll))
+}
+
+pub async fn retract_poll_vote(
+  State(state): State<SharedState>,
+  Path(message_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<PollDto>, ApiError> {
+  let user_id = authenticate(state.as_ref(), &headers).await?;
+
+  let poll = poll::retract_vote(state.as_ref(), message_id, user_id).await?;
+  Ok(ApiResponse::success(poll))
+}
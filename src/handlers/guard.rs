@@ -0,0 +1,47 @@
+use axum::{
+  extract::{Request, State},
+  middleware::Next,
+  response::Response,
+};
+
+use crate::{
+  database::SharedState,
+  repositories::user as user_repo,
+  response::ApiError,
+  services::admin,
+};
+
+// Session tokens are stateless, so a ban only takes effect if every request re-checks the account.
+// Invalid or missing tokens pass through; the handlers reject those themselves.
+pub async fn reject_inactive_accounts(
+  State(state): State<SharedState>,
+  request: Request,
+  next: Next,
+) -> Result<Response, ApiError> {
+  let token = request
+      .headers()
+      .get("authorization")
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.strip_prefix("Bearer "));
+  // Counted here, once, rather than in every handler that rejects the token.
+  let user_id = token.and_then(|token| match state.jwt.validate(token) {
+      Ok(user_id) => Some(user_id),
+      Err(_) => {
+        state.metrics.auth_failure("invalid_token");
+        None
+      }
+  });
+
+  if let Some(user_id) = user_id {
+      let Some(user) = user_repo::find_by_id(&state.db, &user_id.to_string()).await? else {
+          state.metrics.auth_failure("deleted_account");
+          return Err(ApiError::Unauthorized("Account no longer exists".into()));
+      };
+      if let Err(e) = admin::ensure_active(&user) {
+          state.metrics.auth_failure("inactive_account");
+          return Err(e);
+      }
+  }
+
+  Ok(next.run(request).await)
+}
@@ -0,0 +1,210 @@
+use axum::{
+  extract::{Path, State},
+  http::HeaderMap,
+  Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+  database::SharedState,
+  dtos::{
+    chat::{MessageResponse, SendMessageRequest, WsOutboundMessage},
+    integration::{
+      BotCommandCreatedResponse, BotCommandResponse, BotCreatedResponse, BotResponse,
+      CreateBotCommandRequest, CreateBotRequest, CreateIncomingWebhookRequest,
+      CreateOutgoingWebhookRequest, IncomingWebhookCreatedResponse, OutgoingWebhookCreatedResponse,
+      RoomHooksResponse,
+    },
+  },
+  response::{ApiError, ApiResponse},
+  services::integration,
+};
+
+fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
+  let auth_header = headers
+      .get("authorization")
+      .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".into()))?
+      .to_str()
+      .map_err(|_| ApiError::Unauthorized("Invalid authorization header".into()))?;
+
+  if !auth_header.starts_with("Bearer ") {
+      return Err(ApiError::Unauthorized("Invalid authorization format".into()));
+  }
+
+  Ok(&auth_header[7..])
+}
+
+pub async fn create_bot(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Json(payload): Json<CreateBotRequest>,
+) -> Result<ApiResponse<BotCreatedResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let bot = integration::create_bot(state.as_ref(), user_id, payload).await?;
+  Ok(ApiResponse::success(bot))
+}
+
+pub async fn list_bots(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<Vec<BotResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let bots = integration::list_bots(state.as_ref(), user_id).await?;
+  Ok(ApiResponse::success(bots))
+}
+
+pub async fn delete_bot(
+  State(state): State<SharedState>,
+  Path(bot_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  integration::delete_bot(state.as_ref(), user_id, bot_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn create_bot_command(
+  State(state): State<SharedState>,
+  Path(bot_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<CreateBotCommandRequest>,
+) -> Result<ApiResponse<BotCommandCreatedResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let command = integration::create_bot_command(state.as_ref(), user_id, bot_id, payload).await?;
+  Ok(ApiResponse::success(command))
+}
+
+pub async fn list_bot_commands(
+  State(state): State<SharedState>,
+  Path(bot_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<Vec<BotCommandResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let commands = integration::list_bot_commands(state.as_ref(), user_id, bot_id).await?;
+  Ok(ApiResponse::success(commands))
+}
+
+pub async fn delete_bot_command(
+  State(state): State<SharedState>,
+  Path((bot_id, command_id)): Path<(Uuid, Uuid)>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  integration::delete_bot_command(state.as_ref(), user_id, bot_id, command_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn list_room_hooks(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<RoomHooksResponse>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let hooks = integration::list_room_hooks(state.as_ref(), room_id, user_id).await?;
+  Ok(ApiResponse::success(hooks))
+}
+
+pub async fn create_incoming_webhook(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<CreateIncomingWebhookRequest>,
+) -> Result<ApiResponse<IncomingWebhookCreatedResponse>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let hook = integration::create_incoming_webhook(state.as_ref(), room_id, user_id, payload).await?;
+  Ok(ApiResponse::success(hook))
+}
+
+pub async fn create_outgoing_webhook(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<CreateOutgoingWebhookRequest>,
+) -> Result<ApiResponse<OutgoingWebhookCreatedResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let hook = integration::create_outgoing_webhook(state.as_ref(), room_id, user_id, payload).await?;
+  Ok(ApiResponse::success(hook))
+}
+
+pub async fn delete_room_hook(
+  State(state): State<SharedState>,
+  Path((room_id, hook_id)): Path<(Uuid, Uuid)>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  integration::delete_room_hook(state.as_ref(), room_id, hook_id, user_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+// Public endpoint: the token in the path is the only credential.
+pub async fn post_incoming(
+  State(state): State<SharedState>,
+  Path(token): Path<String>,
+  Json(payload): Json<SendMessageRequest>,
+) -> Result<ApiResponse<MessageResponse>, ApiError> {
+  let message = integration::post_incoming(state.as_ref(), &token, payload).await?;
+
+  let _ = state.chat_tx.send(WsOutboundMessage::MessageCreated(message.clone()));
+
+  Ok(ApiResponse::success(message))
+}
@@ -0,0 +1,13 @@
+use axum::{
+  extract::State,
+  http::header,
+  response::IntoResponse,
+};
+
+use crate::database::SharedState;
+
+pub async fn scrape(
+  State(state): State<SharedState>,
+) -> impl IntoResponse {
+  ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], state.metrics.render(&state))
+}
@@ -1,5 +1,11 @@
+pub mod admin;
 pub mod auth;
 pub mod chat;
+pub mod guard;
+pub mod integration;
+pub mod metrics;
+pub mod notification;
+pub mod report;
 pub mod ws;
 pub mod room;
-pub mod user;
\ No newline at end of file
+pub mod user;
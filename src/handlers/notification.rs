@@ -0,0 +1,109 @@
+use axum::{
+  extract::{Path, Query, State},
+  http::HeaderMap,
+  Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+  database::SharedState,
+  dtos::notification::{
+    CreateWebhookRequest, ListNotificationsQuery, NotificationResponse, NotifySettingsResponse,
+    UpdateNotifySettingsRequest, WebhookCreatedResponse, WebhookResponse,
+  },
+  response::{ApiError, ApiResponse},
+  services::notification,
+};
+
+fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
+  let auth_header = headers
+      .get("authorization")
+      .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".into()))?
+      .to_str()
+      .map_err(|_| ApiError::Unauthorized("Invalid authorization header".into()))?;
+
+  if !auth_header.starts_with("Bearer ") {
+      return Err(ApiError::Unauthorized("Invalid authorization format".into()));
+  }
+
+  Ok(&auth_header[7..])
+}
+
+pub async fn list_webhooks(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<Vec<WebhookResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let webhooks = notification::list_webhooks(state.as_ref(), user_id).await?;
+  Ok(ApiResponse::success(webhooks))
+}
+
+pub async fn create_webhook(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Json(payload): Json<CreateWebhookRequest>,
+) -> Result<ApiResponse<WebhookCreatedResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let webhook = notification::create_webhook(state.as_ref(), user_id, payload).await?;
+  Ok(ApiResponse::success(webhook))
+}
+
+pub async fn delete_webhook(
+  State(state): State<SharedState>,
+  Path(webhook_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  notification::delete_webhook(state.as_ref(), user_id, webhook_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn list_notifications(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Query(params): Query<ListNotificationsQuery>,
+) -> Result<ApiResponse<Vec<NotificationResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let notifications = notification::list_notifications(state.as_ref(), user_id, params).await?;
+  Ok(ApiResponse::success(notifications))
+}
+
+pub async fn update_notify_settings(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<UpdateNotifySettingsRequest>,
+) -> Result<ApiResponse<NotifySettingsResponse>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let settings = notification::update_notify_settings(state.as_ref(), room_id, user_id, payload).await?;
+  Ok(ApiResponse::success(settings))
+}
@@ -0,0 +1,82 @@
+use axum::{
+  extract::{Path, Query, State},
+  http::HeaderMap,
+  Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+  database::SharedState,
+  dtos::report::{CreateReportRequest, ListReportsQuery, ReportResponse, ResolveReportRequest},
+  response::{ApiError, ApiResponse},
+  services::report,
+};
+
+fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
+  let auth_header = headers
+      .get("authorization")
+      .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".into()))?
+      .to_str()
+      .map_err(|_| ApiError::Unauthorized("Invalid authorization header".into()))?;
+
+  if !auth_header.starts_with("Bearer ") {
+      return Err(ApiError::Unauthorized("Invalid authorization format".into()));
+  }
+
+  Ok(&auth_header[7..])
+}
+
+pub async fn create_report(
+  State(state): State<SharedState>,
+  Path(message_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<CreateReportRequest>,
+) -> Result<ApiResponse<ReportResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let report = report::create_report(state.as_ref(), message_id, user_id, payload).await?;
+  Ok(ApiResponse::success(report))
+}
+
+pub async fn list_room_reports(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Query(params): Query<ListReportsQuery>,
+) -> Result<ApiResponse<Vec<ReportResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let reports = report::list_room_reports(state.as_ref(), room_id, user_id, params).await?;
+  Ok(ApiResponse::success(reports))
+}
+
+pub async fn resolve_report(
+  State(state): State<SharedState>,
+  Path(report_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<ResolveReportRequest>,
+) -> Result<ApiResponse<ReportResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let report = report::resolve_report(state.as_ref(), report_id, user_id, payload).await?;
+  Ok(ApiResponse::success(report))
+}
@@ -1,5 +1,5 @@
 use axum::{
-  extract::{Path, State},
+  extract::{Path, Query, State},
   http::HeaderMap,
   Json,
 };
@@ -8,9 +8,17 @@ use validator::Validate;
 
 use crate::{
   database::SharedState,
-  dtos::room::{AddMemberRequest, CreateRoomRequest, RoomDetailResponse, RoomResponse},
-  response::{ApiError, ApiResponse},
-  services::room,
+  dtos::{
+    export::RoomExportQuery,
+    filter::{RoomFilterConfig, RoomFilterResponse},
+    retention::RetentionPolicy,
+    room::{
+      AddMemberRequest, CreateRoomRequest, RoomDetailResponse, RoomResponse, RoomSanctionRequest, RoomSanctionResponse,
+      UpdateMemberRoleRequest, UpdateSlowModeRequest,
+    },
+  },
+  response::{ApiError, ApiResponse, FileDownload},
+  services::{export, filter, moderation, retention, room},
 };
 
 fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
@@ -134,3 +142,203 @@ pub async fn remove_member(
   Ok(ApiResponse::success(()))
 }
 
+pub async fn update_member_role(
+  State(state): State<SharedState>,
+  Path((room_id, user_id)): Path<(Uuid, Uuid)>,
+  headers: HeaderMap,
+  Json(payload): Json<UpdateMemberRoleRequest>,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  moderation::update_member_role(state.as_ref(), room_id, requester_id, user_id, payload).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn list_bans(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<Vec<RoomSanctionResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let bans = moderation::list_bans(state.as_ref(), room_id, requester_id).await?;
+  Ok(ApiResponse::success(bans))
+}
+
+pub async fn ban_member(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<RoomSanctionRequest>,
+) -> Result<ApiResponse<RoomSanctionResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let ban = moderation::ban_member(state.as_ref(), room_id, requester_id, payload).await?;
+  Ok(ApiResponse::success(ban))
+}
+
+pub async fn unban_member(
+  State(state): State<SharedState>,
+  Path((room_id, user_id)): Path<(Uuid, Uuid)>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  moderation::unban_member(state.as_ref(), room_id, requester_id, user_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn list_mutes(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<Vec<RoomSanctionResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let mutes = moderation::list_mutes(state.as_ref(), room_id, requester_id).await?;
+  Ok(ApiResponse::success(mutes))
+}
+
+pub async fn mute_member(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<RoomSanctionRequest>,
+) -> Result<ApiResponse<RoomSanctionResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let mute = moderation::mute_member(state.as_ref(), room_id, requester_id, payload).await?;
+  Ok(ApiResponse::success(mute))
+}
+
+pub async fn unmute_member(
+  State(state): State<SharedState>,
+  Path((room_id, user_id)): Path<(Uuid, Uuid)>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  moderation::unmute_member(state.as_ref(), room_id, requester_id, user_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn update_slow_mode(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<UpdateSlowModeRequest>,
+) -> Result<ApiResponse<RoomResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let room = moderation::update_slow_mode(state.as_ref(), room_id, requester_id, payload).await?;
+  Ok(ApiResponse::success(room))
+}
+
+pub async fn update_retention(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<RetentionPolicy>,
+) -> Result<ApiResponse<RoomResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let room = retention::update_room_policy(state.as_ref(), room_id, requester_id, payload).await?;
+  Ok(ApiResponse::success(room))
+}
+
+pub async fn get_room_filters(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<RoomFilterResponse>, ApiError> {
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let filters = filter::get_room_filters(state.as_ref(), room_id, requester_id).await?;
+  Ok(ApiResponse::success(filters))
+}
+
+pub async fn update_room_filters(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<RoomFilterConfig>,
+) -> Result<ApiResponse<RoomFilterResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let filters = filter::update_room_filters(state.as_ref(), room_id, requester_id, payload).await?;
+  Ok(ApiResponse::success(filters))
+}
+
+pub async fn export_room(
+  State(state): State<SharedState>,
+  Path(room_id): Path<Uuid>,
+  headers: HeaderMap,
+  Query(params): Query<RoomExportQuery>,
+) -> Result<FileDownload, ApiError> {
+  let token = extract_token(&headers)?;
+  let requester_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  export::export_room(state, room_id, requester_id, params.format).await
+}
@@ -1,14 +1,22 @@
 // chat-app-be/src/handlers/user.rs
 use axum::{
-  extract::State,
+  extract::{Path, Query, State},
   http::HeaderMap,
+  Json,
 };
+use uuid::Uuid;
+use validator::Validate;
 
 use crate::{
   database::SharedState,
-  dtos::room::UserInfo,
-  response::{ApiError, ApiResponse},
-  services::user,
+  dtos::{
+    chat::{ListSavedQuery, SaveMessageRequest, SavedMessageDto},
+    room::UserInfo,
+    schedule::{ListScheduledJobsQuery, ScheduledJobResponse, UpdateScheduledJobRequest},
+    user::{AccountDeletionResponse, BlockUserRequest, BlockedUserResponse, DeleteAccountRequest},
+  },
+  response::{ApiError, ApiResponse, FileDownload},
+  services::{account, export, saved, schedule, user},
 };
 
 fn extract_token(headers: &HeaderMap) -> Result<&str, ApiError> {
@@ -38,4 +46,173 @@ pub async fn list_all_users(
 
   let users = user::list_all_users(state.as_ref()).await?;
   Ok(ApiResponse::success(users))
-}
\ No newline at end of file
+}
+
+pub async fn list_blocks(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<Vec<BlockedUserResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let blocks = user::list_blocks(state.as_ref(), user_id).await?;
+  Ok(ApiResponse::success(blocks))
+}
+
+pub async fn block_user(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Json(payload): Json<BlockUserRequest>,
+) -> Result<ApiResponse<BlockedUserResponse>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let block = user::block_user(state.as_ref(), user_id, payload).await?;
+  Ok(ApiResponse::success(block))
+}
+
+pub async fn unblock_user(
+  State(state): State<SharedState>,
+  Path(blocked_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  user::unblock_user(state.as_ref(), user_id, blocked_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn list_saved(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Query(params): Query<ListSavedQuery>,
+) -> Result<ApiResponse<Vec<SavedMessageDto>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let saved = saved::list_saved(state.as_ref(), user_id, params.limit).await?;
+  Ok(ApiResponse::success(saved))
+}
+
+pub async fn save_message(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Json(payload): Json<SaveMessageRequest>,
+) -> Result<ApiResponse<SavedMessageDto>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let saved = saved::save_message(state.as_ref(), user_id, payload).await?;
+  Ok(ApiResponse::success(saved))
+}
+
+pub async fn unsave_message(
+  State(state): State<SharedState>,
+  Path(message_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  saved::unsave_message(state.as_ref(), user_id, message_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn list_scheduled(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Query(params): Query<ListScheduledJobsQuery>,
+) -> Result<ApiResponse<Vec<ScheduledJobResponse>>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let jobs = schedule::list_jobs(state.as_ref(), user_id, params).await?;
+  Ok(ApiResponse::success(jobs))
+}
+
+pub async fn update_scheduled(
+  State(state): State<SharedState>,
+  Path(job_id): Path<Uuid>,
+  headers: HeaderMap,
+  Json(payload): Json<UpdateScheduledJobRequest>,
+) -> Result<ApiResponse<ScheduledJobResponse>, ApiError> {
+  payload.validate()
+      .map_err(|e| ApiError::BadRequest(format!("Validation error: {}", e)))?;
+
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let job = schedule::update_job(state.as_ref(), user_id, job_id, payload).await?;
+  Ok(ApiResponse::success(job))
+}
+
+pub async fn cancel_scheduled(
+  State(state): State<SharedState>,
+  Path(job_id): Path<Uuid>,
+  headers: HeaderMap,
+) -> Result<ApiResponse<()>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  schedule::cancel_job(state.as_ref(), user_id, job_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn export_account(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+) -> Result<FileDownload, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  export::export_account(state, user_id).await
+}
+
+pub async fn delete_account(
+  State(state): State<SharedState>,
+  headers: HeaderMap,
+  Json(payload): Json<DeleteAccountRequest>,
+) -> Result<ApiResponse<AccountDeletionResponse>, ApiError> {
+  let token = extract_token(&headers)?;
+  let user_id = state
+      .jwt
+      .validate(token)
+      .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
+
+  let deletion = account::request_deletion(&state, user_id, payload).await?;
+  Ok(ApiResponse::success(deletion))
+}
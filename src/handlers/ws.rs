@@ -7,15 +7,18 @@ use axum::{
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};   // use WebSocketUpgrade to upgrade a HTTP request to a WebSocket connection
 use futures::{StreamExt, SinkExt};
 use serde::Deserialize;
-use tokio::select;      // run multiple futures concurrently and handle the results
-use tokio_stream::wrappers::BroadcastStream; // Wrapper to convert a broadcast channel into a stream
+use std::collections::HashSet;
+use tokio::{select, sync::broadcast::error::RecvError};      // run multiple futures concurrently and handle the results
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream}; // Wrapper to convert a broadcast channel into a stream
+use tracing::Instrument;
 use uuid::Uuid;
 
 use crate::{
   database::SharedState,
-  dtos::chat::{WsInboundMessage, WsOutboundMessage},
+  dtos::chat::{EphemeralDto, WsInboundMessage, WsOutboundMessage},
+  logging,
   response::ApiError,
-  services::chat,
+  services::{admin, chat, command, user},
 };
 
 #[derive(Debug, Deserialize)]
@@ -50,19 +53,35 @@ pub async fn upgrade (
       .map_err(|_| ApiError::Unauthorized("Invalid token".into()))?;
 
   chat::ensure_membership(state.as_ref(), params.room_id, user_id).await?;
+  let blocked = user::blocked_ids(state.as_ref(), user_id).await?;
 
-  Ok(ws.on_upgrade(move |socket| handle_socket(state, user_id, params.room_id, socket)))
+  // The connection outlives the upgrade request, so it gets its own root span carrying the request id.
+  let span = tracing::info_span!(
+      parent: None,
+      "websocket",
+      request_id = logging::request_id(&headers).unwrap_or(""),
+      %user_id,
+      room_id = %params.room_id,
+  );
+  Ok(ws.on_upgrade(move |socket| handle_socket(state, user_id, params.room_id, blocked, socket).instrument(span)))
 }
 
 async fn handle_socket(
   state: SharedState,
   user_id: Uuid,
   room_id: Uuid,
+  mut blocked: HashSet<Uuid>,
   socket: WebSocket,
 ) {
+  let _presence = state.presence.connect(user_id);
+  let _socket_metrics = state.metrics.socket_opened(room_id);
   let (mut ws_sender, mut ws_receiver) = socket.split();
   let mut rx_stream = BroadcastStream::new(state.chat_tx.subscribe());
+  let mut kicks = state.presence.subscribe_kicks();
+  let mut block_changes = state.presence.subscribe_block_changes();
   let reader_state = state.clone();
+  let writer_state = state.clone();
+  tracing::info!("WebSocket connected");
 
   let mut read_task = tokio::spawn(async move {
     while let Some(Ok(msg)) = ws_receiver.next().await {
@@ -72,15 +91,17 @@ async fn handle_socket(
                     if payload.content.trim().is_empty() {
                         continue;
                     }
-                    if let Ok(message) = chat::send_message(
-                        reader_state.as_ref(),
-                        room_id,
-                        user_id,
-                        payload.content,
-                    )
-                    .await
-                    {
-                        let _ = reader_state.chat_tx.send(message);
+                    match command::submit(reader_state.as_ref(), room_id, user_id, payload).await {
+                        Ok(outcome) => command::publish(reader_state.as_ref(), room_id, user_id, outcome),
+                        Err(e) => {
+                            tracing::debug!(error = %e, "WebSocket message rejected");
+                            // Errors go back to the sender only, as an ephemeral event.
+                            let _ = reader_state.chat_tx.send(WsOutboundMessage::Ephemeral(EphemeralDto {
+                                room_id,
+                                user_id,
+                                content: e.client_message(),
+                            }));
+                        }
                     }
                 }
             }
@@ -88,11 +109,62 @@ async fn handle_socket(
             _ => {}
         }
     }
-  });
+  }
+  .in_current_span());
 
   let mut write_task = tokio::spawn(async move {
-    while let Some(Ok(event)) = rx_stream.next().await {
-        if event.room_id != room_id {
+    loop {
+        let event = select! {
+            event = rx_stream.next() => match event {
+                Some(Ok(event)) => event,
+                Some(Err(BroadcastStreamRecvError::Lagged(skipped))) => {
+                    tracing::warn!(skipped, "WebSocket fell behind the event stream; closing it");
+                    writer_state.metrics.events_dropped("websocket", skipped);
+                    break;
+                }
+                None => break,
+            },
+            kicked = kicks.recv() => {
+                // Banned or suspended accounts lose their live sockets at once.
+                let closes = match kicked {
+                    Ok(kicked_id) => kicked_id == user_id,
+                    // One of the missed kicks may have been ours, so ask the database instead.
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "WebSocket missed account kicks; re-checking the account");
+                        !matches!(admin::is_active(writer_state.as_ref(), user_id).await, Ok(true))
+                    }
+                    Err(RecvError::Closed) => false,
+                };
+                if closes {
+                    tracing::info!("WebSocket closed: account is no longer active");
+                    let _ = ws_sender.send(Message::Close(None)).await;
+                    break;
+                }
+                continue;
+            }
+            changed = block_changes.recv() => {
+                // After a lag we can't tell whose list changed, so reload ours anyway.
+                let reload = match changed {
+                    Ok(changed_id) => changed_id == user_id,
+                    Err(RecvError::Lagged(_)) => true,
+                    Err(RecvError::Closed) => false,
+                };
+                if reload && let Ok(ids) = user::blocked_ids(writer_state.as_ref(), user_id).await {
+                    blocked = ids;
+                }
+                continue;
+            }
+        };
+        if !event.is_visible_to(room_id, user_id) {
+            continue;
+        }
+        // Hide what users this socket's owner blocked are saying.
+        let sender = match &event {
+            WsOutboundMessage::MessageCreated(message) | WsOutboundMessage::MessageUpdated(message) => Some(message.sender_id),
+            WsOutboundMessage::MessagePinned(pin) => Some(pin.message.sender_id),
+            _ => None,
+        };
+        if sender.is_some_and(|sender| blocked.contains(&sender)) {
             continue;
         }
         if ws_sender
@@ -105,8 +177,20 @@ async fn handle_socket(
         {
             break;
         }
+        // A socket whose user left or was removed from the room, or whose room is gone, is closed.
+        let closes = match &event {
+            WsOutboundMessage::MemberRemoved(removed) => removed.user_id == user_id,
+            WsOutboundMessage::RoomDeleted(_) => true,
+            _ => false,
+        };
+        if closes {
+            tracing::info!("WebSocket closed: no longer in the room");
+            let _ = ws_sender.send(Message::Close(None)).await;
+            break;
+        }
       }
-  });
+  }
+  .in_current_span());
 
   select! {
       _ = &mut read_task => {},
@@ -115,4 +199,5 @@ async fn handle_socket(
 
   read_task.abort();
   write_task.abort();
+  tracing::info!("WebSocket disconnected");
 }
\ No newline at end of file
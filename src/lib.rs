@@ -0,0 +1,16 @@
+// Shared by the server (`main.rs`) and the `chat-admin` tool.
+pub mod config;
+pub mod database;
+pub mod dtos;
+pub mod entities;
+pub mod handlers;
+pub mod logging;
+pub mod metrics;
+pub mod repositories;
+pub mod response;
+pub mod routes;
+pub mod security;
+pub mod services;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,137 @@
+use axum::{
+  extract::MatchedPath,
+  http::{HeaderMap, Request},
+};
+use tracing::Span;
+use tracing_subscriber::EnvFilter;
+
+use crate::config::{LogConfig, LogFormat};
+
+// Taken from the client when it sends one, generated otherwise, and echoed on the response.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Logs go to stderr, so `chat-admin --json` output on stdout stays machine-readable.
+// Records from crates using `log` (sqlx among them) are captured too.
+pub fn init(config: &LogConfig) {
+  let builder = tracing_subscriber::fmt()
+      .with_env_filter(EnvFilter::new(&config.filter))
+      .with_writer(std::io::stderr);
+  let result = match config.format {
+      LogFormat::Json => builder.json().flatten_event(true).with_current_span(true).with_span_list(true).try_init(),
+      LogFormat::Text => builder.try_init(),
+  };
+  if let Err(e) = result {
+      eprintln!("Logging is already initialised: {}", e);
+  }
+}
+
+// Root span of an HTTP request: everything logged while handling it carries the request id.
+pub fn request_span<B>(request: &Request<B>) -> Span {
+  let route = request.extensions().get::<MatchedPath>().map(MatchedPath::as_str).unwrap_or("");
+  tracing::info_span!(
+      "request",
+      method = %request.method(),
+      // The path only: query strings may carry tokens.
+      path = request.uri().path(),
+      route,
+      request_id = request_id(request.headers()).unwrap_or(""),
+  )
+}
+
+pub fn request_id(headers: &HeaderMap) -> Option<&str> {
+  headers.get(REQUEST_ID_HEADER).and_then(|v| v.to_str().ok())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use axum::{body::Body, http::HeaderValue, routing::get, Router};
+  use tower::Service;
+  use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+  };
+
+  use super::*;
+
+  // Collects JSON log lines written while the guard returned by `capture` is alive.
+  #[derive(Clone, Default)]
+  struct Captured(Arc<Mutex<Vec<u8>>>);
+
+  impl std::io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+      self.0.lock().unwrap().extend_from_slice(buf);
+      Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+      Ok(())
+    }
+  }
+
+  impl Captured {
+    fn capture(&self) -> tracing::subscriber::DefaultGuard {
+      let writer = self.clone();
+      let subscriber = tracing_subscriber::fmt()
+          .json()
+          .flatten_event(true)
+          .with_current_span(true)
+          .with_writer(move || writer.clone())
+          .finish();
+      tracing::subscriber::set_default(subscriber)
+    }
+
+    fn lines(&self) -> Vec<serde_json::Value> {
+      String::from_utf8(self.0.lock().unwrap().clone())
+          .unwrap()
+          .lines()
+          .map(|line| serde_json::from_str(line).unwrap())
+          .collect()
+    }
+  }
+
+  // The same request id layers as the server, around a handler that logs. A router is always
+  // ready, so tests call it directly.
+  fn app() -> Router {
+    let header = axum::http::HeaderName::from_static(REQUEST_ID_HEADER);
+    Router::new()
+        .route("/rooms/:room_id", get(|| async {
+          tracing::info!("handled");
+        }))
+        .layer(PropagateRequestIdLayer::new(header.clone()))
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .layer(SetRequestIdLayer::new(header, MakeRequestUuid))
+  }
+
+  #[tokio::test]
+  async fn client_request_ids_are_logged_and_echoed() {
+    let captured = Captured::default();
+    let _guard = captured.capture();
+
+    let request = Request::get("/rooms/42?token=secret").header(REQUEST_ID_HEADER, "abc-123").body(Body::empty()).unwrap();
+    let response = app().call(request).await.unwrap();
+    assert_eq!(response.headers().get(REQUEST_ID_HEADER), Some(&HeaderValue::from_static("abc-123")));
+
+    let lines = captured.lines();
+    let handled = lines.iter().find(|l| l["message"] == "handled").expect("handler logged");
+    assert_eq!(handled["span"]["request_id"], "abc-123");
+    assert_eq!(handled["span"]["path"], "/rooms/42");
+    assert_eq!(handled["span"]["route"], "/rooms/:room_id");
+    assert!(!String::from_utf8(captured.0.lock().unwrap().clone()).unwrap().contains("secret"));
+  }
+
+  #[tokio::test]
+  async fn missing_request_ids_are_generated() {
+    let captured = Captured::default();
+    let _guard = captured.capture();
+
+    let response = app().call(Request::get("/rooms/42").body(Body::empty()).unwrap()).await.unwrap();
+    let echoed = response.headers().get(REQUEST_ID_HEADER).expect("request id echoed").to_str().unwrap().to_string();
+    assert!(uuid::Uuid::parse_str(&echoed).is_ok());
+
+    let lines = captured.lines();
+    let handled = lines.iter().find(|l| l["message"] == "handled").expect("handler logged");
+    assert_eq!(handled["span"]["request_id"], echoed.as_str());
+  }
+}
@@ -1,50 +1,155 @@
-mod database;
-mod entities;
-mod dtos;
-mod handlers;
-mod repositories;
-mod response;
-mod routes;
-mod security;
-mod services;
-
-use std::time::Duration;
-use axum::Router;
-use tower_http::cors::CorsLayer;
+use std::path::PathBuf;
+use axum::{extract::DefaultBodyLimit, http::{HeaderName, HeaderValue}, Router};
+use chat_app::{config::Config, database, handlers, logging, metrics, routes, services};
+use clap::{Parser, Subcommand};
+use tower_http::{
+    cors::{Any, CorsLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::{DefaultOnResponse, TraceLayer},
+    LatencyUnit,
+};
+use tracing::Level;
+
+/// The chat server.
+///
+/// Settings come from the config file, then the environment (and `.env`), then these flags.
+#[derive(Parser)]
+#[command(name = "chat-app")]
+struct Cli {
+    /// TOML config file; defaults to `CONFIG_FILE`, or `config.toml` if it exists.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Address to listen on, as host:port.
+    #[arg(long)]
+    bind: Option<String>,
+
+    /// Print the effective configuration, secrets redacted, and exit.
+    #[arg(long)]
+    print_config: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Import rooms, people and history from another chat service.
+    #[command(subcommand)]
+    Import(ImportCommand),
+}
+
+#[derive(Subcommand)]
+enum ImportCommand {
+    /// A Slack workspace export zip.
+    Slack { export: PathBuf },
+    /// DiscordChatExporter JSON files, one per channel.
+    Discord {
+        #[arg(required = true)]
+        channels: Vec<PathBuf>,
+    },
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+
+    let mut config = Config::load(cli.config.as_deref())?;
+    if let Some(bind) = cli.bind {
+        config.server.bind = bind;
+    }
+    config.validate()?;
+
+    if cli.print_config {
+        print!("{}", config.redacted());
+        return Ok(());
+    }
+    logging::init(&config.log);
+    if let Some(Command::Import(command)) = cli.command {
+        return run_import(&config, command).await;
+    }
 
-    let port = std::env::var("PORT")
-        .unwrap_or_else(|_| "3000".into())
-        .parse::<u16>()
-        .unwrap_or(3000);
+    tracing::info!(config = %config.redacted(), "Effective configuration");
+    if config.auth.totp_encryption_key.is_none() {
+        tracing::warn!("auth.totp_encryption_key is not set: two-factor secrets are stored unencrypted");
+    }
 
-        let jwt_secret = std::env::var("JWT_SECRET")
-        .unwrap_or_else(|_| "your-secret-key-change-in-production".into());
-    
-    let jwt_expiration_minutes = std::env::var("JWT_EXPIRATION_MINUTES")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(1440); // Default 24 hours
-    
-    let jwt_expiration = Duration::from_secs(jwt_expiration_minutes * 60);
+    let cors = cors_layer(&config.server.cors_origins)?;
+    let body_limit = DefaultBodyLimit::max(config.limits.max_body_bytes);
+    let addr = config.server.bind.clone();
+    let metrics_addr = config.metrics.bind.clone();
+    let request_id_header = HeaderName::from_static(logging::REQUEST_ID_HEADER);
 
-    let state = database::init_app_state(jwt_secret, jwt_expiration).await?;
+    // Fails startup unless a secure signing key is configured.
+    let state = database::init_app_state(config).await?;
+
+    services::notification::spawn_dispatcher(state.clone());
+    services::integration::spawn_outgoing_dispatcher(state.clone());
+    services::unfurl::spawn_unfurler(state.clone());
+    services::schedule::spawn_scheduler(state.clone());
+    services::retention::spawn_pruner(state.clone());
+    services::account::spawn_deletion_sweeper(state.clone());
+
+    // Metrics are never served on the public API listener: they reveal traffic and user counts.
+    match metrics_addr {
+        Some(metrics_addr) => {
+            let listener = tokio::net::TcpListener::bind(&metrics_addr).await?;
+            let metrics_app = routes::metrics::router().with_state(state.clone());
+            tracing::info!(addr = %metrics_addr, "Serving metrics");
+            tokio::spawn(async move {
+                if let Err(e) = axum::serve(listener, metrics_app).await {
+                    tracing::error!(error = %e, "Metrics listener failed");
+                }
+            });
+        }
+        None => tracing::info!("Metrics are disabled; set metrics.bind to serve them"),
+    }
 
     let app = Router::new()
         .merge(routes::build())
-        .layer(CorsLayer::permissive())
+        .layer(axum::middleware::from_fn_with_state(state.clone(), handlers::guard::reject_inactive_accounts))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), metrics::track_http))
+        .layer(body_limit)
+        .layer(cors)
+        .layer(PropagateRequestIdLayer::new(request_id_header.clone()))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(logging::request_span)
+                .on_response(DefaultOnResponse::new().level(Level::INFO).latency_unit(LatencyUnit::Millis)),
+        )
+        .layer(SetRequestIdLayer::new(request_id_header, MakeRequestUuid))
         .with_state(state);
 
-    let addr = format!("localhost:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    
-    println!("🚀 Server is running on http://{}", addr);
-    
+
+    tracing::info!(%addr, "Server is running");
+
     axum::serve(listener, app).await?;
 
     Ok(())
 }
 
+// No origins configured keeps the old allow-anything behaviour, handy in development.
+fn cors_layer(origins: &[String]) -> anyhow::Result<CorsLayer> {
+    if origins.is_empty() {
+        return Ok(CorsLayer::permissive());
+    }
+    let origins = origins
+        .iter()
+        .map(|origin| HeaderValue::from_str(origin.trim_end_matches('/')))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(CorsLayer::new().allow_origin(origins).allow_methods(Any).allow_headers(Any))
+}
+
+async fn run_import(config: &Config, command: ImportCommand) -> anyhow::Result<()> {
+    let db = database::init_db_pool(&config.database).await?;
+
+    let report = match command {
+        ImportCommand::Slack { export } => services::import::import_slack(&db, &export).await?,
+        ImportCommand::Discord { channels } => services::import::import_discord(&db, &channels).await?,
+    };
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use std::{
+  collections::HashMap,
+  fmt,
+  sync::{Arc, Mutex},
+  time::Instant,
+};
+
+use axum::{
+  extract::{MatchedPath, Request, State},
+  middleware::Next,
+  response::Response,
+};
+use prometheus::{
+  Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use uuid::Uuid;
+
+use crate::database::{AppState, SharedState};
+
+// Seconds; requests and queries share buckets from 1ms to 10s.
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+// What `/metrics` exposes. Values that are cheap to read on demand (pool usage, the event
+// backlog) are sampled when scraped rather than tracked as they change.
+pub struct Metrics {
+  registry: Registry,
+  http_requests: IntCounterVec,
+  http_duration: HistogramVec,
+  ws_connections: IntGauge,
+  ws_rooms: IntGauge,
+  // Open sockets per room, backing `ws_rooms`.
+  room_sockets: Mutex<HashMap<Uuid, usize>>,
+  messages_sent: IntCounter,
+  events_dropped: IntCounterVec,
+  event_backlog: IntGauge,
+  db_queries: HistogramVec,
+  db_pool: IntGaugeVec,
+  auth_failures: IntCounterVec,
+}
+
+impl fmt::Debug for Metrics {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Metrics").finish_non_exhaustive()
+  }
+}
+
+impl Metrics {
+  pub fn new() -> prometheus::Result<Self> {
+    let registry = Registry::new();
+    let metrics = Self {
+        http_requests: IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled, by route and status"),
+            &["method", "route", "status"],
+        )?,
+        http_duration: HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "Time to produce an HTTP response, by route")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["method", "route"],
+        )?,
+        ws_connections: IntGauge::new("ws_connections_active", "Open WebSocket connections")?,
+        ws_rooms: IntGauge::new("ws_subscribed_rooms", "Rooms with at least one open WebSocket connection")?,
+        room_sockets: Mutex::new(HashMap::new()),
+        messages_sent: IntCounter::new("chat_messages_sent_total", "Messages posted to rooms; use rate() for messages per second")?,
+        events_dropped: IntCounterVec::new(
+            Opts::new("chat_events_dropped_total", "Live events a subscriber missed because it fell behind"),
+            &["consumer"],
+        )?,
+        event_backlog: IntGauge::new("chat_events_backlog", "Live events queued for the slowest subscriber")?,
+        db_queries: HistogramVec::new(
+            HistogramOpts::new("db_query_duration_seconds", "Database statement latency").buckets(LATENCY_BUCKETS.to_vec()),
+            &["operation", "outcome"],
+        )?,
+        db_pool: IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state; `max` is the configured limit"),
+            &["state"],
+        )?,
+        auth_failures: IntCounterVec::new(
+            Opts::new("auth_failures_total", "Rejected authentication attempts, by reason"),
+            &["reason"],
+        )?,
+        registry,
+    };
+
+    let registry = &metrics.registry;
+    registry.register(Box::new(metrics.http_requests.clone()))?;
+    registry.register(Box::new(metrics.http_duration.clone()))?;
+    registry.register(Box::new(metrics.ws_connections.clone()))?;
+    registry.register(Box::new(metrics.ws_rooms.clone()))?;
+    registry.register(Box::new(metrics.messages_sent.clone()))?;
+    registry.register(Box::new(metrics.events_dropped.clone()))?;
+    registry.register(Box::new(metrics.event_backlog.clone()))?;
+    registry.register(Box::new(metrics.db_queries.clone()))?;
+    registry.register(Box::new(metrics.db_pool.clone()))?;
+    registry.register(Box::new(metrics.auth_failures.clone()))?;
+    Ok(metrics)
+  }
+
+  // Counts the socket until the returned guard is dropped.
+  pub fn socket_opened(self: &Arc<Self>, room_id: Uuid) -> SocketGuard {
+    self.ws_connections.inc();
+    let mut rooms = self.room_sockets.lock().expect("metrics lock poisoned");
+    *rooms.entry(room_id).or_default() += 1;
+    self.ws_rooms.set(rooms.len() as i64);
+    SocketGuard { metrics: self.clone(), room_id }
+  }
+
+  pub fn message_sent(&self) {
+    self.messages_sent.inc();
+  }
+
+  pub fn events_dropped(&self, consumer: &str, count: u64) {
+    self.events_dropped.with_label_values(&[consumer]).inc_by(count);
+  }
+
+  pub fn auth_failure(&self, reason: &str) {
+    self.auth_failures.with_label_values(&[reason]).inc();
+  }
+
+  pub fn observe_query(&self, info: &sea_orm::metric::Info<'_>) {
+    // The statement's leading keyword keeps the label set small.
+    let operation = info
+        .statement
+        .sql
+        .split_whitespace()
+        .next()
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let outcome = if info.failed { "error" } else { "ok" };
+    self.db_queries
+        .with_label_values(&[&operation, outcome])
+        .observe(info.elapsed.as_secs_f64());
+  }
+
+  // Prometheus text format.
+  pub fn render(&self, state: &AppState) -> String {
+    self.event_backlog.set(state.chat_tx.len() as i64);
+    let pool = state.db.get_mysql_connection_pool();
+    let idle = pool.num_idle() as i64;
+    self.db_pool.with_label_values(&["idle"]).set(idle);
+    self.db_pool.with_label_values(&["active"]).set(i64::from(pool.size()) - idle);
+    self.db_pool.with_label_values(&["max"]).set(i64::from(state.config.database.max_connections));
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+        tracing::error!(error = %e, "Failed to encode metrics");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+  }
+}
+
+pub struct SocketGuard {
+  metrics: Arc<Metrics>,
+  room_id: Uuid,
+}
+
+impl Drop for SocketGuard {
+  fn drop(&mut self) {
+    self.metrics.ws_connections.dec();
+    let mut rooms = self.metrics.room_sockets.lock().expect("metrics lock poisoned");
+    if let Some(count) = rooms.get_mut(&self.room_id) {
+        *count -= 1;
+        if *count == 0 {
+            rooms.remove(&self.room_id);
+        }
+    }
+    self.metrics.ws_rooms.set(rooms.len() as i64);
+  }
+}
+
+// Labels requests with their route template rather than the raw path, so ids don't multiply series.
+pub async fn track_http(State(state): State<SharedState>, request: Request, next: Next) -> Response {
+  let method = request.method().to_string();
+  let route = request
+      .extensions()
+      .get::<MatchedPath>()
+      .map(|path| path.as_str().to_string())
+      .unwrap_or_else(|| "unmatched".into());
+  let started = Instant::now();
+
+  let response = next.run(request).await;
+
+  let metrics = &state.metrics;
+  metrics.http_duration.with_label_values(&[&method, &route]).observe(started.elapsed().as_secs_f64());
+  metrics
+      .http_requests
+      .with_label_values(&[&method, &route, response.status().as_str()])
+      .inc();
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use axum::{body::Body, routing::get, Router};
+  use sea_orm::{DatabaseBackend, Statement};
+  use tower::Service;
+
+  use super::*;
+  use crate::test_support;
+
+  fn gathered(metrics: &Metrics) -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&metrics.registry.gather(), &mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+  }
+
+  #[test]
+  fn rooms_count_while_any_socket_is_open() {
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let (lobby, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let first = metrics.socket_opened(lobby);
+    let second = metrics.socket_opened(lobby);
+    let third = metrics.socket_opened(other);
+    assert_eq!((metrics.ws_connections.get(), metrics.ws_rooms.get()), (3, 2));
+
+    drop(first);
+    drop(third);
+    assert_eq!((metrics.ws_connections.get(), metrics.ws_rooms.get()), (1, 1));
+    drop(second);
+    assert_eq!((metrics.ws_connections.get(), metrics.ws_rooms.get()), (0, 0));
+  }
+
+  #[test]
+  fn queries_are_labelled_by_their_leading_keyword() {
+    let metrics = Metrics::new().unwrap();
+    let select = Statement::from_string(DatabaseBackend::MySql, "  SELECT * FROM `users` WHERE `id` = 'x'");
+    let delete = Statement::from_string(DatabaseBackend::MySql, "DELETE FROM `messages`");
+    metrics.observe_query(&sea_orm::metric::Info { elapsed: Duration::from_millis(3), statement: &select, failed: false });
+    metrics.observe_query(&sea_orm::metric::Info { elapsed: Duration::from_millis(3), statement: &delete, failed: true });
+
+    let text = gathered(&metrics);
+    assert!(text.contains(r#"db_query_duration_seconds_count{operation="select",outcome="ok"} 1"#), "{}", text);
+    assert!(text.contains(r#"db_query_duration_seconds_count{operation="delete",outcome="error"} 1"#), "{}", text);
+    assert!(!text.contains("users"));
+  }
+
+  #[tokio::test]
+  async fn requests_are_labelled_by_route_template() {
+    let state = Arc::new(test_support::state(test_support::mock_db().into_connection()));
+    let mut app = Router::new()
+        .route("/rooms/:room_id", get(|| async {}))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), track_http))
+        .with_state(state.clone());
+
+    for path in [format!("/rooms/{}", Uuid::new_v4()), format!("/rooms/{}", Uuid::new_v4()), "/nowhere".into()] {
+      app.call(axum::http::Request::get(path).body(Body::empty()).unwrap()).await.unwrap();
+    }
+
+    let text = gathered(&state.metrics);
+    assert!(text.contains(r#"http_requests_total{method="GET",route="/rooms/:room_id",status="200"} 2"#), "{}", text);
+    assert!(text.contains(r#"http_requests_total{method="GET",route="unmatched",status="404"} 1"#), "{}", text);
+  }
+}
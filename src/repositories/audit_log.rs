@@ -0,0 +1,42 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+
+use crate::{
+  database::DbPool,
+  entities::audit_log::{ActiveModel, Column, Entity as AuditLogEntity, Model as AuditLogModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, entry: AuditLogModel) -> Result<AuditLogModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(entry.id),
+    actor_id: Set(entry.actor_id),
+    action: Set(entry.action),
+    target_type: Set(entry.target_type),
+    target_id: Set(entry.target_id),
+    details: Set(entry.details),
+    created_at: Set(entry.created_at),
+  };
+  active_model.insert(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list(
+  db: &DbPool,
+  actor_id: Option<&str>,
+  action: Option<&str>,
+  limit: u64,
+) -> Result<Vec<AuditLogModel>, sea_orm::DbErr> {
+  let mut select = AuditLogEntity::find();
+  if let Some(actor_id) = actor_id {
+      select = select.filter(Column::ActorId.eq(actor_id));
+  }
+  if let Some(action) = action {
+      select = select.filter(Column::Action.eq(action));
+  }
+
+  select
+      .order_by_desc(Column::CreatedAt)
+      .limit(limit)
+      .all(db)
+      .await
+}
@@ -0,0 +1,54 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, sea_query::Expr};
+
+use crate::{
+  database::DbPool,
+  entities::auth_token::{ActiveModel, Column, Entity as AuthTokenEntity, Model as AuthTokenModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_token_hash(db: &DbPool, token_hash: &str) -> Result<Option<AuthTokenModel>, sea_orm::DbErr> {
+  AuthTokenEntity::find()
+      .filter(Column::TokenHash.eq(token_hash))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, token: AuthTokenModel) -> Result<AuthTokenModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(token.id),
+    user_id: Set(token.user_id),
+    purpose: Set(token.purpose),
+    token_hash: Set(token.token_hash),
+    expires_at: Set(token.expires_at),
+    used_at: Set(token.used_at),
+    created_at: Set(token.created_at),
+  };
+  active_model.insert(db).await
+}
+
+// Consume a token. Returns false when another request already used it.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn mark_used(db: &DbPool, id: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = AuthTokenEntity::update_many()
+      .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+      .filter(Column::Id.eq(id))
+      .filter(Column::UsedAt.is_null())
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected == 1)
+}
+
+// Retire every outstanding token of a purpose, e.g. when a newer one is issued.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn invalidate_for_user(db: &DbPool, user_id: &str, purpose: &str) -> Result<(), sea_orm::DbErr> {
+  AuthTokenEntity::update_many()
+      .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+      .filter(Column::UserId.eq(user_id))
+      .filter(Column::Purpose.eq(purpose))
+      .filter(Column::UsedAt.is_null())
+      .exec(db)
+      .await?;
+  Ok(())
+}
@@ -0,0 +1,39 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::bot::{ActiveModel, Column, Entity as BotEntity, Model as BotModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_user_id(db: &DbPool, user_id: &str) -> Result<Option<BotModel>, sea_orm::DbErr> {
+  BotEntity::find_by_id(user_id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_api_key_hash(db: &DbPool, api_key_hash: &str) -> Result<Option<BotModel>, sea_orm::DbErr> {
+  BotEntity::find()
+      .filter(Column::ApiKeyHash.eq(api_key_hash))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_owner(db: &DbPool, owner_id: &str) -> Result<Vec<BotModel>, sea_orm::DbErr> {
+  BotEntity::find()
+      .filter(Column::OwnerId.eq(owner_id))
+      .order_by_asc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, bot: BotModel) -> Result<BotModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    user_id: Set(bot.user_id),
+    owner_id: Set(bot.owner_id),
+    api_key_hash: Set(bot.api_key_hash),
+    created_at: Set(bot.created_at),
+  };
+  active_model.insert(db).await
+}
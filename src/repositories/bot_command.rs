@@ -0,0 +1,53 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::bot_command::{ActiveModel, Column, Entity as BotCommandEntity, Model as BotCommandModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_bot(db: &DbPool, bot_id: &str) -> Result<Vec<BotCommandModel>, sea_orm::DbErr> {
+  BotCommandEntity::find()
+      .filter(Column::BotId.eq(bot_id))
+      .order_by_asc(Column::Name)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_name(db: &DbPool, name: &str) -> Result<Vec<BotCommandModel>, sea_orm::DbErr> {
+  BotCommandEntity::find()
+      .filter(Column::Name.eq(name))
+      .order_by_asc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_bot(db: &DbPool, bot_id: &str, id: &str) -> Result<Option<BotCommandModel>, sea_orm::DbErr> {
+  BotCommandEntity::find_by_id(id)
+      .filter(Column::BotId.eq(bot_id))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, command: BotCommandModel) -> Result<BotCommandModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(command.id),
+    bot_id: Set(command.bot_id),
+    name: Set(command.name),
+    description: Set(command.description),
+    url: Set(command.url),
+    secret: Set(command.secret),
+    created_at: Set(command.created_at),
+  };
+  active_model.insert(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, command: BotCommandModel) -> Result<(), sea_orm::DbErr> {
+  let active_model: ActiveModel = command.into();
+  active_model.delete(db).await?;
+  Ok(())
+}
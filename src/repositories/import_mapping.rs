@@ -0,0 +1,38 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+
+use crate::{
+  database::DbPool,
+  entities::import_mapping::{ActiveModel, Entity as ImportMappingEntity, Model as ImportMappingModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find(
+  db: &DbPool,
+  source: &str,
+  kind: &str,
+  external_id: &str,
+) -> Result<Option<ImportMappingModel>, sea_orm::DbErr> {
+  ImportMappingEntity::find_by_id((source.to_string(), kind.to_string(), external_id.to_string()))
+    .one(db)
+    .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(
+  db: &DbPool,
+  source: &str,
+  kind: &str,
+  external_id: &str,
+  local_id: &str,
+) -> Result<ImportMappingModel, sea_orm::DbErr> {
+  ActiveModel {
+    source: Set(source.to_string()),
+    kind: Set(kind.to_string()),
+    external_id: Set(external_id.to_string()),
+    local_id: Set(local_id.to_string()),
+    created_at: Set(Utc::now()),
+  }
+  .insert(db)
+  .await
+}
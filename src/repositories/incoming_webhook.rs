@@ -0,0 +1,50 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::incoming_webhook::{ActiveModel, Column, Entity as IncomingWebhookEntity, Model as IncomingWebhookModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_token_hash(db: &DbPool, token_hash: &str) -> Result<Option<IncomingWebhookModel>, sea_orm::DbErr> {
+  IncomingWebhookEntity::find()
+      .filter(Column::TokenHash.eq(token_hash))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_in_room(db: &DbPool, room_id: &str, id: &str) -> Result<Option<IncomingWebhookModel>, sea_orm::DbErr> {
+  IncomingWebhookEntity::find_by_id(id)
+      .filter(Column::RoomId.eq(room_id))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_room(db: &DbPool, room_id: &str) -> Result<Vec<IncomingWebhookModel>, sea_orm::DbErr> {
+  IncomingWebhookEntity::find()
+      .filter(Column::RoomId.eq(room_id))
+      .order_by_asc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, hook: IncomingWebhookModel) -> Result<IncomingWebhookModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(hook.id),
+    room_id: Set(hook.room_id),
+    bot_id: Set(hook.bot_id),
+    token_hash: Set(hook.token_hash),
+    created_at: Set(hook.created_at),
+  };
+  active_model.insert(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, hook: IncomingWebhookModel) -> Result<(), sea_orm::DbErr> {
+  let active_model: ActiveModel = hook.into();
+  active_model.delete(db).await?;
+  Ok(())
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Query, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::{
+    link_preview::{ActiveModel, Column, Entity as LinkPreviewEntity, Model as LinkPreviewModel},
+    message_link_preview::{
+      ActiveModel as MessageLinkPreviewActiveModel, Column as MessageLinkPreviewColumn,
+      Entity as MessageLinkPreviewEntity, Model as MessageLinkPreviewModel,
+    },
+  },
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_id(db: &DbPool, id: &str) -> Result<Option<LinkPreviewModel>, sea_orm::DbErr> {
+  LinkPreviewEntity::find_by_id(id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_ids(db: &DbPool, ids: Vec<String>) -> Result<Vec<LinkPreviewModel>, sea_orm::DbErr> {
+  if ids.is_empty() {
+      return Ok(Vec::new());
+  }
+  LinkPreviewEntity::find()
+      .filter(Column::Id.is_in(ids))
+      .all(db)
+      .await
+}
+
+// Insert a fresh preview or overwrite a stale cache entry for the same url.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn upsert(db: &DbPool, preview: LinkPreviewModel) -> Result<LinkPreviewModel, sea_orm::DbErr> {
+  let existing = LinkPreviewEntity::find_by_id(&preview.id).one(db).await?;
+
+  let active_model = ActiveModel {
+    id: Set(preview.id),
+    url: Set(preview.url),
+    title: Set(preview.title),
+    description: Set(preview.description),
+    image_url: Set(preview.image_url),
+    site_name: Set(preview.site_name),
+    status: Set(preview.status),
+    fetched_at: Set(preview.fetched_at),
+  };
+
+  match existing {
+    Some(_) => active_model.update(db).await,
+    None => active_model.insert(db).await,
+  }
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn attach_to_message(
+  db: &DbPool,
+  message_id: &str,
+  preview_id: &str,
+  position: i32,
+) -> Result<(), sea_orm::DbErr> {
+  let existing = MessageLinkPreviewEntity::find_by_id((message_id.to_string(), preview_id.to_string()))
+      .one(db)
+      .await?;
+  if existing.is_some() {
+      return Ok(());
+  }
+
+  MessageLinkPreviewActiveModel {
+    message_id: Set(message_id.to_string()),
+    preview_id: Set(preview_id.to_string()),
+    position: Set(position),
+  }
+  .insert(db)
+  .await?;
+  Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_links_for_messages(
+  db: &DbPool,
+  message_ids: Vec<String>,
+) -> Result<Vec<MessageLinkPreviewModel>, sea_orm::DbErr> {
+  if message_ids.is_empty() {
+      return Ok(Vec::new());
+  }
+  MessageLinkPreviewEntity::find()
+      .filter(MessageLinkPreviewColumn::MessageId.is_in(message_ids))
+      .order_by_asc(MessageLinkPreviewColumn::Position)
+      .all(db)
+      .await
+}
+
+// Drop cached previews no message links to any more. Recent ones are kept, since the unfurler
+// stores a preview before linking it.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete_orphaned(db: &DbPool, fetched_before: DateTime<Utc>) -> Result<u64, sea_orm::DbErr> {
+  let result = LinkPreviewEntity::delete_many()
+      .filter(Column::FetchedAt.lt(fetched_before))
+      .filter(
+        Column::Id.not_in_subquery(
+          Query::select()
+              .column(MessageLinkPreviewColumn::PreviewId)
+              .from(MessageLinkPreviewEntity)
+              .to_owned(),
+        ),
+      )
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected)
+}
@@ -0,0 +1,88 @@
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QuerySelect, Set};
+
+use crate::{
+  database::DbPool,
+  entities::message::{ActiveModel, Column, Entity as MessageEntity, Model as MessageModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_id(db: &DbPool, message_id: &str) -> Result<Option<MessageModel>, sea_orm::DbErr> {
+  MessageEntity::find_by_id(message_id).one(db).await
+}
+
+// Stores a message as given; callers are responsible for validation and fan-out.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, message: MessageModel) -> Result<MessageModel, sea_orm::DbErr> {
+  ActiveModel {
+    id: Set(message.id),
+    room_id: Set(message.room_id),
+    sender_id: Set(message.sender_id),
+    content: Set(message.content),
+    format: Set(message.format),
+    rendered_html: Set(message.rendered_html),
+    thread_id: Set(message.thread_id),
+    created_at: Set(message.created_at),
+  }
+  .insert(db)
+  .await
+}
+
+// Credits every message of `from` to `to`.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn reassign_sender(db: &DbPool, from: &str, to: &str) -> Result<u64, sea_orm::DbErr> {
+  let result = MessageEntity::update_many()
+    .col_expr(Column::SenderId, Expr::value(to))
+    .filter(Column::SenderId.eq(from))
+    .exec(db)
+    .await?;
+  Ok(result.rows_affected)
+}
+
+// Deletes up to `limit` messages of `sender_id` outside `excluded_room_ids`.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete_batch_by_sender(
+  db: &DbPool,
+  sender_id: &str,
+  excluded_room_ids: Vec<String>,
+  limit: u64,
+) -> Result<u64, sea_orm::DbErr> {
+  let ids: Vec<String> = MessageEntity::find()
+    .select_only()
+    .column(Column::Id)
+    .filter(Column::SenderId.eq(sender_id))
+    .filter(Column::RoomId.is_not_in(excluded_room_ids))
+    .limit(limit)
+    .into_tuple()
+    .all(db)
+    .await?;
+  if ids.is_empty() {
+    return Ok(0);
+  }
+
+  let result = MessageEntity::delete_many().filter(Column::Id.is_in(ids)).exec(db).await?;
+  Ok(result.rows_affected)
+}
+
+// Deletes up to `limit` messages of the room, only those created before `before` when given.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete_batch_in_room(
+  db: &DbPool,
+  room_id: &str,
+  before: Option<chrono::DateTime<chrono::Utc>>,
+  limit: u64,
+) -> Result<u64, sea_orm::DbErr> {
+  let mut query = MessageEntity::find()
+    .select_only()
+    .column(Column::Id)
+    .filter(Column::RoomId.eq(room_id));
+  if let Some(before) = before {
+    query = query.filter(Column::CreatedAt.lt(before));
+  }
+  let ids: Vec<String> = query.limit(limit).into_tuple().all(db).await?;
+  if ids.is_empty() {
+    return Ok(0);
+  }
+
+  let result = MessageEntity::delete_many().filter(Column::Id.is_in(ids)).exec(db).await?;
+  Ok(result.rows_affected)
+}
@@ -0,0 +1,101 @@
+use chrono::Utc;
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+
+use crate::{
+  database::DbPool,
+  entities::message_report::{ActiveModel, Column, Entity as MessageReportEntity, Model as MessageReportModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, report: MessageReportModel) -> Result<MessageReportModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(report.id),
+    room_id: Set(report.room_id),
+    message_id: Set(report.message_id),
+    reporter_id: Set(report.reporter_id),
+    author_id: Set(report.author_id),
+    reason: Set(report.reason),
+    content_snapshot: Set(report.content_snapshot),
+    format_snapshot: Set(report.format_snapshot),
+    message_created_at: Set(report.message_created_at),
+    status: Set(report.status),
+    resolution: Set(report.resolution),
+    resolved_by: Set(report.resolved_by),
+    resolved_at: Set(report.resolved_at),
+    created_at: Set(report.created_at),
+  };
+  active_model.insert(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_id(db: &DbPool, id: &str) -> Result<Option<MessageReportModel>, sea_orm::DbErr> {
+  MessageReportEntity::find_by_id(id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_message_and_reporter(
+  db: &DbPool,
+  message_id: &str,
+  reporter_id: &str,
+) -> Result<Option<MessageReportModel>, sea_orm::DbErr> {
+  MessageReportEntity::find()
+      .filter(Column::MessageId.eq(message_id))
+      .filter(Column::ReporterId.eq(reporter_id))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list(
+  db: &DbPool,
+  room_id: Option<&str>,
+  status: Option<&str>,
+  limit: u64,
+) -> Result<Vec<MessageReportModel>, sea_orm::DbErr> {
+  let mut select = MessageReportEntity::find();
+  if let Some(room_id) = room_id {
+      select = select.filter(Column::RoomId.eq(room_id));
+  }
+  if let Some(status) = status {
+      select = select.filter(Column::Status.eq(status));
+  }
+
+  select
+      .order_by_asc(Column::CreatedAt)
+      .limit(limit)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_open_for_message(
+  db: &DbPool,
+  message_id: &str,
+  open_status: &str,
+) -> Result<Vec<MessageReportModel>, sea_orm::DbErr> {
+  MessageReportEntity::find()
+      .filter(Column::MessageId.eq(message_id))
+      .filter(Column::Status.eq(open_status))
+      .all(db)
+      .await
+}
+
+// Close a batch of reports with the same outcome.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn resolve(
+  db: &DbPool,
+  ids: Vec<String>,
+  status: &str,
+  resolution: &str,
+  resolved_by: &str,
+) -> Result<(), sea_orm::DbErr> {
+  MessageReportEntity::update_many()
+      .col_expr(Column::Status, Expr::value(status))
+      .col_expr(Column::Resolution, Expr::value(resolution))
+      .col_expr(Column::ResolvedBy, Expr::value(resolved_by))
+      .col_expr(Column::ResolvedAt, Expr::value(Utc::now()))
+      .filter(Column::Id.is_in(ids))
+      .exec(db)
+      .await?;
+  Ok(())
+}
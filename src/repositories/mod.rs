@@ -1,3 +1,28 @@
 pub mod user;
 pub mod room;
 pub mod room_member;
+pub mod webhook_endpoint;
+pub mod notification;
+pub mod bot;
+pub mod incoming_webhook;
+pub mod outgoing_webhook;
+pub mod bot_command;
+pub mod link_preview;
+pub mod auth_token;
+pub mod user_totp;
+pub mod recovery_code;
+pub mod user_identity;
+pub mod oidc_login_state;
+pub mod audit_log;
+pub mod room_ban;
+pub mod room_mute;
+pub mod message_report;
+pub mod room_filter_setting;
+pub mod user_block;
+pub mod room_pin;
+pub mod saved_message;
+pub mod scheduled_job;
+pub mod poll;
+pub mod server_setting;
+pub mod import_mapping;
+pub mod message;
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+
+use crate::{
+  database::DbPool,
+  dtos::notification::NotificationStatus,
+  entities::notification::{ActiveModel, Column, Entity as NotificationEntity, Model as NotificationModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, notification: NotificationModel) -> Result<NotificationModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(notification.id),
+    user_id: Set(notification.user_id),
+    endpoint_id: Set(notification.endpoint_id),
+    room_id: Set(notification.room_id),
+    message_id: Set(notification.message_id),
+    kind: Set(notification.kind),
+    payload: Set(notification.payload),
+    status: Set(notification.status),
+    attempts: Set(notification.attempts),
+    next_attempt_at: Set(notification.next_attempt_at),
+    last_error: Set(notification.last_error),
+    created_at: Set(notification.created_at),
+    delivered_at: Set(notification.delivered_at),
+  };
+  active_model.insert(db).await
+}
+
+// Pending notifications whose next attempt is due, oldest first.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_due(
+  db: &DbPool,
+  now: DateTime<Utc>,
+  limit: u64,
+) -> Result<Vec<NotificationModel>, sea_orm::DbErr> {
+  NotificationEntity::find()
+      .filter(Column::Status.eq(NotificationStatus::Pending.as_str()))
+      .filter(Column::NextAttemptAt.lte(now))
+      .order_by_asc(Column::NextAttemptAt)
+      .limit(limit)
+      .all(db)
+      .await
+}
+
+// Lease a due notification to this instance by pushing its next attempt to `leased_until`.
+// Only one of several competing dispatchers gets `true`; if it dies mid-delivery the
+// notification becomes due again once the lease runs out.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn claim(
+  db: &DbPool,
+  id: &str,
+  now: DateTime<Utc>,
+  leased_until: DateTime<Utc>,
+) -> Result<bool, sea_orm::DbErr> {
+  let result = NotificationEntity::update_many()
+      .col_expr(Column::NextAttemptAt, Expr::value(leased_until))
+      .filter(Column::Id.eq(id))
+      .filter(Column::Status.eq(NotificationStatus::Pending.as_str()))
+      .filter(Column::NextAttemptAt.lte(now))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected == 1)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_user(
+  db: &DbPool,
+  user_id: &str,
+  limit: u64,
+) -> Result<Vec<NotificationModel>, sea_orm::DbErr> {
+  NotificationEntity::find()
+      .filter(Column::UserId.eq(user_id))
+      .order_by_desc(Column::CreatedAt)
+      .limit(limit)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn mark_delivered(
+  db: &DbPool,
+  notification: NotificationModel,
+  delivered_at: DateTime<Utc>,
+) -> Result<NotificationModel, sea_orm::DbErr> {
+  let attempts = notification.attempts + 1;
+  let mut active_model: ActiveModel = notification.into();
+  active_model.status = Set(NotificationStatus::Delivered.as_str().to_string());
+  active_model.attempts = Set(attempts);
+  active_model.last_error = Set(None);
+  active_model.delivered_at = Set(Some(delivered_at));
+  active_model.update(db).await
+}
+
+// Record a failed attempt; `next_attempt_at` of `None` moves the row to the dead-letter state.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn mark_failed(
+  db: &DbPool,
+  notification: NotificationModel,
+  error: String,
+  next_attempt_at: Option<DateTime<Utc>>,
+) -> Result<NotificationModel, sea_orm::DbErr> {
+  let attempts = notification.attempts + 1;
+  let mut active_model: ActiveModel = notification.into();
+  active_model.attempts = Set(attempts);
+  active_model.last_error = Set(Some(error));
+  match next_attempt_at {
+    Some(at) => active_model.next_attempt_at = Set(at),
+    None => active_model.status = Set(NotificationStatus::Dead.as_str().to_string()),
+  }
+  active_model.update(db).await
+}
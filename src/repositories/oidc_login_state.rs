@@ -0,0 +1,35 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+
+use crate::{
+  database::DbPool,
+  entities::oidc_login_state::{ActiveModel, Column, Entity as OidcLoginStateEntity, Model as OidcLoginStateModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, login_state: OidcLoginStateModel) -> Result<OidcLoginStateModel, sea_orm::DbErr> {
+  // Abandoned logins are never consumed, so sweep them whenever a new one starts.
+  OidcLoginStateEntity::delete_many()
+      .filter(Column::ExpiresAt.lt(Utc::now()))
+      .exec(db)
+      .await?;
+
+  let active_model = ActiveModel {
+    id: Set(login_state.id),
+    code_verifier: Set(login_state.code_verifier),
+    nonce: Set(login_state.nonce),
+    expires_at: Set(login_state.expires_at),
+    created_at: Set(login_state.created_at),
+  };
+  active_model.insert(db).await
+}
+
+// Fetch and delete in one go so a state value can only be redeemed once.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn take(db: &DbPool, id: &str) -> Result<Option<OidcLoginStateModel>, sea_orm::DbErr> {
+  let Some(login_state) = OidcLoginStateEntity::find_by_id(id).one(db).await? else {
+      return Ok(None);
+  };
+  let result = OidcLoginStateEntity::delete_by_id(id).exec(db).await?;
+  Ok((result.rows_affected == 1).then_some(login_state))
+}
@@ -0,0 +1,43 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::outgoing_webhook::{ActiveModel, Column, Entity as OutgoingWebhookEntity, Model as OutgoingWebhookModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_in_room(db: &DbPool, room_id: &str, id: &str) -> Result<Option<OutgoingWebhookModel>, sea_orm::DbErr> {
+  OutgoingWebhookEntity::find_by_id(id)
+      .filter(Column::RoomId.eq(room_id))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_room(db: &DbPool, room_id: &str) -> Result<Vec<OutgoingWebhookModel>, sea_orm::DbErr> {
+  OutgoingWebhookEntity::find()
+      .filter(Column::RoomId.eq(room_id))
+      .order_by_asc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, hook: OutgoingWebhookModel) -> Result<OutgoingWebhookModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(hook.id),
+    room_id: Set(hook.room_id),
+    bot_id: Set(hook.bot_id),
+    url: Set(hook.url),
+    secret: Set(hook.secret),
+    created_at: Set(hook.created_at),
+  };
+  active_model.insert(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, hook: OutgoingWebhookModel) -> Result<(), sea_orm::DbErr> {
+  let active_model: ActiveModel = hook.into();
+  active_model.delete(db).await?;
+  Ok(())
+}
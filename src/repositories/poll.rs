@@ -0,0 +1,130 @@
+use sea_orm::{
+  ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+
+use crate::{
+  database::DbPool,
+  entities::{
+    poll::{ActiveModel, Column, Entity as PollEntity, Model as PollModel},
+    poll_option::{
+      ActiveModel as PollOptionActiveModel, Column as PollOptionColumn, Entity as PollOptionEntity,
+      Model as PollOptionModel,
+    },
+    poll_vote::{
+      ActiveModel as PollVoteActiveModel, Column as PollVoteColumn, Entity as PollVoteEntity,
+      Model as PollVoteModel,
+    },
+  },
+};
+
+// Store a poll together with its options, or nothing at all.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, poll: PollModel, options: Vec<PollOptionModel>) -> Result<PollModel, sea_orm::DbErr> {
+  let txn = db.begin().await?;
+
+  let poll = ActiveModel {
+    message_id: Set(poll.message_id),
+    multiple_choice: Set(poll.multiple_choice),
+    anonymous: Set(poll.anonymous),
+    closes_at: Set(poll.closes_at),
+    created_at: Set(poll.created_at),
+  }
+  .insert(&txn)
+  .await?;
+
+  for option in options {
+      PollOptionActiveModel {
+        id: Set(option.id),
+        poll_id: Set(option.poll_id),
+        position: Set(option.position),
+        text: Set(option.text),
+      }
+      .insert(&txn)
+      .await?;
+  }
+
+  txn.commit().await?;
+  Ok(poll)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_message_id(db: &DbPool, message_id: &str) -> Result<Option<PollModel>, sea_orm::DbErr> {
+  PollEntity::find_by_id(message_id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_message_ids(db: &DbPool, message_ids: Vec<String>) -> Result<Vec<PollModel>, sea_orm::DbErr> {
+  if message_ids.is_empty() {
+      return Ok(Vec::new());
+  }
+  PollEntity::find()
+      .filter(Column::MessageId.is_in(message_ids))
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_options(db: &DbPool, poll_ids: Vec<String>) -> Result<Vec<PollOptionModel>, sea_orm::DbErr> {
+  if poll_ids.is_empty() {
+      return Ok(Vec::new());
+  }
+  PollOptionEntity::find()
+      .filter(PollOptionColumn::PollId.is_in(poll_ids))
+      .order_by_asc(PollOptionColumn::Position)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_votes(db: &DbPool, poll_ids: Vec<String>) -> Result<Vec<PollVoteModel>, sea_orm::DbErr> {
+  if poll_ids.is_empty() {
+      return Ok(Vec::new());
+  }
+  PollVoteEntity::find()
+      .filter(PollVoteColumn::PollId.is_in(poll_ids))
+      .order_by_asc(PollVoteColumn::CreatedAt)
+      .all(db)
+      .await
+}
+
+// Swap a user's votes on a poll for a new set in one transaction.
+// The primary key rejects a second concurrent ballot instead of letting both land.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn replace_votes(
+  db: &DbPool,
+  poll_id: &str,
+  user_id: &str,
+  votes: Vec<PollVoteModel>,
+) -> Result<(), sea_orm::DbErr> {
+  let txn = db.begin().await?;
+
+  PollVoteEntity::delete_many()
+      .filter(PollVoteColumn::PollId.eq(poll_id))
+      .filter(PollVoteColumn::UserId.eq(user_id))
+      .exec(&txn)
+      .await?;
+
+  for vote in votes {
+      PollVoteActiveModel {
+        poll_id: Set(vote.poll_id),
+        user_id: Set(vote.user_id),
+        slot: Set(vote.slot),
+        option_id: Set(vote.option_id),
+        created_at: Set(vote.created_at),
+      }
+      .insert(&txn)
+      .await?;
+  }
+
+  txn.commit().await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete_votes(db: &DbPool, poll_id: &str, user_id: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = PollVoteEntity::delete_many()
+      .filter(PollVoteColumn::PollId.eq(poll_id))
+      .filter(PollVoteColumn::UserId.eq(user_id))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected > 0)
+}
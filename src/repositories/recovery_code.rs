@@ -0,0 +1,47 @@
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Set, sea_query::Expr};
+use uuid::Uuid;
+
+use crate::{
+  database::DbPool,
+  entities::recovery_code::{ActiveModel, Column, Entity as RecoveryCodeEntity},
+};
+
+// Replace all of a user's recovery codes with a new set of hashes.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn replace_for_user(db: &DbPool, user_id: &str, code_hashes: Vec<String>) -> Result<(), sea_orm::DbErr> {
+  delete_for_user(db, user_id).await?;
+
+  let now = Utc::now();
+  let models = code_hashes.into_iter().map(|code_hash| ActiveModel {
+    id: Set(Uuid::new_v4().to_string()),
+    user_id: Set(user_id.to_string()),
+    code_hash: Set(code_hash),
+    used_at: Set(None),
+    created_at: Set(now),
+  });
+  RecoveryCodeEntity::insert_many(models).exec(db).await?;
+  Ok(())
+}
+
+// Burn an unused code. Returns false when it does not exist or was already used.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn consume(db: &DbPool, user_id: &str, code_hash: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = RecoveryCodeEntity::update_many()
+      .col_expr(Column::UsedAt, Expr::value(Utc::now()))
+      .filter(Column::UserId.eq(user_id))
+      .filter(Column::CodeHash.eq(code_hash))
+      .filter(Column::UsedAt.is_null())
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected == 1)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete_for_user(db: &DbPool, user_id: &str) -> Result<(), sea_orm::DbErr> {
+  RecoveryCodeEntity::delete_many()
+      .filter(Column::UserId.eq(user_id))
+      .exec(db)
+      .await?;
+  Ok(())
+}
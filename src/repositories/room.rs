@@ -1,11 +1,11 @@
 use sea_orm::{ActiveModelTrait, EntityTrait, Set};
-use uuid::Uuid;
 
 use crate::{
     database::DbPool,
     entities::room::{ActiveModel, Entity as RoomEntity, Model as RoomModel},
 };
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn find_by_id(db: &DbPool, room_id: &str) -> Result<RoomModel, sea_orm::DbErr> {
   RoomEntity::find_by_id(room_id)
     .one(db)
@@ -13,11 +13,18 @@ pub async fn find_by_id(db: &DbPool, room_id: &str) -> Result<RoomModel, sea_orm
     .ok_or_else(|| sea_orm::DbErr::RecordNotFound(format!("Room not found: {}", room_id)))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn insert(db: &DbPool, room: RoomModel) -> Result<RoomModel, sea_orm::DbErr> {
   let room_id = room.id.clone();
   let active_model = ActiveModel {
     id: Set(room.id.clone()),
     name: Set(room.name.clone()),
+    topic: Set(room.topic.clone()),
+    slow_mode_seconds: Set(room.slow_mode_seconds),
+    retention_max_age_days: Set(room.retention_max_age_days),
+    retention_max_messages: Set(room.retention_max_messages),
+    legal_hold: Set(room.legal_hold),
+    is_direct: Set(room.is_direct),
     created_at: Set(room.created_at),
   };
 
@@ -28,6 +35,7 @@ pub async fn insert(db: &DbPool, room: RoomModel) -> Result<RoomModel, sea_orm::
     .ok_or_else(|| sea_orm::DbErr::RecordNotFound(format!("Failed to find inserted room with id: {}", room_id)))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn delete(db: &DbPool, room_id: &str) -> Result<(), sea_orm::DbErr> {
   let room = RoomEntity::find_by_id(room_id)
       .one(db)
@@ -39,6 +47,41 @@ pub async fn delete(db: &DbPool, room_id: &str) -> Result<(), sea_orm::DbErr> {
   Ok(())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_topic(db: &DbPool, room: RoomModel, topic: Option<String>) -> Result<RoomModel, sea_orm::DbErr> {
+  let mut active_model: ActiveModel = room.into();
+  active_model.topic = Set(topic);
+  active_model.update(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_slow_mode(db: &DbPool, room: RoomModel, seconds: i32) -> Result<RoomModel, sea_orm::DbErr> {
+  let mut active_model: ActiveModel = room.into();
+  active_model.slow_mode_seconds = Set(seconds);
+  active_model.update(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn list_all(db: &DbPool) -> Result<Vec<RoomModel>, sea_orm::DbErr> {
   RoomEntity::find().all(db).await
-}
\ No newline at end of file
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_retention(
+  db: &DbPool,
+  room: RoomModel,
+  max_age_days: Option<i32>,
+  max_messages: Option<i32>,
+) -> Result<RoomModel, sea_orm::DbErr> {
+  let mut active_model: ActiveModel = room.into();
+  active_model.retention_max_age_days = Set(max_age_days);
+  active_model.retention_max_messages = Set(max_messages);
+  active_model.update(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_legal_hold(db: &DbPool, room: RoomModel, legal_hold: bool) -> Result<RoomModel, sea_orm::DbErr> {
+  let mut active_model: ActiveModel = room.into();
+  active_model.legal_hold = Set(legal_hold);
+  active_model.update(db).await
+}
@@ -0,0 +1,31 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+
+use crate::{
+  database::DbPool,
+  entities::room_filter_setting::{ActiveModel, Entity as RoomFilterSettingEntity, Model as RoomFilterSettingModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_room_id(db: &DbPool, room_id: &str) -> Result<Option<RoomFilterSettingModel>, sea_orm::DbErr> {
+  RoomFilterSettingEntity::find_by_id(room_id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn upsert(
+  db: &DbPool,
+  room_id: &str,
+  config: String,
+  updated_by: &str,
+) -> Result<RoomFilterSettingModel, sea_orm::DbErr> {
+  RoomFilterSettingEntity::delete_by_id(room_id).exec(db).await?;
+
+  ActiveModel {
+    room_id: Set(room_id.to_string()),
+    config: Set(config),
+    updated_by: Set(Some(updated_by.to_string())),
+    updated_at: Set(Utc::now()),
+  }
+  .insert(db)
+  .await
+}
@@ -1,5 +1,4 @@
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
-use uuid::Uuid;
 use chrono::Utc;
 
 use crate::{
@@ -7,6 +6,7 @@ use crate::{
   entities::room_member::{ActiveModel, Entity as RoomMemberEntity, Model as RoomMemberModel, Column},
 };
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn find_by_room_and_user(
   db: &DbPool,
   room_id: &str,
@@ -15,24 +15,30 @@ pub async fn find_by_room_and_user(
   RoomMemberEntity::find_by_id((room_id.to_string(), user_id.to_string()))
     .one(db)
     .await?
-    .ok_or_else(|| sea_orm::DbErr::RecordNotFound(format!("Room member not found:")))
+    .ok_or_else(|| sea_orm::DbErr::RecordNotFound("Room member not found:".to_string()))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn insert(
   db: &DbPool,
   room_id: String,
   user_id: String,
+  role: &str,
 ) -> Result<RoomMemberModel, sea_orm::DbErr> {
   let active_model = ActiveModel {
     room_id: Set(room_id.clone()),
     user_id: Set(user_id.clone()),
     joined_at: Set(Utc::now()),
+    notify_level: Set("mentions".to_string()),
+    notifications_muted: Set(false),
+    role: Set(role.to_string()),
   };
   
   let result = active_model.insert(db).await?;
   Ok(result)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn delete(
   db: &DbPool,
   room_id: &str,
@@ -48,6 +54,7 @@ pub async fn delete(
   Ok(())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn list_by_room(
   db: &DbPool,
   room_id: &str,
@@ -58,6 +65,7 @@ pub async fn list_by_room(
       .await
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn list_by_user(
   db: &DbPool,
   user_id: &str,
@@ -67,3 +75,27 @@ pub async fn list_by_user(
       .all(db)
       .await
 }
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_notify_settings(
+  db: &DbPool,
+  member: RoomMemberModel,
+  notify_level: String,
+  notifications_muted: bool,
+) -> Result<RoomMemberModel, sea_orm::DbErr> {
+  let mut active_model: ActiveModel = member.into();
+  active_model.notify_level = Set(notify_level);
+  active_model.notifications_muted = Set(notifications_muted);
+  active_model.update(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_role(
+  db: &DbPool,
+  member: RoomMemberModel,
+  role: &str,
+) -> Result<RoomMemberModel, sea_orm::DbErr> {
+  let mut active_model: ActiveModel = member.into();
+  active_model.role = Set(role.to_string());
+  active_model.update(db).await
+}
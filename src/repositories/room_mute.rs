@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::room_mute::{ActiveModel, Column, Entity as RoomMuteEntity, Model as RoomMuteModel},
+};
+
+// Entries whose expiry has passed are ignored rather than deleted.
+fn active(now: DateTime<Utc>) -> Condition {
+  Condition::any()
+      .add(Column::ExpiresAt.is_null())
+      .add(Column::ExpiresAt.gt(now))
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_active(db: &DbPool, room_id: &str, user_id: &str) -> Result<Option<RoomMuteModel>, sea_orm::DbErr> {
+  RoomMuteEntity::find_by_id((room_id.to_string(), user_id.to_string()))
+      .filter(active(Utc::now()))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_active_by_room(db: &DbPool, room_id: &str) -> Result<Vec<RoomMuteModel>, sea_orm::DbErr> {
+  RoomMuteEntity::find()
+      .filter(Column::RoomId.eq(room_id))
+      .filter(active(Utc::now()))
+      .order_by_desc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+// Replaces any previous (possibly expired) entry for the same member.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn upsert(
+  db: &DbPool,
+  room_id: &str,
+  user_id: &str,
+  created_by: &str,
+  reason: Option<String>,
+  expires_at: Option<DateTime<Utc>>,
+) -> Result<RoomMuteModel, sea_orm::DbErr> {
+  RoomMuteEntity::delete_by_id((room_id.to_string(), user_id.to_string())).exec(db).await?;
+
+  ActiveModel {
+    room_id: Set(room_id.to_string()),
+    user_id: Set(user_id.to_string()),
+    created_by: Set(Some(created_by.to_string())),
+    reason: Set(reason),
+    expires_at: Set(expires_at),
+    created_at: Set(Utc::now()),
+  }
+  .insert(db)
+  .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, room_id: &str, user_id: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = RoomMuteEntity::delete_by_id((room_id.to_string(), user_id.to_string())).exec(db).await?;
+  Ok(result.rows_affected > 0)
+}
@@ -0,0 +1,51 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::room_pin::{ActiveModel, Column, Entity as RoomPinEntity, Model as RoomPinModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find(db: &DbPool, room_id: &str, message_id: &str) -> Result<Option<RoomPinModel>, sea_orm::DbErr> {
+  RoomPinEntity::find_by_id((room_id.to_string(), message_id.to_string()))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn count_by_room(db: &DbPool, room_id: &str) -> Result<u64, sea_orm::DbErr> {
+  RoomPinEntity::find()
+      .filter(Column::RoomId.eq(room_id))
+      .count(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_room(db: &DbPool, room_id: &str) -> Result<Vec<RoomPinModel>, sea_orm::DbErr> {
+  RoomPinEntity::find()
+      .filter(Column::RoomId.eq(room_id))
+      .order_by_desc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, room_id: &str, message_id: &str, pinned_by: &str) -> Result<RoomPinModel, sea_orm::DbErr> {
+  ActiveModel {
+    room_id: Set(room_id.to_string()),
+    message_id: Set(message_id.to_string()),
+    pinned_by: Set(Some(pinned_by.to_string())),
+    created_at: Set(Utc::now()),
+  }
+  .insert(db)
+  .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, room_id: &str, message_id: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = RoomPinEntity::delete_by_id((room_id.to_string(), message_id.to_string()))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected > 0)
+}
@@ -0,0 +1,56 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+
+use crate::{
+  database::DbPool,
+  entities::saved_message::{ActiveModel, Column, Entity as SavedMessageEntity, Model as SavedMessageModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find(db: &DbPool, user_id: &str, message_id: &str) -> Result<Option<SavedMessageModel>, sea_orm::DbErr> {
+  SavedMessageEntity::find_by_id((user_id.to_string(), message_id.to_string()))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_user(db: &DbPool, user_id: &str, limit: u64) -> Result<Vec<SavedMessageModel>, sea_orm::DbErr> {
+  SavedMessageEntity::find()
+      .filter(Column::UserId.eq(user_id))
+      .order_by_desc(Column::CreatedAt)
+      .limit(limit)
+      .all(db)
+      .await
+}
+
+// Save a message, or update the note when it is already saved.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn upsert(
+  db: &DbPool,
+  user_id: &str,
+  message_id: &str,
+  note: Option<String>,
+) -> Result<SavedMessageModel, sea_orm::DbErr> {
+  if let Some(existing) = find(db, user_id, message_id).await? {
+      let mut active_model: ActiveModel = existing.into();
+      active_model.note = Set(note);
+      return active_model.update(db).await;
+  }
+
+  ActiveModel {
+    user_id: Set(user_id.to_string()),
+    message_id: Set(message_id.to_string()),
+    note: Set(note),
+    created_at: Set(Utc::now()),
+  }
+  .insert(db)
+  .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, user_id: &str, message_id: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = SavedMessageEntity::delete_by_id((user_id.to_string(), message_id.to_string()))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected > 0)
+}
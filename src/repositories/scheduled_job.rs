@@ -0,0 +1,162 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+  sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+use crate::{
+  database::DbPool,
+  dtos::schedule::JobStatus,
+  entities::scheduled_job::{ActiveModel, Column, Entity as ScheduledJobEntity, Model as ScheduledJobModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, job: ScheduledJobModel) -> Result<ScheduledJobModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(job.id),
+    kind: Set(job.kind),
+    user_id: Set(job.user_id),
+    room_id: Set(job.room_id),
+    message_id: Set(job.message_id),
+    content: Set(job.content),
+    format: Set(job.format),
+    note: Set(job.note),
+    run_at: Set(job.run_at),
+    status: Set(job.status),
+    attempts: Set(job.attempts),
+    locked_until: Set(job.locked_until),
+    last_error: Set(job.last_error),
+    created_at: Set(job.created_at),
+    completed_at: Set(job.completed_at),
+  };
+  active_model.insert(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_id(db: &DbPool, id: &str) -> Result<Option<ScheduledJobModel>, sea_orm::DbErr> {
+  ScheduledJobEntity::find_by_id(id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_user(
+  db: &DbPool,
+  user_id: &str,
+  kind: Option<&str>,
+  status: &str,
+) -> Result<Vec<ScheduledJobModel>, sea_orm::DbErr> {
+  let mut select = ScheduledJobEntity::find()
+      .filter(Column::UserId.eq(user_id))
+      .filter(Column::Status.eq(status));
+  if let Some(kind) = kind {
+      select = select.filter(Column::Kind.eq(kind));
+  }
+  select.order_by_asc(Column::RunAt).all(db).await
+}
+
+// Due jobs, plus running ones whose claim expired (their instance died mid-run).
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_claimable(db: &DbPool, now: DateTime<Utc>, limit: u64) -> Result<Vec<ScheduledJobModel>, sea_orm::DbErr> {
+  ScheduledJobEntity::find()
+      .filter(claimable(now))
+      .order_by_asc(Column::RunAt)
+      .limit(limit)
+      .all(db)
+      .await
+}
+
+fn claimable(now: DateTime<Utc>) -> Condition {
+  Condition::any()
+      .add(
+          Condition::all()
+              .add(Column::Status.eq(JobStatus::Pending.as_str()))
+              .add(Column::RunAt.lte(now)),
+      )
+      .add(
+          Condition::all()
+              .add(Column::Status.eq(JobStatus::Running.as_str()))
+              .add(Column::LockedUntil.lt(now)),
+      )
+}
+
+// Take a job for this instance. Only one of several competing schedulers gets `true`.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn claim(db: &DbPool, id: &str, now: DateTime<Utc>, locked_until: DateTime<Utc>) -> Result<bool, sea_orm::DbErr> {
+  let result = ScheduledJobEntity::update_many()
+      .col_expr(Column::Status, Expr::value(JobStatus::Running.as_str()))
+      .col_expr(Column::LockedUntil, Expr::value(locked_until))
+      .col_expr(Column::Attempts, Expr::col(Column::Attempts).add(1))
+      .filter(Column::Id.eq(id))
+      .filter(claimable(now))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected == 1)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn finish(db: &DbPool, id: &str, status: JobStatus, error: Option<String>) -> Result<(), sea_orm::DbErr> {
+  ScheduledJobEntity::update_many()
+      .col_expr(Column::Status, Expr::value(status.as_str()))
+      .col_expr(Column::LastError, Expr::value(error))
+      .col_expr(Column::LockedUntil, Expr::value(Option::<DateTime<Utc>>::None))
+      .col_expr(Column::CompletedAt, Expr::value(Utc::now()))
+      .filter(Column::Id.eq(id))
+      .exec(db)
+      .await?;
+  Ok(())
+}
+
+// Put a claimed job back in the queue for another attempt.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn reschedule(db: &DbPool, id: &str, run_at: DateTime<Utc>, error: String) -> Result<(), sea_orm::DbErr> {
+  ScheduledJobEntity::update_many()
+      .col_expr(Column::Status, Expr::value(JobStatus::Pending.as_str()))
+      .col_expr(Column::RunAt, Expr::value(run_at))
+      .col_expr(Column::LastError, Expr::value(error))
+      .col_expr(Column::LockedUntil, Expr::value(Option::<DateTime<Utc>>::None))
+      .filter(Column::Id.eq(id))
+      .exec(db)
+      .await?;
+  Ok(())
+}
+
+// Edit a job while it is still pending; a job claimed in the meantime is left alone.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_pending(
+  db: &DbPool,
+  id: &str,
+  user_id: &str,
+  run_at: Option<DateTime<Utc>>,
+  content: Option<String>,
+  note: Option<String>,
+) -> Result<(), sea_orm::DbErr> {
+  let mut update = ScheduledJobEntity::update_many();
+  if let Some(run_at) = run_at {
+      update = update.col_expr(Column::RunAt, Expr::value(run_at));
+  }
+  if let Some(content) = content {
+      update = update.col_expr(Column::Content, Expr::value(content));
+  }
+  if let Some(note) = note {
+      update = update.col_expr(Column::Note, Expr::value(note));
+  }
+
+  update
+      .filter(Column::Id.eq(id))
+      .filter(Column::UserId.eq(user_id))
+      .filter(Column::Status.eq(JobStatus::Pending.as_str()))
+      .exec(db)
+      .await?;
+  Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn cancel(db: &DbPool, id: &str, user_id: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = ScheduledJobEntity::update_many()
+      .col_expr(Column::Status, Expr::value(JobStatus::Cancelled.as_str()))
+      .col_expr(Column::CompletedAt, Expr::value(Utc::now()))
+      .filter(Column::Id.eq(id))
+      .filter(Column::UserId.eq(user_id))
+      .filter(Column::Status.eq(JobStatus::Pending.as_str()))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected == 1)
+}
@@ -0,0 +1,32 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+
+use crate::{
+  database::DbPool,
+  entities::server_setting::{ActiveModel, Entity as ServerSettingEntity, Model as ServerSettingModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find(db: &DbPool, name: &str) -> Result<Option<ServerSettingModel>, sea_orm::DbErr> {
+  ServerSettingEntity::find_by_id(name).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn upsert(
+  db: &DbPool,
+  name: &str,
+  value: String,
+  updated_by: &str,
+) -> Result<ServerSettingModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    name: Set(name.to_string()),
+    value: Set(value),
+    updated_by: Set(Some(updated_by.to_string())),
+    updated_at: Set(Utc::now()),
+  };
+
+  match find(db, name).await? {
+    Some(_) => active_model.update(db).await,
+    None => active_model.insert(db).await,
+  }
+}
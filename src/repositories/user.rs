@@ -1,12 +1,11 @@
-use sea_orm::{EntityTrait, QueryFilter, ColumnTrait, DbErr, ActiveModelTrait, Set, NotSet};
-use uuid::Uuid;
-use chrono::Utc;
+use sea_orm::{EntityTrait, QueryFilter, QueryOrder, QuerySelect, ColumnTrait, Condition, DbErr, ActiveModelTrait, Set, NotSet};
 
 use crate::{
     database::DbPool,
     entities::user::{Entity as UserEntity, Model as UserModel, ActiveModel, Column},
 };
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn find_by_email(db: &DbPool, email: &str) -> Result<Option<UserModel>, DbErr> {
     UserEntity::find()
     .filter(Column::Email.eq(email))
@@ -14,6 +13,7 @@ pub async fn find_by_email(db: &DbPool, email: &str) -> Result<Option<UserModel>
     .await
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn insert(db: &DbPool, user: UserModel) -> Result<UserModel, DbErr> {
     let user_id = user.id.clone();
     let active_model = ActiveModel {
@@ -22,6 +22,12 @@ pub async fn insert(db: &DbPool, user: UserModel) -> Result<UserModel, DbErr> {
         email: Set(user.email.clone()),
         password: Set(user.password.clone()),
         created_at: NotSet,
+        email_verified_at: Set(user.email_verified_at),
+        role: Set(user.role.clone()),
+        status: Set(user.status.clone()),
+        suspended_until: Set(user.suspended_until),
+        status_reason: Set(user.status_reason.clone()),
+        delete_after: Set(user.delete_after),
     };
     
     let _ = active_model.insert(db).await;
@@ -32,7 +38,157 @@ pub async fn insert(db: &DbPool, user: UserModel) -> Result<UserModel, DbErr> {
         .ok_or_else(|| DbErr::RecordNotFound(format!("Failed to find inserted user with id: {}", user_id)))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn list_all(db: &DbPool) -> Result<Vec<UserModel>, DbErr> {
     UserEntity::find().all(db).await
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_ids(db: &DbPool, user_ids: Vec<String>) -> Result<Vec<UserModel>, DbErr> {
+    if user_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    UserEntity::find()
+        .filter(Column::Id.is_in(user_ids))
+        .all(db)
+        .await
+}
+
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_id(db: &DbPool, user_id: &str) -> Result<Option<UserModel>, DbErr> {
+    UserEntity::find_by_id(user_id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, user_id: &str) -> Result<(), DbErr> {
+    let user = UserEntity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound(format!("User not found: {}", user_id)))?;
+
+    let active_model: ActiveModel = user.into();
+    active_model.delete(db).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_username(db: &DbPool, username: &str) -> Result<Vec<UserModel>, DbErr> {
+    UserEntity::find()
+        .filter(Column::Username.eq(username))
+        .all(db)
+        .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn mark_email_verified(db: &DbPool, user_id: &str) -> Result<(), DbErr> {
+    let active_model = ActiveModel {
+        id: Set(user_id.to_string()),
+        email_verified_at: Set(Some(chrono::Utc::now())),
+        ..Default::default()
+    };
+    active_model.update(db).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_password(db: &DbPool, user_id: &str, password_hash: String) -> Result<(), DbErr> {
+    let active_model = ActiveModel {
+        id: Set(user_id.to_string()),
+        password: Set(password_hash),
+        ..Default::default()
+    };
+    active_model.update(db).await?;
+    Ok(())
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn search(
+    db: &DbPool,
+    query: Option<&str>,
+    role: Option<&str>,
+    status: Option<&str>,
+    limit: u64,
+    offset: u64,
+) -> Result<Vec<UserModel>, DbErr> {
+    let mut select = UserEntity::find();
+    if let Some(query) = query {
+        select = select.filter(
+            Condition::any()
+                .add(Column::Username.contains(query))
+                .add(Column::Email.contains(query)),
+        );
+    }
+    if let Some(role) = role {
+        select = select.filter(Column::Role.eq(role));
+    }
+    if let Some(status) = status {
+        select = select.filter(Column::Status.eq(status));
+    }
+
+    select
+        .order_by_asc(Column::CreatedAt)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_status(
+    db: &DbPool,
+    user_id: &str,
+    status: &str,
+    suspended_until: Option<chrono::DateTime<chrono::Utc>>,
+    reason: Option<String>,
+) -> Result<UserModel, DbErr> {
+    let active_model = ActiveModel {
+        id: Set(user_id.to_string()),
+        status: Set(status.to_string()),
+        suspended_until: Set(suspended_until),
+        status_reason: Set(reason),
+        ..Default::default()
+    };
+    active_model.update(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_role(db: &DbPool, user_id: &str, role: &str) -> Result<UserModel, DbErr> {
+    let active_model = ActiveModel {
+        id: Set(user_id.to_string()),
+        role: Set(role.to_string()),
+        ..Default::default()
+    };
+    active_model.update(db).await
+}
+
+// Status and deletion date move together: scheduling a deletion and restoring an account.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn update_deletion(
+    db: &DbPool,
+    user_id: &str,
+    status: &str,
+    delete_after: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<UserModel, DbErr> {
+    let active_model = ActiveModel {
+        id: Set(user_id.to_string()),
+        status: Set(status.to_string()),
+        delete_after: Set(delete_after),
+        ..Default::default()
+    };
+    active_model.update(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_due_for_deletion(
+    db: &DbPool,
+    status: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<UserModel>, DbErr> {
+    UserEntity::find()
+        .filter(Column::Status.eq(status))
+        .filter(Column::DeleteAfter.lte(now))
+        .order_by_asc(Column::DeleteAfter)
+        .all(db)
+        .await
+}
@@ -0,0 +1,59 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::user_block::{ActiveModel, Column, Entity as UserBlockEntity, Model as UserBlockModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find(db: &DbPool, blocker_id: &str, blocked_id: &str) -> Result<Option<UserBlockModel>, sea_orm::DbErr> {
+  UserBlockEntity::find_by_id((blocker_id.to_string(), blocked_id.to_string()))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, blocker_id: &str, blocked_id: &str) -> Result<UserBlockModel, sea_orm::DbErr> {
+  ActiveModel {
+    blocker_id: Set(blocker_id.to_string()),
+    blocked_id: Set(blocked_id.to_string()),
+    created_at: Set(Utc::now()),
+  }
+  .insert(db)
+  .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, blocker_id: &str, blocked_id: &str) -> Result<bool, sea_orm::DbErr> {
+  let result = UserBlockEntity::delete_by_id((blocker_id.to_string(), blocked_id.to_string()))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected > 0)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_blocker(db: &DbPool, blocker_id: &str) -> Result<Vec<UserBlockModel>, sea_orm::DbErr> {
+  UserBlockEntity::find()
+      .filter(Column::BlockerId.eq(blocker_id))
+      .order_by_desc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+// Which of `blocker_ids` have blocked `blocked_id`, in one query.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_blockers_among(
+  db: &DbPool,
+  blocked_id: &str,
+  blocker_ids: Vec<String>,
+) -> Result<Vec<UserBlockModel>, sea_orm::DbErr> {
+  if blocker_ids.is_empty() {
+      return Ok(Vec::new());
+  }
+  UserBlockEntity::find()
+      .filter(Column::BlockedId.eq(blocked_id))
+      .filter(Column::BlockerId.is_in(blocker_ids))
+      .all(db)
+      .await
+}
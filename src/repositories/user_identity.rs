@@ -0,0 +1,31 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+
+use crate::{
+  database::DbPool,
+  entities::user_identity::{ActiveModel, Column, Entity as UserIdentityEntity, Model as UserIdentityModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_subject(
+  db: &DbPool,
+  issuer: &str,
+  subject: &str,
+) -> Result<Option<UserIdentityModel>, sea_orm::DbErr> {
+  UserIdentityEntity::find()
+      .filter(Column::Issuer.eq(issuer))
+      .filter(Column::Subject.eq(subject))
+      .one(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, identity: UserIdentityModel) -> Result<UserIdentityModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(identity.id),
+    user_id: Set(identity.user_id),
+    issuer: Set(identity.issuer),
+    subject: Set(identity.subject),
+    created_at: Set(identity.created_at),
+  };
+  active_model.insert(db).await
+}
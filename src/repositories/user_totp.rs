@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, Set};
+
+use crate::{
+  database::DbPool,
+  entities::user_totp::{ActiveModel, Column, Entity as UserTotpEntity, Model as UserTotpModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_user_id(db: &DbPool, user_id: &str) -> Result<Option<UserTotpModel>, sea_orm::DbErr> {
+  UserTotpEntity::find_by_id(user_id).one(db).await
+}
+
+// Start (or restart) an enrollment with a fresh, unconfirmed secret.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn replace(db: &DbPool, user_id: &str, secret: String) -> Result<UserTotpModel, sea_orm::DbErr> {
+  UserTotpEntity::delete_by_id(user_id).exec(db).await?;
+
+  ActiveModel {
+    user_id: Set(user_id.to_string()),
+    secret: Set(secret),
+    confirmed_at: Set(None),
+    last_used_step: Set(None),
+    failed_attempts: Set(0),
+    last_failed_at: Set(None),
+    created_at: Set(Utc::now()),
+  }
+  .insert(db)
+  .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn confirm(db: &DbPool, user_id: &str, step: i64) -> Result<(), sea_orm::DbErr> {
+  ActiveModel {
+    user_id: Set(user_id.to_string()),
+    confirmed_at: Set(Some(Utc::now())),
+    last_used_step: Set(Some(step)),
+    failed_attempts: Set(0),
+    last_failed_at: Set(None),
+    ..Default::default()
+  }
+  .update(db)
+  .await?;
+  Ok(())
+}
+
+// Spend a TOTP time step. Of two requests racing with the same code, only one gets `true`.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn use_step(db: &DbPool, user_id: &str, step: i64) -> Result<bool, sea_orm::DbErr> {
+  let result = UserTotpEntity::update_many()
+      .col_expr(Column::LastUsedStep, Expr::value(step))
+      .col_expr(Column::FailedAttempts, Expr::value(0))
+      .col_expr(Column::LastFailedAt, Expr::value(Option::<DateTime<Utc>>::None))
+      .filter(Column::UserId.eq(user_id))
+      .filter(
+          Condition::any()
+              .add(Column::LastUsedStep.is_null())
+              .add(Column::LastUsedStep.lt(step)),
+      )
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected == 1)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn record_success(db: &DbPool, user_id: &str) -> Result<(), sea_orm::DbErr> {
+  ActiveModel {
+    user_id: Set(user_id.to_string()),
+    failed_attempts: Set(0),
+    last_failed_at: Set(None),
+    ..Default::default()
+  }
+  .update(db)
+  .await?;
+  Ok(())
+}
+
+// Count an attempt before its code is checked, in one conditional UPDATE: the count restarts
+// once the last attempt is older than `window_start`, and nothing changes while `max_attempts`
+// are already counted in the window. Concurrent requests thus get at most `max_attempts` tries
+// between them. A valid code resets the count (see `use_step` and `record_success`).
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn claim_attempt(
+  db: &DbPool,
+  user_id: &str,
+  max_attempts: i32,
+  now: DateTime<Utc>,
+  window_start: DateTime<Utc>,
+) -> Result<bool, sea_orm::DbErr> {
+  let window_expired = || {
+    Condition::any()
+        .add(Column::LastFailedAt.is_null())
+        .add(Column::LastFailedAt.lte(window_start))
+  };
+  let result = UserTotpEntity::update_many()
+      .col_expr(
+          Column::FailedAttempts,
+          Expr::case(window_expired(), 1)
+              .finally(Expr::col(Column::FailedAttempts).add(1))
+              .into(),
+      )
+      .col_expr(Column::LastFailedAt, Expr::value(now))
+      .filter(Column::UserId.eq(user_id))
+      .filter(window_expired().add(Column::FailedAttempts.lt(max_attempts)))
+      .exec(db)
+      .await?;
+  Ok(result.rows_affected == 1)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, user_id: &str) -> Result<(), sea_orm::DbErr> {
+  UserTotpEntity::delete_by_id(user_id).exec(db).await?;
+  Ok(())
+}
@@ -0,0 +1,45 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::{
+  database::DbPool,
+  entities::webhook_endpoint::{ActiveModel, Column, Entity as WebhookEndpointEntity, Model as WebhookEndpointModel},
+};
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn find_by_id(db: &DbPool, id: &str) -> Result<Option<WebhookEndpointModel>, sea_orm::DbErr> {
+  WebhookEndpointEntity::find_by_id(id).one(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn list_by_user(db: &DbPool, user_id: &str) -> Result<Vec<WebhookEndpointModel>, sea_orm::DbErr> {
+  WebhookEndpointEntity::find()
+      .filter(Column::UserId.eq(user_id))
+      .order_by_asc(Column::CreatedAt)
+      .all(db)
+      .await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn insert(db: &DbPool, endpoint: WebhookEndpointModel) -> Result<WebhookEndpointModel, sea_orm::DbErr> {
+  let active_model = ActiveModel {
+    id: Set(endpoint.id),
+    user_id: Set(endpoint.user_id),
+    url: Set(endpoint.url),
+    secret: Set(endpoint.secret),
+    created_at: Set(endpoint.created_at),
+  };
+  active_model.insert(db).await
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn delete(db: &DbPool, id: &str, user_id: &str) -> Result<(), sea_orm::DbErr> {
+  let endpoint = WebhookEndpointEntity::find_by_id(id)
+      .filter(Column::UserId.eq(user_id))
+      .one(db)
+      .await?
+      .ok_or_else(|| sea_orm::DbErr::RecordNotFound(format!("Webhook not found: {}", id)))?;
+
+  let active_model: ActiveModel = endpoint.into();
+  active_model.delete(db).await?;
+  Ok(())
+}
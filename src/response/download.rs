@@ -0,0 +1,23 @@
+use axum::{
+  body::Body,
+  http::header,
+  response::{IntoResponse, Response},
+};
+
+// A file sent as an attachment; the body may still be produced while it is being sent.
+pub struct FileDownload {
+  pub content_type: &'static str,
+  pub file_name: String,
+  pub body: Body,
+}
+
+impl IntoResponse for FileDownload {
+  fn into_response(self) -> Response {
+    let disposition = format!("attachment; filename=\"{}\"", self.file_name);
+    (
+      [(header::CONTENT_TYPE, self.content_type.to_string()), (header::CONTENT_DISPOSITION, disposition)],
+      self.body,
+    )
+      .into_response()
+  }
+}
@@ -15,17 +15,37 @@ pub enum ApiError {
   Forbidden(String),
   #[error("Not found: {0}")]
   NotFound(String),
+  #[error("Too many requests: {0}")]
+  TooManyRequests(String),
+}
+
+impl ApiError {
+  // The text shown to clients. Internal details are logged (inside the request's span,
+  // so with its request id) and replaced by a generic message.
+  pub fn client_message(&self) -> String {
+    match self {
+      ApiError::InternalServerError(detail) => {
+        tracing::error!(error = %detail, "Internal server error");
+        "Internal server error".into()
+      }
+      other => other.to_string(),
+    }
+  }
 }
 
 // Centralized API error definitions and conversion into the unified JSON response format.
 impl IntoResponse for ApiError {
   fn into_response(self) -> Response {
     let (code, message) = match self {
-      ApiError::InternalServerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+      ApiError::InternalServerError(detail) => {
+        tracing::error!(error = %detail, "Internal server error");
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+      }
       ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
       ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
       ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
       ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+      ApiError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
     };
     (code, Json(ApiResponse::<()>::message(code, message))).into_response()
   }
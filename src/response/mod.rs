@@ -1,5 +1,8 @@
+#[allow(clippy::module_inception)]
 mod response;
 mod error;
+mod download;
 
 pub use error::ApiError;
+pub use download::FileDownload;
 pub use response::ApiResponse;
\ No newline at end of file
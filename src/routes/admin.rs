@@ -0,0 +1,23 @@
+use axum::{
+  routing::{delete, get, put},
+  Router,
+};
+
+use crate::{database::SharedState, handlers};
+
+pub fn router() -> Router<SharedState> {
+  Router::new()
+    .route("/admin/users", get(handlers::admin::list_users))
+    .route("/admin/users/:user_id", get(handlers::admin::get_user))
+    .route("/admin/users/:user_id/status", put(handlers::admin::update_user_status))
+    .route("/admin/users/:user_id/role", put(handlers::admin::update_user_role))
+    .route("/admin/rooms/:room_id", delete(handlers::admin::delete_room))
+    .route("/admin/rooms/:room_id/legal-hold", put(handlers::admin::update_legal_hold))
+    .route("/admin/messages/:message_id", delete(handlers::admin::delete_message))
+    .route("/admin/stats", get(handlers::admin::server_stats))
+    .route("/admin/audit-log", get(handlers::admin::list_audit_log))
+    .route("/admin/reports", get(handlers::admin::list_reports))
+    .route("/admin/retention", get(handlers::admin::get_retention).put(handlers::admin::update_retention))
+    .route("/admin/retention/preview", get(handlers::admin::preview_retention))
+    .route("/admin/account-deletion", get(handlers::admin::get_account_deletion).put(handlers::admin::update_account_deletion))
+}
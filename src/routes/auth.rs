@@ -1,9 +1,20 @@
-use axum::{routing::post, Router};
+use axum::{routing::{get, post}, Router};
 
 use crate::{database::SharedState, handlers};
 
 pub fn router() -> Router<SharedState> {
   Router::new()
+    .route("/.well-known/jwks.json", get(handlers::auth::jwks))
     .route("/auth/register", post(handlers::auth::register))
     .route("/auth/login", post(handlers::auth::login))
-}
\ No newline at end of file
+    .route("/auth/login/mfa", post(handlers::auth::login_mfa))
+    .route("/auth/restore", post(handlers::auth::restore_account))
+    .route("/auth/oidc/login", get(handlers::auth::oidc_login))
+    .route("/auth/oidc/callback", get(handlers::auth::oidc_callback))
+    .route("/auth/mfa/totp", post(handlers::auth::enroll_totp).delete(handlers::auth::disable_totp))
+    .route("/auth/mfa/totp/confirm", post(handlers::auth::confirm_totp))
+    .route("/auth/verify-email", post(handlers::auth::verify_email))
+    .route("/auth/verify-email/resend", post(handlers::auth::resend_verification))
+    .route("/auth/forgot-password", post(handlers::auth::forgot_password))
+    .route("/auth/reset-password", post(handlers::auth::reset_password))
+}
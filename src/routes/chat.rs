@@ -1,5 +1,5 @@
 use axum::{
-  routing::{get, post, delete},
+  routing::{get, post, put, delete},
   Router,
 };
 
@@ -12,6 +12,25 @@ pub fn router() -> Router<SharedState> {
     .route("/rooms", post(handlers::room::create_room).get(handlers::room::list_rooms))
     .route("/rooms/:room_id", get(handlers::room::get_room).delete(handlers::room::delete_room))
     .route("/rooms/:room_id/detail", get(handlers::room::get_room_detail))
+    .route("/rooms/:room_id/export", get(handlers::room::export_room))
     .route("/rooms/:room_id/members", post(handlers::room::add_member))
     .route("/rooms/:room_id/members/:user_id", delete(handlers::room::remove_member))
+    .route("/rooms/:room_id/members/:user_id/role", put(handlers::room::update_member_role))
+    .route("/rooms/:room_id/bans", get(handlers::room::list_bans).post(handlers::room::ban_member))
+    .route("/rooms/:room_id/bans/:user_id", delete(handlers::room::unban_member))
+    .route("/rooms/:room_id/mutes", get(handlers::room::list_mutes).post(handlers::room::mute_member))
+    .route("/rooms/:room_id/mutes/:user_id", delete(handlers::room::unmute_member))
+    .route("/rooms/:room_id/slow-mode", put(handlers::room::update_slow_mode))
+    .route("/rooms/:room_id/retention", put(handlers::room::update_retention))
+    .route("/rooms/:room_id/pins", get(handlers::chat::list_pins))
+    .route("/rooms/:room_id/pins/:message_id", put(handlers::chat::pin_message).delete(handlers::chat::unpin_message))
+    .route("/rooms/:room_id/polls", post(handlers::chat::create_poll))
+    .route("/messages/:message_id/poll/votes", put(handlers::chat::vote_poll).delete(handlers::chat::retract_poll_vote))
+    .route("/rooms/:room_id/scheduled", post(handlers::chat::schedule_message))
+    .route("/messages/:message_id/reminders", post(handlers::chat::create_reminder))
+    .route("/rooms/:room_id/filters", get(handlers::room::get_room_filters).put(handlers::room::update_room_filters))
+    .route("/rooms/:room_id/reports", get(handlers::report::list_room_reports))
+    .route("/messages/:message_id/reports", post(handlers::report::create_report))
+    .route("/reports/:report_id/resolve", post(handlers::report::resolve_report))
+    .route("/rooms/:room_id/notifications", put(handlers::notification::update_notify_settings))
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use axum::{
+  routing::{delete, get, post},
+  Router,
+};
+
+use crate::{database::SharedState, handlers};
+
+pub fn router() -> Router<SharedState> {
+  Router::new()
+    .route("/bots", get(handlers::integration::list_bots).post(handlers::integration::create_bot))
+    .route("/bots/:bot_id", delete(handlers::integration::delete_bot))
+    .route("/bots/:bot_id/commands", get(handlers::integration::list_bot_commands).post(handlers::integration::create_bot_command))
+    .route("/bots/:bot_id/commands/:command_id", delete(handlers::integration::delete_bot_command))
+    .route("/rooms/:room_id/hooks", get(handlers::integration::list_room_hooks))
+    .route("/rooms/:room_id/hooks/incoming", post(handlers::integration::create_incoming_webhook))
+    .route("/rooms/:room_id/hooks/outgoing", post(handlers::integration::create_outgoing_webhook))
+    .route("/rooms/:room_id/hooks/:hook_id", delete(handlers::integration::delete_room_hook))
+    .route("/hooks/:token", post(handlers::integration::post_incoming))
+}
@@ -0,0 +1,9 @@
+use axum::{routing::get, Router};
+
+use crate::{database::SharedState, handlers};
+
+// Kept out of `build`: it is only served on its own listener (`metrics.bind`).
+pub fn router() -> Router<SharedState> {
+  Router::new()
+    .route("/metrics", get(handlers::metrics::scrape))
+}
@@ -1,5 +1,8 @@
+pub mod admin;
 pub mod auth;
 pub mod chat;
+pub mod integration;
+pub mod metrics;
 pub mod user;
 
 use axum::Router;
@@ -7,7 +10,9 @@ use crate::database::SharedState;
 
 pub fn build() -> Router<SharedState> {
   Router::new()
+      .merge(admin::router())
       .merge(auth::router())
       .merge(chat::router())
+      .merge(integration::router())
       .merge(user::router())
-}
\ No newline at end of file
+}
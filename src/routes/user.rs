@@ -1,5 +1,5 @@
 use axum::{
-  routing::get,
+  routing::{delete, get, patch},
   Router,
 };
 
@@ -8,4 +8,15 @@ use crate::{database::SharedState, handlers};
 pub fn router() -> Router<SharedState> {
   Router::new()
     .route("/users", get(handlers::user::list_all_users))
+    .route("/users/me", delete(handlers::user::delete_account))
+    .route("/users/me/webhooks", get(handlers::notification::list_webhooks).post(handlers::notification::create_webhook))
+    .route("/users/me/webhooks/:webhook_id", delete(handlers::notification::delete_webhook))
+    .route("/users/me/notifications", get(handlers::notification::list_notifications))
+    .route("/users/me/blocks", get(handlers::user::list_blocks).post(handlers::user::block_user))
+    .route("/users/me/blocks/:user_id", delete(handlers::user::unblock_user))
+    .route("/users/me/saved", get(handlers::user::list_saved).post(handlers::user::save_message))
+    .route("/users/me/saved/:message_id", delete(handlers::user::unsave_message))
+    .route("/users/me/export", get(handlers::user::export_account))
+    .route("/users/me/scheduled", get(handlers::user::list_scheduled))
+    .route("/users/me/scheduled/:job_id", patch(handlers::user::update_scheduled).delete(handlers::user::cancel_scheduled))
 }
\ No newline at end of file
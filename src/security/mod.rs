@@ -1,5 +1,10 @@
 pub mod jwt;
 pub mod password;
+pub mod signature;
+pub mod token;
+pub mod totp;
 
 pub use jwt::JwtManager;
-pub use password::{hash_password, verify_password};
\ No newline at end of file
+pub use password::{hash_password, verify_dummy_password, verify_password};
+pub use signature::{generate_secret, sign_payload};
+pub use token::{generate_token, hash_token};
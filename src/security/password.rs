@@ -1,5 +1,10 @@
 use argon2::{password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString}, Argon2};
 use rand_core::OsRng;
+use std::sync::LazyLock;
+
+// Made with the same parameters as real hashes, so checking against it costs the same.
+static DUMMY_HASH: LazyLock<String> =
+    LazyLock::new(|| hash_password("dummy password").expect("hashing a constant cannot fail"));
 
 pub fn hash_password(raw: &str) -> anyhow::Result<String> {
   let salt = SaltString::generate(&mut OsRng);
@@ -14,4 +19,10 @@ pub fn verify_password(raw: &str, hash: &str) -> anyhow::Result<bool> {
   let parsed_hash = PasswordHash::new(hash)
       .map_err(|e| anyhow::anyhow!("Failed to parse password hash: {}", e))?;
   Ok(Argon2::default().verify_password(raw.as_bytes(), &parsed_hash).is_ok())
-}
\ No newline at end of file
+}
+
+// Does the work of `verify_password` for an account that does not exist, so a login for an unknown
+// email takes as long as one with a wrong password.
+pub fn verify_dummy_password(raw: &str) {
+  let _ = verify_password(raw, &DUMMY_HASH);
+}
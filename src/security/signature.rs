@@ -0,0 +1,22 @@
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Random hex secret shared with a webhook receiver.
+pub fn generate_secret() -> String {
+  let mut bytes = [0u8; 32];
+  OsRng.fill_bytes(&mut bytes);
+  hex::encode(bytes)
+}
+
+// Sign `{timestamp}.{body}` so receivers can reject replayed or tampered payloads.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+  let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+      .expect("HMAC accepts keys of any length");
+  mac.update(timestamp.to_string().as_bytes());
+  mac.update(b".");
+  mac.update(body.as_bytes());
+  format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
@@ -0,0 +1,14 @@
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// Random opaque token, prefixed so leaked values are easy to identify.
+pub fn generate_token(prefix: &str) -> String {
+  let mut bytes = [0u8; 32];
+  OsRng.fill_bytes(&mut bytes);
+  format!("{}_{}", prefix, hex::encode(bytes))
+}
+
+// Tokens are stored as SHA-256 digests; they carry enough entropy that a slow hash is unnecessary.
+pub fn hash_token(raw: &str) -> String {
+  hex::encode(Sha256::digest(raw.as_bytes()))
+}
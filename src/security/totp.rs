@@ -0,0 +1,171 @@
+use aes_gcm::{
+  aead::{Aead, Payload},
+  Aes256Gcm, Nonce,
+};
+use data_encoding::{BASE32_NOPAD, BASE64};
+use hmac::{Hmac, Mac};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use rand_core::{OsRng, RngCore};
+use sha1::Sha1;
+
+type HmacSha1 = Hmac<Sha1>;
+
+// RFC 6238 defaults, which is what every authenticator app expects.
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+// Accept one step of clock drift in either direction.
+const ALLOWED_DRIFT: i64 = 1;
+// Encrypted secrets carry this prefix; rows written without a key hold the bare base32 secret.
+const SEALED_PREFIX: &str = "v1:";
+const NONCE_LEN: usize = 12;
+
+// 160-bit secret, base32 encoded for authenticator apps.
+pub fn generate_totp_secret() -> String {
+  let mut bytes = [0u8; 20];
+  OsRng.fill_bytes(&mut bytes);
+  BASE32_NOPAD.encode(&bytes)
+}
+
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+  let issuer = utf8_percent_encode(issuer, NON_ALPHANUMERIC).to_string();
+  let account = utf8_percent_encode(account, NON_ALPHANUMERIC).to_string();
+  format!(
+      "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECONDS}"
+  )
+}
+
+// Returns the time step the code matched so callers can refuse to accept it twice.
+pub fn verify_totp(secret: &str, code: &str, unix_time: i64) -> Option<i64> {
+  let code = code.trim();
+  if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+      return None;
+  }
+  let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+  let current = unix_time.div_euclid(STEP_SECONDS);
+
+  (current - ALLOWED_DRIFT..=current + ALLOWED_DRIFT)
+      .find(|&step| step >= 0 && hotp(&key, step as u64) == code)
+}
+
+// The code an authenticator shows at `unix_time`.
+#[cfg(test)]
+pub(crate) fn code_at(secret: &str, unix_time: i64) -> String {
+  let key = BASE32_NOPAD.decode(secret.as_bytes()).expect("valid base32 secret");
+  hotp(&key, unix_time.div_euclid(STEP_SECONDS) as u64)
+}
+
+fn hotp(key: &[u8], counter: u64) -> String {
+  let mut mac = HmacSha1::new_from_slice(key).expect("HMAC accepts keys of any length");
+  mac.update(&counter.to_be_bytes());
+  let digest = mac.finalize().into_bytes();
+
+  let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+  let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+      & 0x7fff_ffff;
+  format!("{:0width$}", binary % 10u32.pow(DIGITS), width = DIGITS as usize)
+}
+
+// One-time recovery code such as `3f9a-c21e-77b0`, easy to read out and type.
+pub fn generate_recovery_code() -> String {
+  let mut bytes = [0u8; 6];
+  OsRng.fill_bytes(&mut bytes);
+  let hex = hex::encode(bytes);
+  format!("{}-{}-{}", &hex[0..4], &hex[4..8], &hex[8..12])
+}
+
+// Recovery codes are compared case-insensitively and without separators.
+pub fn normalize_recovery_code(code: &str) -> String {
+  code.chars()
+      .filter(|c| c.is_ascii_alphanumeric())
+      .map(|c| c.to_ascii_lowercase())
+      .collect()
+}
+
+// Encrypt a TOTP secret for storage with AES-256-GCM. The user id is authenticated with it, so a
+// sealed secret copied into another account's row does not open.
+pub fn seal_secret(key: &[u8; 32], user_id: &str, secret: &str) -> anyhow::Result<String> {
+  let mut nonce = [0u8; NONCE_LEN];
+  OsRng.fill_bytes(&mut nonce);
+  let ciphertext = cipher(key)
+      .encrypt(&Nonce::from(nonce), Payload { msg: secret.as_bytes(), aad: user_id.as_bytes() })
+      .map_err(|_| anyhow::anyhow!("Failed to encrypt the TOTP secret"))?;
+  Ok(format!("{}{}", SEALED_PREFIX, BASE64.encode(&[nonce.as_slice(), &ciphertext].concat())))
+}
+
+// Reverse of `seal_secret`. Secrets stored before a key was configured pass through unchanged.
+pub fn open_secret(key: Option<&[u8; 32]>, user_id: &str, stored: &str) -> anyhow::Result<String> {
+  let Some(sealed) = stored.strip_prefix(SEALED_PREFIX) else {
+      return Ok(stored.to_string());
+  };
+  let key = key.ok_or_else(|| anyhow::anyhow!("TOTP secret is encrypted but no encryption key is configured"))?;
+  let bytes = BASE64.decode(sealed.as_bytes())?;
+  if bytes.len() < NONCE_LEN {
+      anyhow::bail!("Encrypted TOTP secret is truncated");
+  }
+  let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+  let nonce: [u8; NONCE_LEN] = nonce.try_into()?;
+  let secret = cipher(key)
+      .decrypt(&Nonce::from(nonce), Payload { msg: ciphertext, aad: user_id.as_bytes() })
+      .map_err(|_| anyhow::anyhow!("Failed to decrypt the TOTP secret"))?;
+  Ok(String::from_utf8(secret)?)
+}
+
+// `KeyInit` is not imported: its `new_from_slice` would clash with the HMAC one above.
+fn cipher(key: &[u8; 32]) -> Aes256Gcm {
+  <Aes256Gcm as aes_gcm::KeyInit>::new(key.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // RFC 6238 appendix B secret ("12345678901234567890"), truncated to six digits.
+  const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+  #[test]
+  fn matches_rfc_6238_vectors() {
+    assert_eq!(verify_totp(RFC_SECRET, "287082", 59), Some(1));
+    assert_eq!(verify_totp(RFC_SECRET, "081804", 1111111109), Some(1111111109 / 30));
+    assert_eq!(verify_totp(RFC_SECRET, "005924", 1234567890), Some(1234567890 / 30));
+  }
+
+  #[test]
+  fn accepts_one_step_of_drift_only() {
+    let secret = generate_totp_secret();
+    let now = 1_700_000_000;
+    assert!(verify_totp(&secret, &code_at(&secret, now - 30), now).is_some());
+    assert!(verify_totp(&secret, &code_at(&secret, now + 30), now).is_some());
+    assert!(verify_totp(&secret, &code_at(&secret, now - 90), now).is_none());
+  }
+
+  #[test]
+  fn rejects_malformed_codes() {
+    assert_eq!(verify_totp(RFC_SECRET, "28708", 59), None);
+    assert_eq!(verify_totp(RFC_SECRET, "28708a", 59), None);
+  }
+
+  #[test]
+  fn recovery_codes_normalize() {
+    assert_eq!(normalize_recovery_code(" 3F9A-c21e 77b0 "), "3f9ac21e77b0");
+    assert_eq!(normalize_recovery_code(&generate_recovery_code()).len(), 12);
+  }
+
+  #[test]
+  fn sealed_secrets_open_only_for_their_user_and_key() {
+    let (key, other_key) = ([7u8; 32], [8u8; 32]);
+    let secret = generate_totp_secret();
+
+    let sealed = seal_secret(&key, "user-1", &secret).unwrap();
+    assert!(!sealed.contains(&secret));
+    assert_eq!(open_secret(Some(&key), "user-1", &sealed).unwrap(), secret);
+    assert!(open_secret(Some(&key), "user-2", &sealed).is_err());
+    assert!(open_secret(Some(&other_key), "user-1", &sealed).is_err());
+    assert!(open_secret(None, "user-1", &sealed).is_err());
+  }
+
+  #[test]
+  fn plain_secrets_pass_through() {
+    assert_eq!(open_secret(Some(&[7u8; 32]), "user-1", RFC_SECRET).unwrap(), RFC_SECRET);
+    assert_eq!(open_secret(None, "user-1", RFC_SECRET).unwrap(), RFC_SECRET);
+  }
+}
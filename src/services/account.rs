@@ -0,0 +1,427 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde_json::json;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::{
+  database::{AppState, SharedState},
+  dtos::{
+    admin::{AccountDeletionPolicy, AccountDeletionPolicyResponse, AccountStatus, DeletedMessagePolicy, UserRole},
+    auth::{LoginResponse, RestoreAccountRequest},
+    chat::{EphemeralDto, MemberEventDto, WsOutboundMessage},
+    room::RoomRole,
+    user::{AccountDeletionResponse, DeleteAccountRequest},
+  },
+  entities::user::{Column as UserColumn, Entity as UserEntity, Model as UserModel},
+  repositories::{
+    bot as bot_repo, message as message_repo, room as room_repo, room_member as member_repo, server_setting as setting_repo,
+    user as user_repo,
+  },
+  response::ApiError,
+  security::{generate_token, hash_password, verify_password},
+  services::{admin, auth},
+};
+
+const SETTING_NAME: &str = "account_deletion";
+const SWEEP_INTERVAL: Duration = Duration::from_secs(3600);
+const DELETE_BATCH_SIZE: u64 = 500;
+// Shared author of the messages that outlive their account.
+const GHOST_USER_ID: &str = "00000000-0000-0000-0000-000000000000";
+
+pub async fn get_deletion_policy(state: &AppState) -> Result<AccountDeletionPolicyResponse, ApiError> {
+  let setting = setting_repo::find(&state.db, SETTING_NAME).await?;
+  Ok(match setting {
+      Some(setting) => AccountDeletionPolicyResponse {
+          policy: serde_json::from_str(&setting.value)
+              .map_err(|e| ApiError::InternalServerError(format!("Invalid account deletion settings: {}", e)))?,
+          updated_at: Some(setting.updated_at),
+      },
+      None => AccountDeletionPolicyResponse { policy: AccountDeletionPolicy::default(), updated_at: None },
+  })
+}
+
+pub async fn update_deletion_policy(
+  state: &AppState,
+  admin_id: Uuid,
+  policy: AccountDeletionPolicy,
+) -> Result<AccountDeletionPolicyResponse, ApiError> {
+  let value = serde_json::to_string(&policy).map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+  let setting = setting_repo::upsert(&state.db, SETTING_NAME, value, &admin_id.to_string()).await?;
+
+  admin::record(state, Some(admin_id), "account_deletion.updated", "server", None, json!(policy)).await;
+  Ok(AccountDeletionPolicyResponse { policy, updated_at: Some(setting.updated_at) })
+}
+
+// Locks the account right away; the data goes once the grace period is over.
+pub async fn request_deletion(
+  state: &AppState,
+  user_id: Uuid,
+  req: DeleteAccountRequest,
+) -> Result<AccountDeletionResponse, ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+  if !verify_password(&req.password, &user.password).context("Failed to verify password")? {
+      return Err(ApiError::Unauthorized("Incorrect password".into()));
+  }
+  ensure_not_last_admin(state, &user).await?;
+
+  let policy = get_deletion_policy(state).await?.policy;
+  let delete_after = Utc::now() + chrono::Duration::days(i64::from(policy.grace_days));
+  let user = user_repo::update_deletion(
+      &state.db,
+      &user.id,
+      AccountStatus::PendingDeletion.as_str(),
+      Some(delete_after),
+  )
+  .await?;
+
+  // Session tokens are rejected by the account guard from now on; open sockets go now.
+  state.presence.kick(user_id);
+  admin::record(
+      state,
+      Some(user_id),
+      "user.deletion_requested",
+      "user",
+      Some(user.id.clone()),
+      json!({ "delete_after": delete_after }),
+  )
+  .await;
+
+  if policy.grace_days == 0 {
+      finalize(state, &user, policy.messages).await?;
+  }
+  Ok(AccountDeletionResponse { delete_after })
+}
+
+// Signing in during the grace period is the only way back, so it takes the credentials directly.
+pub async fn restore(state: &AppState, req: RestoreAccountRequest) -> Result<LoginResponse, ApiError> {
+  let user = user_repo::find_by_email(&state.db, &req.email)
+      .await?
+      .ok_or_else(|| ApiError::Unauthorized("Invalid credentials".into()))?;
+  if !verify_password(&req.password, &user.password).context("Failed to verify password")? {
+      return Err(ApiError::Unauthorized("Invalid credentials".into()));
+  }
+  if AccountStatus::parse(&user.status) != Some(AccountStatus::PendingDeletion)
+      || user.delete_after.is_some_and(|at| at <= Utc::now())
+  {
+      return Err(ApiError::BadRequest("This account is not scheduled for deletion".into()));
+  }
+
+  let user = user_repo::update_deletion(&state.db, &user.id, AccountStatus::Active.as_str(), None).await?;
+  admin::record(state, Uuid::parse_str(&user.id).ok(), "user.deletion_cancelled", "user", Some(user.id.clone()), json!({})).await;
+
+  auth::issue_login(state, &user).await
+}
+
+// Skips the grace period; for operators removing an account outright.
+pub async fn delete_now(state: &AppState, user_id: Uuid) -> Result<(), ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+  if user.id == GHOST_USER_ID {
+      return Err(ApiError::BadRequest("The deleted-user account holds anonymized messages".into()));
+  }
+  let policy = get_deletion_policy(state).await?.policy;
+  finalize(state, &user, policy.messages).await
+}
+
+// Background task finishing deletions whose grace period is over.
+pub fn spawn_deletion_sweeper(state: SharedState) -> tokio::task::JoinHandle<()> {
+  tokio::spawn(async move {
+    let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = sweep(state.as_ref()).await {
+            tracing::error!(error = %e, "Account deletion sweep failed");
+        }
+    }
+  }
+  .instrument(tracing::info_span!("deletion_sweeper")))
+}
+
+async fn sweep(state: &AppState) -> Result<(), ApiError> {
+  let policy = get_deletion_policy(state).await?.policy;
+  let due = user_repo::list_due_for_deletion(&state.db, AccountStatus::PendingDeletion.as_str(), Utc::now()).await?;
+
+  for user in due {
+      // One stuck account shouldn't hold up the rest.
+      if let Err(e) = finalize(state, &user, policy.messages).await {
+          tracing::error!(user_id = %user.id, error = %e, "Failed to delete account");
+      }
+  }
+  Ok(())
+}
+
+// Hands off owned rooms, leaves every room, deals with the messages, then drops the account row
+// and everything that cascades from it (tokens, identities, 2FA, blocks, saved messages, jobs).
+async fn finalize(state: &AppState, user: &UserModel, messages: DeletedMessagePolicy) -> Result<(), ApiError> {
+  let user_id = parse_id(&user.id)?;
+
+  for membership in member_repo::list_by_user(&state.db, &user.id).await? {
+      let room_id = parse_id(&membership.room_id)?;
+      if RoomRole::parse(&membership.role) == Some(RoomRole::Owner) {
+          hand_off_ownership(state, room_id, &user.id, "The owner of this room deleted their account; you are the owner now")
+              .await?;
+      }
+      member_repo::delete(&state.db, &membership.room_id, &user.id).await?;
+      let _ = state.chat_tx.send(WsOutboundMessage::MemberRemoved(MemberEventDto { room_id, user_id }));
+  }
+
+  let mut deleted = 0;
+  if messages == DeletedMessagePolicy::Delete {
+      let held: Vec<String> = room_repo::list_all(&state.db)
+          .await?
+          .into_iter()
+          .filter(|room| room.legal_hold)
+          .map(|room| room.id)
+          .collect();
+      loop {
+          let batch = message_repo::delete_batch_by_sender(&state.db, &user.id, held.clone(), DELETE_BATCH_SIZE).await?;
+          deleted += batch;
+          if batch < DELETE_BATCH_SIZE {
+              break;
+          }
+      }
+  }
+  // Whatever is left (everything when anonymizing) must move before the account row can go.
+  let anonymized = anonymize_messages(state, &user.id).await?;
+
+  // Bots can't outlive their owner; their messages stay like the owner's would.
+  for bot in bot_repo::list_by_owner(&state.db, &user.id).await? {
+      anonymize_messages(state, &bot.user_id).await?;
+      user_repo::delete(&state.db, &bot.user_id).await?;
+  }
+
+  user_repo::delete(&state.db, &user.id).await?;
+  admin::record(
+      state,
+      None,
+      "user.deleted",
+      "user",
+      Some(user.id.clone()),
+      json!({ "messages_deleted": deleted, "messages_anonymized": anonymized }),
+  )
+  .await;
+  Ok(())
+}
+
+// The longest-serving moderator takes over, or the longest-serving member if there is none.
+pub(crate) async fn hand_off_ownership(state: &AppState, room_id: Uuid, owner_id: &str, notice: &str) -> Result<(), ApiError> {
+  let members = member_repo::list_by_room(&state.db, &room_id.to_string()).await?;
+  if members.iter().any(|m| m.user_id != owner_id && RoomRole::parse(&m.role) == Some(RoomRole::Owner)) {
+      return Ok(());
+  }
+  let candidate_ids = members.iter().filter(|m| m.user_id != owner_id).map(|m| m.user_id.clone()).collect();
+  let leaving: Vec<String> = user_repo::list_by_ids(&state.db, candidate_ids)
+      .await?
+      .into_iter()
+      .filter(|u| AccountStatus::parse(&u.status) == Some(AccountStatus::PendingDeletion))
+      .map(|u| u.id)
+      .collect();
+
+  let successor = members
+      .into_iter()
+      .filter(|m| m.user_id != owner_id && !leaving.contains(&m.user_id))
+      .min_by_key(|m| (RoomRole::parse(&m.role) != Some(RoomRole::Moderator), m.joined_at));
+  let Some(successor) = successor else {
+      // Nobody left to take over; the room stays for admins to deal with.
+      return Ok(());
+  };
+
+  let successor_id = successor.user_id.clone();
+  member_repo::update_role(&state.db, successor, RoomRole::Owner.as_str()).await?;
+  let _ = state.chat_tx.send(WsOutboundMessage::Ephemeral(EphemeralDto {
+      room_id,
+      user_id: parse_id(&successor_id)?,
+      content: notice.to_string(),
+  }));
+  admin::record(
+      state,
+      None,
+      "room.owner_handed_off",
+      "room",
+      Some(room_id.to_string()),
+      json!({ "from": owner_id, "to": successor_id }),
+  )
+  .await;
+  Ok(())
+}
+
+// Credits the account's messages to the shared deleted-user account so the account row can be deleted.
+pub async fn anonymize_messages(state: &AppState, user_id: &str) -> Result<u64, ApiError> {
+  let ghost = ensure_ghost_user(state).await?;
+  Ok(message_repo::reassign_sender(&state.db, user_id, &ghost.id).await?)
+}
+
+// Can't sign in: banned, and its password hash matches no password anyone knows.
+async fn ensure_ghost_user(state: &AppState) -> Result<UserModel, ApiError> {
+  if let Some(ghost) = user_repo::find_by_id(&state.db, GHOST_USER_ID).await? {
+      return Ok(ghost);
+  }
+  let password = hash_password(&generate_token("ghost")).context("Failed to hash password")?;
+  let ghost = user_repo::insert(&state.db, UserModel {
+      id: GHOST_USER_ID.to_string(),
+      username: "deleted-user".to_string(),
+      email: "deleted-user@ghost.invalid".to_string(),
+      password,
+      created_at: Utc::now(),
+      email_verified_at: None,
+      role: UserRole::User.as_str().to_string(),
+      status: AccountStatus::Banned.as_str().to_string(),
+      suspended_until: None,
+      status_reason: Some("Author of messages from deleted accounts".to_string()),
+      delete_after: None,
+  })
+  .await?;
+  Ok(ghost)
+}
+
+// A server must keep at least one administrator who can sign in.
+async fn ensure_not_last_admin(state: &AppState, user: &UserModel) -> Result<(), ApiError> {
+  if UserRole::parse(&user.role) != Some(UserRole::Admin) {
+      return Ok(());
+  }
+  let other_admins = UserEntity::find()
+      .filter(UserColumn::Role.eq(UserRole::Admin.as_str()))
+      .filter(UserColumn::Status.eq(AccountStatus::Active.as_str()))
+      .filter(UserColumn::Id.ne(user.id.clone()))
+      .count(&state.db)
+      .await?;
+  if other_admins == 0 {
+      return Err(ApiError::BadRequest("The last administrator cannot delete their account".into()));
+  }
+  Ok(())
+}
+
+fn parse_id(value: &str) -> Result<Uuid, ApiError> {
+  Uuid::parse_str(value).map_err(|_| ApiError::InternalServerError("Invalid id".into()))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::BTreeMap, sync::LazyLock};
+
+  use sea_orm::Value;
+
+  use super::*;
+  use crate::{
+    entities::{bot::Model as BotModel, room::Model as RoomModel},
+    test_support::{self, rows_affected},
+  };
+
+  const PASSWORD: &str = "correct horse battery staple";
+  // Hashing is slow in debug builds; do it once.
+  static PASSWORD_HASH: LazyLock<String> = LazyLock::new(|| hash_password(PASSWORD).unwrap());
+
+  fn user_with_password(role: UserRole) -> UserModel {
+    UserModel { password: PASSWORD_HASH.clone(), ..test_support::user(Uuid::new_v4(), role) }
+  }
+
+  #[tokio::test]
+  async fn deletion_needs_the_password() {
+    let user = user_with_password(UserRole::User);
+    let db = test_support::mock_db().append_query_results([vec![user.clone()]]).into_connection();
+    let state = test_support::state(db);
+
+    let req = DeleteAccountRequest { password: "guess".into() };
+    let result = request_deletion(&state, Uuid::parse_str(&user.id).unwrap(), req).await;
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+  }
+
+  #[tokio::test]
+  async fn the_last_admin_cannot_leave() {
+    let admin = user_with_password(UserRole::Admin);
+    let db = test_support::mock_db()
+        .append_query_results([vec![admin.clone()]])
+        .append_query_results([[BTreeMap::from([("num_items".to_string(), Value::from(0))])]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let req = DeleteAccountRequest { password: PASSWORD.into() };
+    let result = request_deletion(&state, Uuid::parse_str(&admin.id).unwrap(), req).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("UPDATE")));
+  }
+
+  #[tokio::test]
+  async fn accounts_past_their_grace_period_cannot_be_restored() {
+    let user = UserModel {
+        status: AccountStatus::PendingDeletion.as_str().to_string(),
+        delete_after: Some(Utc::now() - chrono::Duration::minutes(1)),
+        ..user_with_password(UserRole::User)
+    };
+    let db = test_support::mock_db().append_query_results([vec![user.clone()]]).into_connection();
+    let state = test_support::state(db);
+
+    let req = RestoreAccountRequest { email: user.email, password: PASSWORD.into() };
+    let result = restore(&state, req).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+  }
+
+  #[tokio::test]
+  async fn the_ghost_account_is_not_deleted() {
+    let ghost = test_support::user(Uuid::parse_str(GHOST_USER_ID).unwrap(), UserRole::User);
+    let db = test_support::mock_db().append_query_results([vec![ghost]]).into_connection();
+    let state = test_support::state(db);
+
+    let result = delete_now(&state, Uuid::parse_str(GHOST_USER_ID).unwrap()).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+  }
+
+  // Messages in rooms under legal hold are anonymized instead of deleted.
+  #[tokio::test]
+  async fn finalizing_keeps_messages_under_legal_hold() {
+    let user = test_support::user(Uuid::new_v4(), UserRole::User);
+    let held = RoomModel { legal_hold: true, ..test_support::room(false) };
+    let membership = test_support::member(&held.id, Uuid::parse_str(&user.id).unwrap(), RoomRole::Member);
+    let ghost = test_support::user(Uuid::parse_str(GHOST_USER_ID).unwrap(), UserRole::User);
+    let db = test_support::mock_db()
+        .append_query_results([vec![membership.clone()]])
+        .append_query_results([vec![membership]])
+        .append_exec_results([rows_affected(1)])
+        .append_query_results([vec![held.clone(), test_support::room(false)]])
+        .append_query_results([[BTreeMap::from([("id".to_string(), Value::from("m1"))])]])
+        .append_exec_results([rows_affected(1)])
+        .append_query_results([vec![ghost]])
+        .append_exec_results([rows_affected(3)])
+        .append_query_results([Vec::<BotModel>::new()])
+        .append_query_results([vec![user.clone()]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+    let mut events = state.chat_tx.subscribe();
+
+    finalize(&state, &user, DeletedMessagePolicy::Delete).await.unwrap();
+    assert!(matches!(events.try_recv(), Ok(WsOutboundMessage::MemberRemoved(_))));
+
+    let statements = test_support::statements(state);
+    let select = statements.iter().find(|s| s.contains("NOT IN")).expect("held rooms excluded");
+    assert!(select.contains(&held.id), "{}", select);
+    assert!(statements.iter().any(|s| s.contains("UPDATE `messages` SET `sender_id`") && s.contains(GHOST_USER_ID)));
+    assert!(statements.iter().any(|s| s.contains("DELETE FROM `users`")));
+    let audit = statements.last().unwrap();
+    assert!(audit.contains(r#"{\"messages_anonymized\":3,\"messages_deleted\":1}"#), "{}", audit);
+  }
+
+  #[tokio::test]
+  async fn the_ghost_account_is_created_banned() {
+    let ghost = test_support::user(Uuid::parse_str(GHOST_USER_ID).unwrap(), UserRole::User);
+    let db = test_support::mock_db()
+        .append_query_results([Vec::<UserModel>::new()])
+        .append_exec_results([rows_affected(1)])
+        .append_query_results([vec![ghost.clone()], vec![ghost]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let ghost = ensure_ghost_user(&state).await.unwrap();
+    assert_eq!(ghost.id, GHOST_USER_ID);
+    let statements = test_support::statements(state);
+    let insert = statements.iter().find(|s| s.contains("INSERT INTO `users`")).expect("ghost inserted");
+    assert!(insert.contains(AccountStatus::Banned.as_str()), "{}", insert);
+  }
+}
@@ -0,0 +1,356 @@
+use chrono::{Duration, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::{
+    admin::{
+      AccountStatus, AdminListUsersQuery, AdminUserResponse, AuditLogResponse, ListAuditLogQuery, ServerStatsResponse,
+      UpdateUserRoleRequest, UpdateUserStatusRequest, UserRole,
+    },
+    chat::{MessageDeletedDto, WsOutboundMessage},
+  },
+  entities::{
+    audit_log::Model as AuditLogModel,
+    message::{Column as MessageColumn, Entity as MessageEntity},
+    room::Entity as RoomEntity,
+    user::{Column as UserColumn, Entity as UserEntity, Model as UserModel},
+  },
+  repositories::{audit_log as audit_repo, user as user_repo},
+  response::ApiError,
+  services::room,
+};
+
+// Rejects accounts that are banned, inside an active suspension or scheduled for deletion.
+pub fn ensure_active(user: &UserModel) -> Result<(), ApiError> {
+  match AccountStatus::parse(&user.status).unwrap_or(AccountStatus::Active) {
+      AccountStatus::Active => Ok(()),
+      AccountStatus::Banned => Err(ApiError::Forbidden("This account has been banned".into())),
+      AccountStatus::PendingDeletion => Err(ApiError::Forbidden(
+          "This account is scheduled for deletion; sign in through /auth/restore to keep it".into(),
+      )),
+      AccountStatus::Suspended => match user.suspended_until {
+          Some(until) if until <= Utc::now() => Ok(()),
+          Some(until) => Err(ApiError::Forbidden(format!("This account is suspended until {}", until.to_rfc3339()))),
+          None => Err(ApiError::Forbidden("This account is suspended".into())),
+      },
+  }
+}
+
+// Whether a live socket may stay open. A deleted account may not.
+pub async fn is_active(state: &AppState, user_id: Uuid) -> Result<bool, ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string()).await?;
+  Ok(user.is_some_and(|user| ensure_active(&user).is_ok()))
+}
+
+// Resolve the caller and make sure they hold the global admin role.
+pub async fn ensure_admin(state: &AppState, user_id: Uuid) -> Result<(), ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::Unauthorized("User not found".into()))?;
+  if UserRole::parse(&user.role) != Some(UserRole::Admin) {
+      return Err(ApiError::Forbidden("Administrator access required".into()));
+  }
+  ensure_active(&user)
+}
+
+pub async fn list_users(state: &AppState, params: AdminListUsersQuery) -> Result<Vec<AdminUserResponse>, ApiError> {
+  let users = user_repo::search(
+      &state.db,
+      params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()),
+      params.role.map(|r| r.as_str()),
+      params.status.map(|s| s.as_str()),
+      params.limit.unwrap_or(50).min(200),
+      params.offset.unwrap_or(0),
+  )
+  .await?;
+
+  users.into_iter().map(to_admin_response).collect()
+}
+
+pub async fn get_user(state: &AppState, user_id: Uuid) -> Result<AdminUserResponse, ApiError> {
+  let user = find_user(state, user_id).await?;
+  to_admin_response(user)
+}
+
+pub async fn update_user_status(
+  state: &AppState,
+  admin_id: Uuid,
+  user_id: Uuid,
+  req: UpdateUserStatusRequest,
+) -> Result<AdminUserResponse, ApiError> {
+  if admin_id == user_id {
+      return Err(ApiError::BadRequest("You cannot change your own status".into()));
+  }
+  // Deletion is the owner's call, and so is taking it back.
+  let current = find_user(state, user_id).await?;
+  if req.status == AccountStatus::PendingDeletion
+      || AccountStatus::parse(&current.status) == Some(AccountStatus::PendingDeletion)
+  {
+      return Err(ApiError::BadRequest("Only the account owner can schedule or cancel its deletion".into()));
+  }
+
+  let suspended_until = match req.status {
+      AccountStatus::Suspended => {
+          if req.until.is_some_and(|until| until <= Utc::now()) {
+              return Err(ApiError::BadRequest("Suspension end must be in the future".into()));
+          }
+          req.until
+      }
+      _ => None,
+  };
+  let reason = req.reason.map(|r| r.trim().chars().take(255).collect::<String>()).filter(|r| !r.is_empty());
+
+  let user = user_repo::update_status(
+      &state.db,
+      &user_id.to_string(),
+      req.status.as_str(),
+      suspended_until,
+      reason.clone(),
+  )
+  .await?;
+
+  if req.status != AccountStatus::Active {
+      state.presence.kick(user_id);
+  }
+
+  record(
+      state,
+      Some(admin_id),
+      &format!("user.{}", req.status.as_str()),
+      "user",
+      Some(user_id.to_string()),
+      json!({ "until": suspended_until, "reason": reason }),
+  )
+  .await;
+
+  to_admin_response(user)
+}
+
+pub async fn update_user_role(
+  state: &AppState,
+  admin_id: Uuid,
+  user_id: Uuid,
+  req: UpdateUserRoleRequest,
+) -> Result<AdminUserResponse, ApiError> {
+  if admin_id == user_id {
+      return Err(ApiError::BadRequest("You cannot change your own role".into()));
+  }
+  let previous = find_user(state, user_id).await?;
+
+  let user = user_repo::update_role(&state.db, &user_id.to_string(), req.role.as_str()).await?;
+
+  record(
+      state,
+      Some(admin_id),
+      "user.role_changed",
+      "user",
+      Some(user_id.to_string()),
+      json!({ "from": previous.role, "to": req.role.as_str() }),
+  )
+  .await;
+
+  to_admin_response(user)
+}
+
+pub async fn delete_room(state: &AppState, admin_id: Uuid, room_id: Uuid) -> Result<(), ApiError> {
+  let room = RoomEntity::find_by_id(room_id.to_string())
+      .one(&state.db)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Room not found".into()))?;
+
+  room::remove_room(state, room_id).await?;
+
+  record(
+      state,
+      Some(admin_id),
+      "room.deleted",
+      "room",
+      Some(room.id),
+      json!({ "name": room.name }),
+  )
+  .await;
+  Ok(())
+}
+
+pub async fn delete_message(state: &AppState, admin_id: Uuid, message_id: Uuid) -> Result<(), ApiError> {
+  let message = MessageEntity::find_by_id(message_id.to_string())
+      .one(&state.db)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Message not found".into()))?;
+
+  MessageEntity::delete_by_id(message.id.clone()).exec(&state.db).await?;
+
+  let room_id = Uuid::parse_str(&message.room_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?;
+  let _ = state.chat_tx.send(WsOutboundMessage::MessageDeleted(MessageDeletedDto { id: message_id, room_id }));
+
+  // Keep the content in the audit trail; the message itself is gone.
+  record(
+      state,
+      Some(admin_id),
+      "message.deleted",
+      "message",
+      Some(message.id),
+      json!({ "room_id": message.room_id, "sender_id": message.sender_id, "content": message.content }),
+  )
+  .await;
+  Ok(())
+}
+
+pub async fn server_stats(state: &AppState) -> Result<ServerStatsResponse, ApiError> {
+  let since = Utc::now() - Duration::hours(24);
+
+  Ok(ServerStatsResponse {
+      users: UserEntity::find().count(&state.db).await?,
+      suspended_users: UserEntity::find()
+          .filter(UserColumn::Status.eq(AccountStatus::Suspended.as_str()))
+          .count(&state.db)
+          .await?,
+      banned_users: UserEntity::find()
+          .filter(UserColumn::Status.eq(AccountStatus::Banned.as_str()))
+          .count(&state.db)
+          .await?,
+      rooms: RoomEntity::find().count(&state.db).await?,
+      messages: MessageEntity::find().count(&state.db).await?,
+      messages_last_24h: MessageEntity::find()
+          .filter(MessageColumn::CreatedAt.gt(since))
+          .count(&state.db)
+          .await?,
+      online_users: state.presence.online_users(),
+      open_connections: state.presence.open_connections(),
+  })
+}
+
+pub async fn list_audit_log(state: &AppState, params: ListAuditLogQuery) -> Result<Vec<AuditLogResponse>, ApiError> {
+  let entries = audit_repo::list(
+      &state.db,
+      params.actor_id.map(|id| id.to_string()).as_deref(),
+      params.action.as_deref(),
+      params.limit.unwrap_or(100).min(500),
+  )
+  .await?;
+
+  entries
+      .into_iter()
+      .map(|entry| {
+        Ok(AuditLogResponse {
+            id: Uuid::parse_str(&entry.id)
+                .map_err(|_| ApiError::InternalServerError("Invalid audit log id".into()))?,
+            actor_id: entry.actor_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+            action: entry.action,
+            target_type: entry.target_type,
+            target_id: entry.target_id,
+            details: entry.details.and_then(|d| serde_json::from_str(&d).ok()),
+            created_at: entry.created_at,
+        })
+      })
+      .collect()
+}
+
+// Append an entry to the audit log. The action already happened, so failures are only logged.
+pub async fn record(
+  state: &AppState,
+  actor_id: Option<Uuid>,
+  action: &str,
+  target_type: &str,
+  target_id: Option<String>,
+  details: serde_json::Value,
+) {
+  let entry = AuditLogModel {
+      id: Uuid::new_v4().to_string(),
+      actor_id: actor_id.map(|id| id.to_string()),
+      action: action.to_string(),
+      target_type: target_type.to_string(),
+      target_id,
+      details: Some(details.to_string()),
+      created_at: Utc::now(),
+  };
+  if let Err(e) = audit_repo::insert(&state.db, entry).await {
+      tracing::error!(action, error = %e, "Failed to write audit log entry");
+  }
+}
+
+async fn find_user(state: &AppState, user_id: Uuid) -> Result<UserModel, ApiError> {
+  user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))
+}
+
+fn to_admin_response(user: UserModel) -> Result<AdminUserResponse, ApiError> {
+  Ok(AdminUserResponse {
+      id: Uuid::parse_str(&user.id)
+          .map_err(|_| ApiError::InternalServerError("Invalid user id".into()))?,
+      username: user.username,
+      email: user.email,
+      role: UserRole::parse(&user.role).unwrap_or(UserRole::User),
+      status: AccountStatus::parse(&user.status).unwrap_or(AccountStatus::Active),
+      suspended_until: user.suspended_until,
+      status_reason: user.status_reason,
+      email_verified: user.email_verified_at.is_some(),
+      created_at: user.created_at,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_support::{self, rows_affected};
+
+  fn with_status(status: AccountStatus, suspended_until: Option<chrono::DateTime<Utc>>) -> UserModel {
+    let mut user = test_support::user(Uuid::new_v4(), UserRole::User);
+    user.status = status.as_str().to_string();
+    user.suspended_until = suspended_until;
+    user
+  }
+
+  #[test]
+  fn only_active_or_lapsed_suspensions_pass() {
+    assert!(ensure_active(&with_status(AccountStatus::Active, None)).is_ok());
+    assert!(ensure_active(&with_status(AccountStatus::Suspended, Some(Utc::now() - Duration::hours(1)))).is_ok());
+    assert!(ensure_active(&with_status(AccountStatus::Suspended, Some(Utc::now() + Duration::hours(1)))).is_err());
+    assert!(ensure_active(&with_status(AccountStatus::Suspended, None)).is_err());
+    assert!(ensure_active(&with_status(AccountStatus::Banned, None)).is_err());
+    assert!(ensure_active(&with_status(AccountStatus::PendingDeletion, None)).is_err());
+  }
+
+  #[tokio::test]
+  async fn sockets_of_missing_or_banned_accounts_close() {
+    for (found, expected) in [
+        (vec![with_status(AccountStatus::Active, None)], true),
+        (vec![with_status(AccountStatus::Banned, None)], false),
+        (Vec::new(), false),
+    ] {
+        let db = test_support::mock_db().append_query_results([found]).into_connection();
+        let state = test_support::state(db);
+        assert_eq!(is_active(&state, Uuid::new_v4()).await.unwrap(), expected);
+    }
+  }
+
+  #[tokio::test]
+  async fn banning_kicks_live_sockets() {
+    let target = with_status(AccountStatus::Active, None);
+    let target_id = Uuid::parse_str(&target.id).unwrap();
+    let db = test_support::mock_db()
+        .append_query_results([vec![target.clone()]])
+        .append_exec_results([rows_affected(1)])
+        .append_query_results([vec![with_status(AccountStatus::Banned, None)]])
+        .into_connection();
+    let state = test_support::state(db);
+    let mut kicks = state.presence.subscribe_kicks();
+
+    let req = UpdateUserStatusRequest { status: AccountStatus::Banned, until: None, reason: Some("spam".into()) };
+    update_user_status(&state, Uuid::new_v4(), target_id, req).await.unwrap();
+    assert_eq!(kicks.try_recv().unwrap(), target_id);
+  }
+
+  #[tokio::test]
+  async fn admins_cannot_change_their_own_status() {
+    let state = test_support::state(test_support::mock_db().into_connection());
+    let admin_id = Uuid::new_v4();
+    let req = UpdateUserStatusRequest { status: AccountStatus::Banned, until: None, reason: None };
+    assert!(matches!(update_user_status(&state, admin_id, admin_id, req).await, Err(ApiError::BadRequest(_))));
+  }
+}
@@ -1,19 +1,30 @@
 use anyhow::Context;
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{Duration, Utc};
+use tracing::Instrument;
 
 use crate::{
-  database::AppState,
-  dtos::auth::{AuthResponse, UserResponse, RegisterRequest, LoginRequest, LoginResponse},
-  entities::user::Model as UserModel,
-  repositories::user as user_repo,
+  database::{AppState, SharedState},
+  dtos::admin::{AccountStatus, UserRole},
+  dtos::auth::{
+    ForgotPasswordRequest, LoginRequest, LoginResponse, RegisterRequest, ResetPasswordRequest, UserResponse,
+    VerifyEmailRequest,
+  },
+  entities::{auth_token::Model as AuthTokenModel, user::Model as UserModel},
+  repositories::{auth_token as auth_token_repo, user as user_repo},
   response::{ApiError, ApiResponse},
-  security::{hash_password, verify_password},
+  security::{generate_token, hash_password, hash_token, verify_dummy_password, verify_password},
+  services::{admin, mailer::Mail, mfa},
 };
 
+const VERIFY_EMAIL_PURPOSE: &str = "verify_email";
+const RESET_PASSWORD_PURPOSE: &str = "reset_password";
+const VERIFY_EMAIL_TTL: Duration = Duration::hours(24);
+const RESET_PASSWORD_TTL: Duration = Duration::minutes(30);
+
 pub async fn register(state: &AppState, req: RegisterRequest) -> Result<ApiResponse<UserResponse>, ApiError> {
   // Check if user already exists
-  if let Some(_) = user_repo::find_by_email(&state.db, &req.email).await? {
+  if user_repo::find_by_email(&state.db, &req.email).await?.is_some() {
     return Err(ApiError::BadRequest("Email already in use".to_string()));
   }
 
@@ -25,18 +36,25 @@ pub async fn register(state: &AppState, req: RegisterRequest) -> Result<ApiRespo
     email: req.email.clone(),
     password: hashed,
     created_at: Utc::now(),
+    email_verified_at: None,
+    role: UserRole::User.as_str().to_string(),
+    status: AccountStatus::Active.as_str().to_string(),
+    suspended_until: None,
+    status_reason: None,
+    delete_after: None,
   };
   
   let user = user_repo::insert(&state.db, user).await?;
   let user_id = Uuid::parse_str(&user.id)
       .context("Invalid user ID format")?;
-  
-  let token = state.jwt.generate(user_id).context("Failed to generate JWT")?;
-  
+
+  send_verification_mail(state, &user).await?;
+
   let payload = UserResponse {
       id: user_id,  
       username: user.username,
       email: user.email,
+      email_verified: false,
       created_at: user.created_at,
   };
 
@@ -44,22 +62,263 @@ pub async fn register(state: &AppState, req: RegisterRequest) -> Result<ApiRespo
 }
 
 pub async fn login(state: &AppState, req: LoginRequest) -> Result<ApiResponse<LoginResponse>, ApiError> {
-    let user = user_repo::find_by_email(&state.db, &req.email)
-        .await?
-        .ok_or_else(|| ApiError::Unauthorized("Invalid credentials".into()))?;
+    let Some(user) = user_repo::find_by_email(&state.db, &req.email).await? else {
+        // Still hash, so the response time does not tell which emails have an account.
+        verify_dummy_password(&req.password);
+        tracing::info!(reason = "unknown_email", "Login failed");
+        state.metrics.auth_failure("unknown_email");
+        return Err(ApiError::Unauthorized("Invalid credentials".into()));
+    };
 
     if !verify_password(&req.password, &user.password).context("Failed to verify password")? {
+        tracing::info!(user_id = %user.id, reason = "wrong_password", "Login failed");
+        state.metrics.auth_failure("wrong_password");
         return Err(ApiError::Unauthorized("Invalid credentials".into()));
     }
 
+    let payload = issue_login(state, &user).await?;
+    Ok(ApiResponse::success(payload))
+}
+
+// Final step of every first-factor login (password or SSO).
+pub async fn issue_login(state: &AppState, user: &UserModel) -> Result<LoginResponse, ApiError> {
+    admin::ensure_active(user)?;
+
     let user_id = Uuid::parse_str(&user.id)
         .context("Invalid user ID format")?;
-    
+
+    // With 2FA on, the first factor only earns a short-lived challenge for `/auth/login/mfa`.
+    if mfa::is_enabled(state, &user.id).await? {
+        let mfa_token = state.jwt.generate_mfa_challenge(user_id).context("Failed to generate MFA token")?;
+        return Ok(LoginResponse {
+            token: None,
+            email_verified: user.email_verified_at.is_some(),
+            mfa_required: true,
+            mfa_token: Some(mfa_token),
+        });
+    }
+
     let token = state.jwt.generate(user_id).context("Failed to generate JWT")?;
-    
-    let payload = LoginResponse {
-        token,
-    };
 
-    Ok(ApiResponse::success(payload))
-}
\ No newline at end of file
+    Ok(LoginResponse {
+        token: Some(token),
+        email_verified: user.email_verified_at.is_some(),
+        mfa_required: false,
+        mfa_token: None,
+    })
+}
+
+pub async fn verify_email(state: &AppState, req: VerifyEmailRequest) -> Result<ApiResponse<()>, ApiError> {
+  let token = consume_token(state, &req.token, VERIFY_EMAIL_PURPOSE).await?;
+  user_repo::mark_email_verified(&state.db, &token.user_id).await?;
+  Ok(ApiResponse::success(()))
+}
+
+pub async fn resend_verification(state: &AppState, user_id: Uuid) -> Result<ApiResponse<()>, ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+  if user.email_verified_at.is_some() {
+      return Err(ApiError::BadRequest("Email address is already verified".into()));
+  }
+
+  send_verification_mail(state, &user).await?;
+  Ok(ApiResponse::success(()))
+}
+
+// Always succeeds so the endpoint cannot be used to probe which emails have accounts.
+// Answers at once and sends the mail in the background, so the response time does not tell
+// which emails have an account.
+pub async fn forgot_password(state: &SharedState, req: ForgotPasswordRequest) -> Result<ApiResponse<()>, ApiError> {
+  let state = state.clone();
+  tokio::spawn(async move {
+    if let Err(e) = send_reset_mail(&state, &req.email).await {
+        tracing::error!(error = %e, "Failed to issue a password reset");
+    }
+  }
+  .in_current_span());
+
+  Ok(ApiResponse::success(()))
+}
+
+async fn send_reset_mail(state: &AppState, email: &str) -> Result<(), ApiError> {
+  let Some(user) = user_repo::find_by_email(&state.db, email).await? else {
+      return Ok(());
+  };
+  let raw = issue_token(state, &user.id, RESET_PASSWORD_PURPOSE, RESET_PASSWORD_TTL).await?;
+  send_mail(state, Mail {
+      to: user.email,
+      subject: "Reset your password".into(),
+      body: format!(
+          "Someone asked to reset the password of your account.\n\nOpen {}/reset-password?token={} within {} minutes to choose a new one. If it was not you, ignore this email.",
+          app_base_url(state),
+          raw,
+          RESET_PASSWORD_TTL.num_minutes(),
+      ),
+  });
+  Ok(())
+}
+
+pub async fn reset_password(state: &AppState, req: ResetPasswordRequest) -> Result<ApiResponse<()>, ApiError> {
+  let token = consume_token(state, &req.token, RESET_PASSWORD_PURPOSE).await?;
+
+  let hashed = hash_password(&req.password).context("Failed to hash password")?;
+  user_repo::update_password(&state.db, &token.user_id, hashed).await?;
+  auth_token_repo::invalidate_for_user(&state.db, &token.user_id, RESET_PASSWORD_PURPOSE).await?;
+
+  // The reset link reached the mailbox, which is as good as verifying it.
+  let user = user_repo::find_by_id(&state.db, &token.user_id).await?;
+  if user.is_some_and(|u| u.email_verified_at.is_none()) {
+      user_repo::mark_email_verified(&state.db, &token.user_id).await?;
+  }
+
+  Ok(ApiResponse::success(()))
+}
+
+// Unverified accounts can sign in and read, but not create content or rooms.
+// Banned and suspended accounts are refused here as well.
+pub async fn ensure_verified(state: &AppState, user_id: Uuid) -> Result<(), ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::Unauthorized("User not found".into()))?;
+  admin::ensure_active(&user)?;
+  if user.email_verified_at.is_none() {
+      return Err(ApiError::Forbidden("Verify your email address first".into()));
+  }
+  Ok(())
+}
+
+async fn send_verification_mail(state: &AppState, user: &UserModel) -> Result<(), ApiError> {
+  let raw = issue_token(state, &user.id, VERIFY_EMAIL_PURPOSE, VERIFY_EMAIL_TTL).await?;
+  send_mail(state, Mail {
+      to: user.email.clone(),
+      subject: "Verify your email address".into(),
+      body: format!(
+          "Welcome, {}!\n\nOpen {}/verify-email?token={} within {} hours to verify your email address.",
+          user.username,
+          app_base_url(state),
+          raw,
+          VERIFY_EMAIL_TTL.num_hours(),
+      ),
+  });
+  Ok(())
+}
+
+// Only one token per purpose is live at a time; issuing a new one retires the old ones.
+async fn issue_token(state: &AppState, user_id: &str, purpose: &str, ttl: Duration) -> Result<String, ApiError> {
+  auth_token_repo::invalidate_for_user(&state.db, user_id, purpose).await?;
+
+  let raw = generate_token(purpose);
+  let now = Utc::now();
+  auth_token_repo::insert(&state.db, AuthTokenModel {
+      id: Uuid::new_v4().to_string(),
+      user_id: user_id.to_string(),
+      purpose: purpose.to_string(),
+      token_hash: hash_token(&raw),
+      expires_at: now + ttl,
+      used_at: None,
+      created_at: now,
+  })
+  .await?;
+
+  Ok(raw)
+}
+
+async fn consume_token(state: &AppState, raw: &str, purpose: &str) -> Result<AuthTokenModel, ApiError> {
+  let invalid = || ApiError::BadRequest("Invalid or expired token".into());
+
+  let token = auth_token_repo::find_by_token_hash(&state.db, &hash_token(raw))
+      .await?
+      .filter(|t| t.purpose == purpose && t.used_at.is_none() && t.expires_at > Utc::now())
+      .ok_or_else(invalid)?;
+
+  if !auth_token_repo::mark_used(&state.db, &token.id).await? {
+      return Err(invalid());
+  }
+  Ok(token)
+}
+
+// Delivery happens off the request path so slow SMTP servers don't stall the response.
+fn send_mail(state: &AppState, mail: Mail) {
+  let mailer = state.mailer.clone();
+  tokio::spawn(async move {
+    let to = mail.to.clone();
+    if let Err(e) = mailer.send(mail).await {
+        tracing::error!(%to, error = %e, "Failed to send mail");
+    }
+  }
+  .in_current_span());
+}
+
+fn app_base_url(state: &AppState) -> &str {
+  state.config.server.base_url.trim_end_matches('/')
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_support::{self, rows_affected};
+
+  const RAW_TOKEN: &str = "verify_email_0123";
+
+  fn token(purpose: &str, expires_in: Duration, used: bool) -> AuthTokenModel {
+    let now = Utc::now();
+    AuthTokenModel {
+      id: Uuid::new_v4().to_string(),
+      user_id: Uuid::new_v4().to_string(),
+      purpose: purpose.to_string(),
+      token_hash: hash_token(RAW_TOKEN),
+      expires_at: now + expires_in,
+      used_at: used.then_some(now),
+      created_at: now,
+    }
+  }
+
+  async fn consume(found: AuthTokenModel, claimed: u64) -> Result<AuthTokenModel, ApiError> {
+    let db = test_support::mock_db()
+        .append_query_results([vec![found]])
+        .append_exec_results([rows_affected(claimed)])
+        .into_connection();
+    consume_token(&test_support::state(db), RAW_TOKEN, VERIFY_EMAIL_PURPOSE).await
+  }
+
+  #[tokio::test]
+  async fn consumes_a_live_token_once() {
+    let live = token(VERIFY_EMAIL_PURPOSE, Duration::hours(1), false);
+    assert_eq!(consume(live.clone(), 1).await.unwrap().id, live.id);
+    // A concurrent request marked it used between the lookup and the update.
+    assert!(matches!(consume(live, 0).await, Err(ApiError::BadRequest(_))));
+  }
+
+  #[tokio::test]
+  async fn rejects_expired_used_and_foreign_tokens() {
+    for bad in [
+        token(VERIFY_EMAIL_PURPOSE, Duration::hours(-1), false),
+        token(VERIFY_EMAIL_PURPOSE, Duration::hours(1), true),
+        token(RESET_PASSWORD_PURPOSE, Duration::hours(1), false),
+    ] {
+        assert!(matches!(consume(bad, 1).await, Err(ApiError::BadRequest(_))));
+    }
+  }
+
+  #[tokio::test]
+  async fn forgot_password_hides_unknown_emails() {
+    let db = test_support::mock_db().append_query_results([Vec::<UserModel>::new()]).into_connection();
+    let state = test_support::state(db);
+
+    send_reset_mail(&state, "nobody@example.com").await.unwrap();
+    // Only the lookup: no token is issued.
+    assert_eq!(test_support::statements(state).len(), 1);
+  }
+
+  #[tokio::test]
+  async fn unverified_accounts_cannot_create_content() {
+    let user_id = Uuid::new_v4();
+    let user = UserModel { email_verified_at: None, ..test_support::user(user_id, UserRole::User) };
+    let db = test_support::mock_db().append_query_results([vec![user]]).into_connection();
+
+    let result = ensure_verified(&test_support::state(db), user_id).await;
+
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+  }
+}
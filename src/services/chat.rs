@@ -6,12 +6,14 @@ use uuid::Uuid;
 
 use crate::{
     database::AppState,
-    dtos::chat::{ListMessagesQuery, MessageDto},
+    dtos::chat::{LinkPreviewDto, ListMessagesQuery, MessageDto, MessageFormat},
     entities::{
         message::{ActiveModel as MessageActiveModel, Column as MessageColumn, Entity as MessageEntity, Model as MessageModel},
         room_member::Entity as RoomMemberEntity,
     },
+    repositories::{link_preview as link_preview_repo, room as room_repo, room_member as member_repo},
     response::ApiError,
+    services::{auth, filter, markdown, moderation, notification, poll, unfurl, user},
 };
 
 // Fetch messages from a room, only if the user is a member.
@@ -25,15 +27,51 @@ pub async fn list_messages(
   ensure_membership(state, room_id, user_id).await?;
 
   let limit = params.limit.unwrap_or(50).min(200);
+  // Messages from users the reader blocked are left out.
+  let blocked: Vec<String> = user::blocked_ids(state, user_id).await?.iter().map(Uuid::to_string).collect();
 
   let models = MessageEntity::find()
       .filter(MessageColumn::RoomId.eq(room_id.to_string()))
+      .filter(MessageColumn::SenderId.is_not_in(blocked))
       .order_by_asc(MessageColumn::CreatedAt)
       .limit(limit)
       .all(&state.db)
       .await?;
 
-  models.into_iter().map(to_dto).collect()
+  let mut messages = models.into_iter().map(to_dto).collect::<Result<Vec<_>, _>>()?;
+  attach_previews(state, &mut messages).await?;
+  poll::attach_polls(state, &mut messages, Some(user_id)).await?;
+  Ok(messages)
+}
+
+// Load cached link previews for a page of messages in two queries.
+pub async fn attach_previews(state: &AppState, messages: &mut [MessageDto]) -> Result<(), ApiError> {
+  let message_ids = messages.iter().map(|m| m.id.to_string()).collect();
+  let links = link_preview_repo::list_links_for_messages(&state.db, message_ids).await?;
+  if links.is_empty() {
+      return Ok(());
+  }
+
+  let preview_ids = links.iter().map(|l| l.preview_id.clone()).collect();
+  let previews = link_preview_repo::list_by_ids(&state.db, preview_ids).await?;
+
+  for message in messages.iter_mut() {
+      let message_id = message.id.to_string();
+      message.previews = links
+          .iter()
+          .filter(|l| l.message_id == message_id)
+          .filter_map(|l| previews.iter().find(|p| p.id == l.preview_id))
+          .filter(|p| p.status == unfurl::STATUS_OK)
+          .map(|p| LinkPreviewDto {
+              url: p.url.clone(),
+              title: p.title.clone(),
+              description: p.description.clone(),
+              image_url: p.image_url.clone(),
+              site_name: p.site_name.clone(),
+          })
+          .collect();
+  }
+  Ok(())
 }
 
 // Insert a new message into the DB if the user is a member of the room.
@@ -43,29 +81,81 @@ pub async fn send_message(
   room_id: Uuid,
   sender_id: Uuid,
   content: String,
+  format: MessageFormat,
+) -> Result<MessageDto, ApiError> {
+  send_message_with_id(state, Uuid::new_v4(), room_id, sender_id, content, format).await
+}
+
+// Same as `send_message` with a caller-chosen id, so a retried delivery can't store the message twice.
+pub async fn send_message_with_id(
+  state: &AppState,
+  message_id: Uuid,
+  room_id: Uuid,
+  sender_id: Uuid,
+  content: String,
+  format: MessageFormat,
 ) -> Result<MessageDto, ApiError> {
   ensure_membership(state, room_id, sender_id).await?;
+  auth::ensure_verified(state, sender_id).await?;
+  moderation::ensure_can_post(state, room_id, sender_id).await?;
+  ensure_not_blocked_in_direct(state, room_id, sender_id).await?;
 
   let trimmed = content.trim();
   if trimmed.is_empty() {
       return Err(ApiError::BadRequest("Message content cannot be empty".into()));
   }
-  if trimmed.len() > 1024 {
-      return Err(ApiError::BadRequest("Message content is too long (max 1024 chars)".into()));
+  // The room's content filters may reject the message or rewrite it (e.g. mask words).
+  let filtered = filter::apply(state, room_id, trimmed).await?;
+  let trimmed = filtered.trim();
+  let max_length = state.config.limits.max_message_length;
+  if trimmed.len() > max_length {
+      return Err(ApiError::BadRequest(format!("Message content is too long (max {} chars)", max_length)));
   }
 
+  let rendered_html = match format {
+      MessageFormat::Plain => None,
+      MessageFormat::Markdown => Some(markdown::render(trimmed)),
+  };
+
   let created_at = Utc::now();
   let model = MessageActiveModel {
-      id: Set(Uuid::new_v4().to_string()),
+      id: Set(message_id.to_string()),
       room_id: Set(room_id.to_string()),
       sender_id: Set(sender_id.to_string()),
       content: Set(trimmed.to_owned()),
+      format: Set(format.as_str().to_string()),
+      rendered_html: Set(rendered_html),
+      thread_id: Set(None),
       created_at: Set(created_at),
   }
   .insert(&state.db)
   .await?;
 
-  to_dto(model)
+  let message = to_dto(model)?;
+  state.metrics.message_sent();
+  if let Err(e) = notification::enqueue_for_message(state, &message).await {
+      tracing::error!(message_id = %message.id, error = %e, "Failed to enqueue notifications");
+  }
+
+  Ok(message)
+}
+
+// In a direct conversation the other side may have blocked the sender.
+async fn ensure_not_blocked_in_direct(state: &AppState, room_id: Uuid, sender_id: Uuid) -> Result<(), ApiError> {
+  let room = room_repo::find_by_id(&state.db, &room_id.to_string()).await?;
+  if !room.is_direct {
+      return Ok(());
+  }
+  let members = member_repo::list_by_room(&state.db, &room.id).await?;
+  for member in members {
+      let Ok(member_id) = Uuid::parse_str(&member.user_id) else {
+          continue;
+      };
+      if member_id != sender_id && user::has_blocked(state, member_id, sender_id).await? {
+          return Err(ApiError::Forbidden("This user is not accepting messages from you".into()));
+      }
+  }
+  Ok(())
 }
 
 // Check if the user is a member of the room.
@@ -83,8 +173,24 @@ pub async fn ensure_membership(
   Ok(())
 }
 
+// Load messages by id (in no particular order), with their link previews and polls.
+pub async fn load_messages(state: &AppState, ids: Vec<String>) -> Result<Vec<MessageDto>, ApiError> {
+  if ids.is_empty() {
+      return Ok(Vec::new());
+  }
+  let models = MessageEntity::find()
+      .filter(MessageColumn::Id.is_in(ids))
+      .all(&state.db)
+      .await?;
+
+  let mut messages = models.into_iter().map(to_dto).collect::<Result<Vec<_>, _>>()?;
+  attach_previews(state, &mut messages).await?;
+  poll::attach_polls(state, &mut messages, None).await?;
+  Ok(messages)
+}
+
 // Convert a DB model into API DTO format, parsing string IDs to UUIDs.
-fn to_dto(model: MessageModel) -> Result<MessageDto, ApiError> {
+pub fn to_dto(model: MessageModel) -> Result<MessageDto, ApiError> {
   Ok(MessageDto {
       id: Uuid::parse_str(&model.id)
           .map_err(|_| ApiError::InternalServerError("Invalid message id".into()))?,
@@ -93,9 +199,84 @@ fn to_dto(model: MessageModel) -> Result<MessageDto, ApiError> {
       sender_id: Uuid::parse_str(&model.sender_id)
           .map_err(|_| ApiError::InternalServerError("Invalid sender id".into()))?,
       content: model.content,
+      format: MessageFormat::parse(&model.format).unwrap_or_default(),
+      html: model.rendered_html,
+      thread_id: model.thread_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+      previews: Vec::new(),
+      poll: None,
       created_at: model.created_at,
   })
 }
 
 
 
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    dtos::room::RoomRole,
+    entities::user_block::Model as UserBlockModel,
+    test_support,
+  };
+
+  fn direct_room_state(blocked: bool) -> (AppState, Uuid, Uuid) {
+    let room = test_support::room(true);
+    let (sender_id, other_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let blocks = match blocked {
+        true => vec![UserBlockModel { blocker_id: other_id.to_string(), blocked_id: sender_id.to_string(), created_at: Utc::now() }],
+        false => Vec::new(),
+    };
+    let db = test_support::mock_db()
+        .append_query_results([vec![room.clone()]])
+        .append_query_results([vec![
+            test_support::member(&room.id, sender_id, RoomRole::Owner),
+            test_support::member(&room.id, other_id, RoomRole::Member),
+        ]])
+        .append_query_results([blocks])
+        .into_connection();
+    (test_support::state(db), Uuid::parse_str(&room.id).unwrap(), sender_id)
+  }
+
+  #[tokio::test]
+  async fn blocked_senders_cannot_write_in_direct_rooms() {
+    let (state, room_id, sender_id) = direct_room_state(true);
+    assert!(matches!(ensure_not_blocked_in_direct(&state, room_id, sender_id).await, Err(ApiError::Forbidden(_))));
+
+    let (state, room_id, sender_id) = direct_room_state(false);
+    assert!(ensure_not_blocked_in_direct(&state, room_id, sender_id).await.is_ok());
+  }
+
+  #[tokio::test]
+  async fn blocks_do_not_silence_group_rooms() {
+    // Two members, but not a direct conversation: nobody's block list is consulted.
+    let room = test_support::room(false);
+    let db = test_support::mock_db().append_query_results([vec![room.clone()]]).into_connection();
+    let state = test_support::state(db);
+
+    assert!(ensure_not_blocked_in_direct(&state, Uuid::parse_str(&room.id).unwrap(), Uuid::new_v4()).await.is_ok());
+    assert_eq!(test_support::statements(state).len(), 1);
+  }
+
+  #[tokio::test]
+  async fn history_leaves_out_blocked_senders() {
+    let room = test_support::room(false);
+    let (reader_id, blocked_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::member(&room.id, reader_id, RoomRole::Member)]])
+        .append_query_results([vec![UserBlockModel {
+            blocker_id: reader_id.to_string(),
+            blocked_id: blocked_id.to_string(),
+            created_at: Utc::now(),
+        }]])
+        .append_query_results([Vec::<MessageModel>::new()])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let params = ListMessagesQuery { limit: None };
+    list_messages(&state, Uuid::parse_str(&room.id).unwrap(), reader_id, params).await.unwrap();
+    let statements = test_support::statements(state);
+    let select = statements.iter().find(|s| s.contains("FROM `messages`")).unwrap();
+    assert!(select.contains("NOT IN") && select.contains(&blocked_id.to_string()), "{}", select);
+  }
+}
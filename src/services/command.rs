@@ -0,0 +1,570 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::{
+    chat::{
+      CommandReplyDto, EphemeralDto, MemberEventDto, MessageDto, MessageFormat, SendMessageRequest,
+      SendMessageResponse, WsOutboundMessage,
+    },
+    integration::{BotCommandPayload, BotCommandReply},
+    notification::UpdateNotifySettingsRequest,
+    room::AddMemberRequest,
+  },
+  entities::bot_command::Model as BotCommandModel,
+  repositories::{
+    bot_command as bot_command_repo, room as room_repo, room_member as member_repo,
+    user as user_repo,
+  },
+  response::ApiError,
+  security::sign_payload,
+  services::{
+    chat, moderation,
+    notification::{self, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    outbound, room,
+  },
+};
+
+const BOT_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_TOPIC_LEN: usize = 250;
+
+pub struct CommandContext {
+  pub room_id: Uuid,
+  pub user_id: Uuid,
+}
+
+// What a command produced: an optional reply only the caller sees, an optional
+// message posted to the room, and any extra events to fan out.
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutcome {
+  pub reply: Option<String>,
+  pub message: Option<MessageDto>,
+  pub events: Vec<WsOutboundMessage>,
+}
+
+impl CommandOutcome {
+  pub fn reply(text: impl Into<String>) -> Self {
+    Self {
+      reply: Some(text.into()),
+      ..Default::default()
+    }
+  }
+}
+
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+  fn usage(&self) -> &'static str;
+
+  async fn run(&self, state: &AppState, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, ApiError>;
+}
+
+// Built-in commands live here; bots extend it at runtime through registered `bot_commands`.
+pub struct CommandRegistry {
+  handlers: HashMap<String, Arc<dyn CommandHandler>>,
+}
+
+impl fmt::Debug for CommandRegistry {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CommandRegistry")
+        .field("commands", &self.handlers.keys().collect::<Vec<_>>())
+        .finish()
+  }
+}
+
+impl CommandRegistry {
+  pub fn new() -> Self {
+    Self { handlers: HashMap::new() }
+  }
+
+  pub fn with_builtins() -> Self {
+    let mut registry = Self::new();
+    registry.register("invite", InviteCommand);
+    registry.register("topic", TopicCommand);
+    registry.register("me", MeCommand);
+    registry.register("leave", LeaveCommand);
+    registry.register("mute", MuteCommand);
+    registry
+  }
+
+  pub fn register(&mut self, name: &str, handler: impl CommandHandler + 'static) {
+    self.handlers.insert(name.to_lowercase(), Arc::new(handler));
+  }
+
+  pub fn get(&self, name: &str) -> Option<Arc<dyn CommandHandler>> {
+    self.handlers.get(name).cloned()
+  }
+
+  // True when `name` is taken by a built-in, so bots cannot shadow it.
+  pub fn is_builtin(&self, name: &str) -> bool {
+    self.handlers.contains_key(&name.to_lowercase())
+  }
+}
+
+impl Default for CommandRegistry {
+  fn default() -> Self {
+    Self::with_builtins()
+  }
+}
+
+#[derive(Debug, Clone)]
+pub enum InputOutcome {
+  Message(MessageDto),
+  Command { name: String, outcome: CommandOutcome },
+}
+
+impl InputOutcome {
+  pub fn to_response(&self) -> SendMessageResponse {
+    match self {
+      InputOutcome::Message(message) => SendMessageResponse::Message(message.clone()),
+      InputOutcome::Command { name, outcome } => SendMessageResponse::Command(CommandReplyDto {
+          command: name.clone(),
+          reply: outcome.reply.clone(),
+          message: outcome.message.clone(),
+      }),
+    }
+  }
+}
+
+// Entry point for chat input from both REST and WebSocket: text starting with `/`
+// runs a command, `//` escapes a literal slash, anything else is stored as a message.
+pub async fn submit(
+  state: &AppState,
+  room_id: Uuid,
+  user_id: Uuid,
+  input: SendMessageRequest,
+) -> Result<InputOutcome, ApiError> {
+  let SendMessageRequest { content, format } = input;
+  let trimmed = content.trim_start();
+  let Some(command) = trimmed.strip_prefix('/') else {
+      let message = chat::send_message(state, room_id, user_id, content, format).await?;
+      return Ok(InputOutcome::Message(message));
+  };
+  if command.starts_with('/') {
+      let message = chat::send_message(state, room_id, user_id, command.to_owned(), format).await?;
+      return Ok(InputOutcome::Message(message));
+  }
+
+  chat::ensure_membership(state, room_id, user_id).await?;
+
+  let (name, args) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+  let name = name.to_lowercase();
+  let ctx = CommandContext { room_id, user_id };
+  let outcome = execute(state, &ctx, &name, args.trim()).await?;
+
+  Ok(InputOutcome::Command { name, outcome })
+}
+
+// Fan out everything an input produced; ephemeral replies reach only the caller's sockets.
+pub fn publish(state: &AppState, room_id: Uuid, user_id: Uuid, outcome: InputOutcome) {
+  match outcome {
+    InputOutcome::Message(message) => {
+        let _ = state.chat_tx.send(WsOutboundMessage::MessageCreated(message));
+    }
+    InputOutcome::Command { outcome, .. } => {
+        if let Some(message) = outcome.message {
+            let _ = state.chat_tx.send(WsOutboundMessage::MessageCreated(message));
+        }
+        if let Some(content) = outcome.reply {
+            let _ = state.chat_tx.send(WsOutboundMessage::Ephemeral(EphemeralDto {
+                room_id,
+                user_id,
+                content,
+            }));
+        }
+        for event in outcome.events {
+            let _ = state.chat_tx.send(event);
+        }
+    }
+  }
+}
+
+async fn execute(
+  state: &AppState,
+  ctx: &CommandContext,
+  name: &str,
+  args: &str,
+) -> Result<CommandOutcome, ApiError> {
+  if let Some(handler) = state.commands.get(name) {
+      return handler.run(state, ctx, args).await;
+  }
+
+  if let Some(command) = find_bot_command(state, ctx.room_id, name).await? {
+      return run_bot_command(state, ctx, command, args).await;
+  }
+
+  Ok(CommandOutcome::reply(format!("Unknown command /{}", name)))
+}
+
+// A bot command is available in rooms where its bot is a member.
+async fn find_bot_command(
+  state: &AppState,
+  room_id: Uuid,
+  name: &str,
+) -> Result<Option<BotCommandModel>, ApiError> {
+  let room_id = room_id.to_string();
+  for command in bot_command_repo::list_by_name(&state.db, name).await? {
+      if member_repo::find_by_room_and_user(&state.db, &room_id, &command.bot_id).await.is_ok() {
+          return Ok(Some(command));
+      }
+  }
+  Ok(None)
+}
+
+async fn run_bot_command(
+  state: &AppState,
+  ctx: &CommandContext,
+  command: BotCommandModel,
+  args: &str,
+) -> Result<CommandOutcome, ApiError> {
+  let body = serde_json::to_string(&BotCommandPayload {
+      command: command.name.clone(),
+      args: args.to_owned(),
+      room_id: ctx.room_id,
+      user_id: ctx.user_id,
+  })
+  .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+  // The url is bot-supplied, so it goes through the same address checks as link previews.
+  let client = match bot_command_client(state, &command.url).await {
+      Ok(client) => client,
+      Err(e) => {
+          tracing::warn!(command = %command.name, error = %e, "Refusing to call bot command");
+          return Ok(CommandOutcome::reply(format!("/{} is not responding", command.name)));
+      }
+  };
+
+  let timestamp = Utc::now().timestamp();
+  let response = client
+      .post(&command.url)
+      .header(reqwest::header::CONTENT_TYPE, "application/json")
+      .header(SIGNATURE_HEADER, sign_payload(&command.secret, timestamp, &body))
+      .header(TIMESTAMP_HEADER, timestamp.to_string())
+      .body(body)
+      .send()
+      .await;
+
+  let body = match response {
+      Ok(response) if response.status().is_success() => {
+          outbound::read_capped(response, outbound::MAX_REPLY_BYTES).await
+      }
+      _ => return Ok(CommandOutcome::reply(format!("/{} is not responding", command.name))),
+  };
+  let body = match body {
+      Ok(body) => body,
+      Err(e) => {
+          tracing::warn!(command = %command.name, error = %e, "Bot command reply refused");
+          return Ok(CommandOutcome::reply(format!("/{} is not responding", command.name)));
+      }
+  };
+  let reply = serde_json::from_slice::<BotCommandReply>(&body).ok();
+  let Some(reply) = reply else {
+      return Ok(CommandOutcome::default());
+  };
+  let Some(content) = reply.content.filter(|content| !content.trim().is_empty()) else {
+      return Ok(CommandOutcome::default());
+  };
+
+  if reply.ephemeral {
+      return Ok(CommandOutcome::reply(content));
+  }
+
+  let bot_id = Uuid::parse_str(&command.bot_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid bot id".into()))?;
+  let message = chat::send_message(state, ctx.room_id, bot_id, content, MessageFormat::Plain).await?;
+  Ok(CommandOutcome {
+      message: Some(message),
+      ..Default::default()
+  })
+}
+
+async fn bot_command_client(state: &AppState, url: &str) -> anyhow::Result<reqwest::Client> {
+  let url = Url::parse(url)?;
+  let builder = outbound::pinned_client(&url, state.config.integrations.allow_private_networks).await?;
+  Ok(builder.timeout(BOT_COMMAND_TIMEOUT).build()?)
+}
+
+struct InviteCommand;
+
+#[async_trait]
+impl CommandHandler for InviteCommand {
+  fn usage(&self) -> &'static str {
+    "/invite @username"
+  }
+
+  async fn run(&self, state: &AppState, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, ApiError> {
+    let username = args.trim_start_matches('@');
+    if username.is_empty() || username.contains(char::is_whitespace) {
+        return Ok(CommandOutcome::reply(format!("Usage: {}", self.usage())));
+    }
+
+    let mut users = user_repo::find_by_username(&state.db, username).await?;
+    let user = match users.len() {
+        0 => return Ok(CommandOutcome::reply(format!("No user named @{}", username))),
+        1 => users.remove(0),
+        _ => {
+            return Ok(CommandOutcome::reply(format!(
+                "Several users are named @{}; add them by id instead",
+                username
+            )))
+        }
+    };
+
+    if member_repo::find_by_room_and_user(&state.db, &ctx.room_id.to_string(), &user.id).await.is_ok() {
+        return Ok(CommandOutcome::reply(format!("@{} is already a member", user.username)));
+    }
+
+    let user_id = Uuid::parse_str(&user.id)
+        .map_err(|_| ApiError::InternalServerError("Invalid user id".into()))?;
+    room::add_member(state, ctx.room_id, ctx.user_id, AddMemberRequest { user_id }).await?;
+
+    Ok(CommandOutcome::reply(format!("Invited @{}", user.username)))
+  }
+}
+
+struct TopicCommand;
+
+#[async_trait]
+impl CommandHandler for TopicCommand {
+  fn usage(&self) -> &'static str {
+    "/topic [new topic]"
+  }
+
+  async fn run(&self, state: &AppState, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, ApiError> {
+    let room = room_repo::find_by_id(&state.db, &ctx.room_id.to_string()).await?;
+
+    if args.is_empty() {
+        let reply = match &room.topic {
+            Some(topic) => format!("Topic: {}", topic),
+            None => "No topic set".to_string(),
+        };
+        return Ok(CommandOutcome::reply(reply));
+    }
+    // Reading the topic is open to members; changing it is up to the owner.
+    moderation::ensure_owner(state, ctx.room_id, ctx.user_id).await?;
+    moderation::ensure_can_post(state, ctx.room_id, ctx.user_id).await?;
+    if args.chars().count() > MAX_TOPIC_LEN {
+        return Ok(CommandOutcome::reply(format!("Topic is too long (max {} chars)", MAX_TOPIC_LEN)));
+    }
+
+    let room = room_repo::update_topic(&state.db, room, Some(args.to_owned())).await?;
+    Ok(CommandOutcome {
+        reply: Some("Topic updated".into()),
+        events: vec![WsOutboundMessage::RoomUpdated(room::to_response(room)?)],
+        ..Default::default()
+    })
+  }
+}
+
+struct MeCommand;
+
+#[async_trait]
+impl CommandHandler for MeCommand {
+  fn usage(&self) -> &'static str {
+    "/me <action>"
+  }
+
+  async fn run(&self, state: &AppState, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, ApiError> {
+    if args.is_empty() {
+        return Ok(CommandOutcome::reply(format!("Usage: {}", self.usage())));
+    }
+
+    let user = user_repo::find_by_id(&state.db, &ctx.user_id.to_string())
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+    let content = format!("* {} {}", user.username, args);
+    let message = chat::send_message(state, ctx.room_id, ctx.user_id, content, MessageFormat::Plain).await?;
+
+    Ok(CommandOutcome {
+        message: Some(message),
+        ..Default::default()
+    })
+  }
+}
+
+struct LeaveCommand;
+
+#[async_trait]
+impl CommandHandler for LeaveCommand {
+  fn usage(&self) -> &'static str {
+    "/leave"
+  }
+
+  async fn run(&self, state: &AppState, ctx: &CommandContext, _args: &str) -> Result<CommandOutcome, ApiError> {
+    room::leave_room(state, ctx.room_id, ctx.user_id).await?;
+
+    Ok(CommandOutcome {
+        reply: Some("You left the room".into()),
+        events: vec![WsOutboundMessage::MemberRemoved(MemberEventDto {
+            room_id: ctx.room_id,
+            user_id: ctx.user_id,
+        })],
+        ..Default::default()
+    })
+  }
+}
+
+struct MuteCommand;
+
+#[async_trait]
+impl CommandHandler for MuteCommand {
+  fn usage(&self) -> &'static str {
+    "/mute [off]"
+  }
+
+  async fn run(&self, state: &AppState, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, ApiError> {
+    let muted = match args {
+        "" | "on" => true,
+        "off" => false,
+        _ => return Ok(CommandOutcome::reply(format!("Usage: {}", self.usage()))),
+    };
+
+    notification::update_notify_settings(
+        state,
+        ctx.room_id,
+        ctx.user_id,
+        UpdateNotifySettingsRequest { level: None, muted: Some(muted) },
+    )
+    .await?;
+
+    let reply = if muted { "Notifications muted for this room" } else { "Notifications unmuted for this room" };
+    Ok(CommandOutcome::reply(reply))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+  use crate::{
+    config::Config,
+    dtos::{admin::UserRole, room::RoomRole},
+    entities::{room::Model as RoomModel, room_mute::Model as RoomMuteModel},
+    test_support,
+  };
+
+  #[test]
+  fn builtins_cannot_be_shadowed() {
+    let registry = CommandRegistry::with_builtins();
+    for name in ["invite", "topic", "me", "leave", "mute"] {
+        assert!(registry.get(name).is_some());
+    }
+    assert!(registry.is_builtin("TOPIC"));
+    assert!(!registry.is_builtin("deploy"));
+  }
+
+  fn bot_command(url: String) -> BotCommandModel {
+    BotCommandModel {
+      id: Uuid::new_v4().to_string(),
+      bot_id: Uuid::new_v4().to_string(),
+      name: "deploy".into(),
+      description: String::new(),
+      url,
+      secret: "command-secret".into(),
+      created_at: Utc::now(),
+    }
+  }
+
+  fn state(allow_private_networks: bool) -> AppState {
+    let mut config = Config::default();
+    config.integrations.allow_private_networks = allow_private_networks;
+    test_support::state_with_config(test_support::mock_db().into_connection(), config)
+  }
+
+  fn ctx() -> CommandContext {
+    CommandContext { room_id: Uuid::new_v4(), user_id: Uuid::new_v4() }
+  }
+
+  #[tokio::test]
+  async fn bot_command_refuses_private_addresses() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(200)).expect(0).mount(&server).await;
+
+    let outcome = run_bot_command(&state(false), &ctx(), bot_command(server.uri()), "now").await.unwrap();
+
+    assert_eq!(outcome.reply.as_deref(), Some("/deploy is not responding"));
+  }
+
+  #[tokio::test]
+  async fn bot_command_returns_ephemeral_reply() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "content": "Deploying", "ephemeral": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let command = bot_command(server.uri());
+
+    let outcome = run_bot_command(&state(true), &ctx(), command.clone(), "now").await.unwrap();
+
+    assert_eq!(outcome.reply.as_deref(), Some("Deploying"));
+    let requests = server.received_requests().await.unwrap();
+    let timestamp: i64 = requests[0].headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert_eq!(requests[0].headers[SIGNATURE_HEADER].to_str().unwrap(), sign_payload(&command.secret, timestamp, &body));
+  }
+
+  #[tokio::test]
+  async fn bot_command_refuses_oversized_replies() {
+    let server = MockServer::start().await;
+    let reply = serde_json::json!({ "content": "x".repeat(outbound::MAX_REPLY_BYTES), "ephemeral": true });
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(reply))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let outcome = run_bot_command(&state(true), &ctx(), bot_command(server.uri()), "now").await.unwrap();
+
+    assert_eq!(outcome.reply.as_deref(), Some("/deploy is not responding"));
+  }
+
+  #[tokio::test]
+  async fn members_cannot_change_the_topic() {
+    let ctx = ctx();
+    let room = RoomModel { id: ctx.room_id.to_string(), ..test_support::room(false) };
+    let db = test_support::mock_db()
+        .append_query_results([vec![room]])
+        .append_query_results([vec![test_support::user(ctx.user_id, UserRole::User)]])
+        .append_query_results([vec![test_support::member(&ctx.room_id.to_string(), ctx.user_id, RoomRole::Member)]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let result = TopicCommand.run(&state, &ctx, "New topic").await;
+
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+  }
+
+  #[tokio::test]
+  async fn muted_owner_cannot_change_the_topic() {
+    let ctx = ctx();
+    let room = RoomModel { id: ctx.room_id.to_string(), ..test_support::room(false) };
+    let mute = RoomMuteModel {
+      room_id: room.id.clone(),
+      user_id: ctx.user_id.to_string(),
+      created_by: None,
+      reason: None,
+      expires_at: None,
+      created_at: Utc::now(),
+    };
+    let db = test_support::mock_db()
+        .append_query_results([vec![room]])
+        .append_query_results([vec![test_support::user(ctx.user_id, UserRole::User)]])
+        .append_query_results([vec![test_support::member(&ctx.room_id.to_string(), ctx.user_id, RoomRole::Owner)]])
+        .append_query_results([vec![mute]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let result = TopicCommand.run(&state, &ctx, "New topic").await;
+
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+  }
+}
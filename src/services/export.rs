@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use async_zip::{tokio::write::ZipFileWriter, Compression, ZipEntryBuilder};
+use axum::body::Body;
+use chrono::{DateTime, Utc};
+use futures::AsyncWriteExt as _;
+use sea_orm::{sea_query::SimpleExpr, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use tokio::io::{AsyncWriteExt as _, DuplexStream};
+use tokio_util::io::ReaderStream;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::{
+  database::{AppState, SharedState},
+  dtos::{
+    chat::{MessageDto, MessageFormat},
+    export::{ExportedMembership, ExportedMessage, ExportedProfile, RoomExportFormat, RoomExportRecord},
+    room::RoomResponse,
+  },
+  entities::message::{Column as MessageColumn, Entity as MessageEntity},
+  repositories::{room as room_repo, room_member as member_repo, user as user_repo},
+  response::{ApiError, FileDownload},
+  services::{chat, moderation, poll, room},
+};
+
+const PAGE_SIZE: u64 = 500;
+// Exports are written into an in-memory pipe of this size; the writer waits while the client catches up.
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+// Room moderators (and global admins) download the full history of a room.
+pub async fn export_room(
+  state: SharedState,
+  room_id: Uuid,
+  user_id: Uuid,
+  format: RoomExportFormat,
+) -> Result<FileDownload, ApiError> {
+  moderation::ensure_moderator(&state, room_id, user_id).await?;
+  let room = room::get_room(&state, room_id).await?;
+
+  let (writer, reader) = tokio::io::duplex(PIPE_CAPACITY);
+  let date = Utc::now().format("%Y%m%d");
+  let (content_type, file_name) = match format {
+      RoomExportFormat::Ndjson => ("application/x-ndjson", format!("room-{}-{}.ndjson", room_id, date)),
+      RoomExportFormat::Html => ("application/zip", format!("room-{}-{}.zip", room_id, date)),
+  };
+
+  tokio::spawn(async move {
+    let pager = MessagePager::new(MessageColumn::RoomId.eq(room_id.to_string()));
+    let result = match format {
+        RoomExportFormat::Ndjson => write_room_ndjson(&state, writer, pager, room, user_id).await,
+        RoomExportFormat::Html => write_room_html(&state, writer, pager, room).await,
+    };
+    // Headers are already sent, so a failure can only cut the download short.
+    if let Err(e) = result {
+        tracing::error!(%room_id, error = %e, "Room export failed");
+    }
+  }
+  .in_current_span());
+
+  Ok(FileDownload { content_type, file_name, body: Body::from_stream(ReaderStream::new(reader)) })
+}
+
+// Everything stored about the caller: profile, memberships and every message they wrote,
+// including in rooms they have since left.
+pub async fn export_account(state: SharedState, user_id: Uuid) -> Result<FileDownload, ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+  let profile = ExportedProfile {
+      id: user_id,
+      username: user.username,
+      email: user.email,
+      email_verified_at: user.email_verified_at,
+      role: user.role,
+      status: user.status,
+      created_at: user.created_at,
+  };
+
+  let (writer, reader) = tokio::io::duplex(PIPE_CAPACITY);
+  tokio::spawn(async move {
+    let pager = MessagePager::new(MessageColumn::SenderId.eq(user_id.to_string()));
+    if let Err(e) = write_account_json(&state, writer, pager, profile).await {
+        tracing::error!(%user_id, error = %e, "Account export failed");
+    }
+  }
+  .in_current_span());
+
+  Ok(FileDownload {
+      content_type: "application/json",
+      file_name: format!("account-{}-{}.json", user_id, Utc::now().format("%Y%m%d")),
+      body: Body::from_stream(ReaderStream::new(reader)),
+  })
+}
+
+// Walks matching messages oldest first, one page in memory at a time.
+struct MessagePager {
+  filter: SimpleExpr,
+  after: Option<(DateTime<Utc>, String)>,
+  usernames: HashMap<Uuid, Option<String>>,
+}
+
+impl MessagePager {
+  fn new(filter: SimpleExpr) -> Self {
+    Self { filter, after: None, usernames: HashMap::new() }
+  }
+
+  async fn next_page(&mut self, state: &AppState) -> Result<Vec<ExportedMessage>, ApiError> {
+    let mut query = MessageEntity::find().filter(self.filter.clone());
+    if let Some((created_at, id)) = &self.after {
+        query = query.filter(
+            Condition::any()
+                .add(MessageColumn::CreatedAt.gt(*created_at))
+                .add(MessageColumn::CreatedAt.eq(*created_at).and(MessageColumn::Id.gt(id.clone()))),
+        );
+    }
+    let models = query
+        .order_by_asc(MessageColumn::CreatedAt)
+        .order_by_asc(MessageColumn::Id)
+        .limit(PAGE_SIZE)
+        .all(&state.db)
+        .await?;
+    if let Some(last) = models.last() {
+        self.after = Some((last.created_at, last.id.clone()));
+    }
+
+    let mut messages = models.into_iter().map(chat::to_dto).collect::<Result<Vec<_>, _>>()?;
+    chat::attach_previews(state, &mut messages).await?;
+    poll::attach_polls(state, &mut messages, None).await?;
+    self.load_usernames(state, &messages).await?;
+
+    Ok(messages
+        .into_iter()
+        .map(|message| ExportedMessage {
+            sender_username: self.usernames.get(&message.sender_id).cloned().flatten(),
+            message,
+        })
+        .collect())
+  }
+
+  async fn load_usernames(&mut self, state: &AppState, messages: &[MessageDto]) -> Result<(), ApiError> {
+    let mut missing: Vec<Uuid> = messages
+        .iter()
+        .map(|m| m.sender_id)
+        .filter(|id| !self.usernames.contains_key(id))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let users = user_repo::list_by_ids(&state.db, missing.iter().map(Uuid::to_string).collect()).await?;
+    for id in missing {
+        let username = users.iter().find(|u| u.id == id.to_string()).map(|u| u.username.clone());
+        self.usernames.insert(id, username);
+    }
+    Ok(())
+  }
+}
+
+async fn write_room_ndjson(
+  state: &AppState,
+  mut writer: DuplexStream,
+  mut pager: MessagePager,
+  room: RoomResponse,
+  exported_by: Uuid,
+) -> anyhow::Result<()> {
+  let header = RoomExportRecord::Room { room, exported_by, exported_at: Utc::now() };
+  writer.write_all(&ndjson_line(&header)?).await?;
+
+  loop {
+      let page = pager.next_page(state).await?;
+      if page.is_empty() {
+          break;
+      }
+      for message in page {
+          writer.write_all(&ndjson_line(&RoomExportRecord::Message(message))?).await?;
+      }
+  }
+
+  writer.shutdown().await?;
+  Ok(())
+}
+
+async fn write_room_html(
+  state: &AppState,
+  writer: DuplexStream,
+  mut pager: MessagePager,
+  room: RoomResponse,
+) -> anyhow::Result<()> {
+  let mut zip = ZipFileWriter::with_tokio(writer);
+  let entry = ZipEntryBuilder::new("index.html".to_string().into(), Compression::Deflate);
+  let mut html = zip.write_entry_stream(entry).await?;
+
+  let title = escape_html(&room.name);
+  html.write_all(
+      format!(
+          "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<p class=\"meta\">Exported {}</p>\n",
+          HTML_STYLE,
+          Utc::now().to_rfc3339(),
+      )
+      .as_bytes(),
+  )
+  .await?;
+
+  loop {
+      let page = pager.next_page(state).await?;
+      if page.is_empty() {
+          break;
+      }
+      for message in page {
+          html.write_all(render_message(&message).as_bytes()).await?;
+      }
+  }
+
+  html.write_all(b"</body>\n</html>\n").await?;
+  html.close().await?;
+  let mut writer = zip.close().await?.into_inner();
+  writer.shutdown().await?;
+  Ok(())
+}
+
+// One JSON document, written piece by piece so the message list never sits in memory.
+async fn write_account_json(
+  state: &AppState,
+  mut writer: DuplexStream,
+  mut pager: MessagePager,
+  profile: ExportedProfile,
+) -> anyhow::Result<()> {
+  let memberships = memberships(state, profile.id).await?;
+
+  writer.write_all(b"{\"exported_at\":").await?;
+  writer.write_all(&serde_json::to_vec(&Utc::now())?).await?;
+  writer.write_all(b",\"profile\":").await?;
+  writer.write_all(&serde_json::to_vec(&profile)?).await?;
+  writer.write_all(b",\"memberships\":").await?;
+  writer.write_all(&serde_json::to_vec(&memberships)?).await?;
+  writer.write_all(b",\"messages\":[").await?;
+
+  let mut first = true;
+  loop {
+      let page = pager.next_page(state).await?;
+      if page.is_empty() {
+          break;
+      }
+      for message in page {
+          if !first {
+              writer.write_all(b",").await?;
+          }
+          first = false;
+          writer.write_all(&serde_json::to_vec(&message.message)?).await?;
+      }
+  }
+
+  writer.write_all(b"]}").await?;
+  writer.shutdown().await?;
+  Ok(())
+}
+
+async fn memberships(state: &AppState, user_id: Uuid) -> Result<Vec<ExportedMembership>, ApiError> {
+  let members = member_repo::list_by_user(&state.db, &user_id.to_string()).await?;
+
+  let mut result = Vec::with_capacity(members.len());
+  for member in members {
+      let room_name = room_repo::find_by_id(&state.db, &member.room_id).await.ok().map(|room| room.name);
+      result.push(ExportedMembership {
+          room_id: Uuid::parse_str(&member.room_id)
+              .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
+          room_name,
+          role: member.role,
+          notify_level: member.notify_level,
+          notifications_muted: member.notifications_muted,
+          joined_at: member.joined_at,
+      });
+  }
+  Ok(result)
+}
+
+fn ndjson_line(record: &RoomExportRecord) -> serde_json::Result<Vec<u8>> {
+  let mut line = serde_json::to_vec(record)?;
+  line.push(b'\n');
+  Ok(line)
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;max-width:60em;margin:2em auto;color:#222}\
+.meta,time{color:#888;font-size:.85em}.msg{border-bottom:1px solid #eee;padding:.5em 0}\
+.who{font-weight:bold;margin-right:.5em}.body{white-space:pre-wrap;margin-top:.25em}\
+.links,.poll{margin:.25em 0 0 1em;font-size:.9em}";
+
+fn render_message(exported: &ExportedMessage) -> String {
+  let message = &exported.message;
+  let sender = exported
+      .sender_username
+      .clone()
+      .unwrap_or_else(|| format!("deleted user {}", message.sender_id));
+
+  // Markdown messages already carry sanitized HTML; plain text is escaped here.
+  let body = match (&message.format, &message.html) {
+      (MessageFormat::Markdown, Some(html)) => html.clone(),
+      _ => escape_html(&message.content),
+  };
+
+  let mut out = format!(
+      "<div class=\"msg\" id=\"m-{}\"><span class=\"who\">{}</span><time>{}</time><div class=\"body\">{}</div>",
+      message.id,
+      escape_html(&sender),
+      message.created_at.to_rfc3339(),
+      body,
+  );
+  if !message.previews.is_empty() {
+      out.push_str("<ul class=\"links\">");
+      for preview in &message.previews {
+          let label = preview.title.as_deref().unwrap_or(&preview.url);
+          out.push_str(&format!(
+              "<li><a href=\"{}\">{}</a></li>",
+              escape_html(&preview.url),
+              escape_html(label)
+          ));
+      }
+      out.push_str("</ul>");
+  }
+  if let Some(poll) = &message.poll {
+      out.push_str("<ul class=\"poll\">");
+      for option in &poll.options {
+          out.push_str(&format!("<li>{} ({})</li>", escape_html(&option.text), option.votes));
+      }
+      out.push_str("</ul>");
+  }
+  out.push_str("</div>\n");
+  out
+}
+
+fn escape_html(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+  for c in value.chars() {
+      match c {
+          '&' => escaped.push_str("&amp;"),
+          '<' => escaped.push_str("&lt;"),
+          '>' => escaped.push_str("&gt;"),
+          '"' => escaped.push_str("&quot;"),
+          '\'' => escaped.push_str("&#39;"),
+          _ => escaped.push(c),
+      }
+  }
+  escaped
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::io::AsyncReadExt;
+
+  use super::*;
+  use crate::{
+    dtos::{admin::UserRole, room::RoomRole},
+    entities::{
+      message::Model as MessageModel, message_link_preview::Model as MessageLinkPreviewModel, poll::Model as PollModel,
+    },
+    test_support,
+  };
+
+  fn exported(content: &str, sender_username: Option<&str>) -> ExportedMessage {
+    ExportedMessage {
+        message: test_support::message(Uuid::new_v4(), Uuid::new_v4(), content),
+        sender_username: sender_username.map(String::from),
+    }
+  }
+
+  #[test]
+  fn plain_text_is_escaped() {
+    let html = render_message(&exported("<script>alert('hi')</script> & co", Some("<b>mallory</b>")));
+    assert!(html.contains("&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt; &amp; co"), "{}", html);
+    assert!(html.contains("&lt;b&gt;mallory&lt;/b&gt;"), "{}", html);
+    assert!(!html.contains("<script>"));
+  }
+
+  #[test]
+  fn markdown_keeps_its_sanitized_html() {
+    let mut message = exported("**hi**", Some("alice"));
+    message.message.format = MessageFormat::Markdown;
+    message.message.html = Some("<p><strong>hi</strong></p>".into());
+    assert!(render_message(&message).contains("<div class=\"body\"><p><strong>hi</strong></p></div>"));
+  }
+
+  #[test]
+  fn deleted_senders_are_labelled() {
+    let message = exported("hi", None);
+    let html = render_message(&message);
+    assert!(html.contains(&format!("deleted user {}", message.message.sender_id)), "{}", html);
+  }
+
+  #[tokio::test]
+  async fn account_export_is_one_json_document() {
+    let user_id = Uuid::new_v4();
+    let room = test_support::room(false);
+    let message = test_support::message_row(&room.id, user_id, "hello");
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::member(&room.id, user_id, RoomRole::Member)]])
+        .append_query_results([vec![room.clone()]])
+        .append_query_results([vec![message.clone()]])
+        .append_query_results([Vec::<MessageLinkPreviewModel>::new()])
+        .append_query_results([Vec::<PollModel>::new()])
+        .append_query_results([vec![test_support::user(user_id, UserRole::User)]])
+        .append_query_results([Vec::<MessageModel>::new()])
+        .into_connection();
+    let state = test_support::state(db);
+    let user = test_support::user(user_id, UserRole::User);
+    let profile = ExportedProfile {
+        id: user_id,
+        username: user.username.clone(),
+        email: user.email,
+        email_verified_at: user.email_verified_at,
+        role: user.role,
+        status: user.status,
+        created_at: user.created_at,
+    };
+
+    let (writer, mut reader) = tokio::io::duplex(PIPE_CAPACITY);
+    let pager = MessagePager::new(MessageColumn::SenderId.eq(user_id.to_string()));
+    write_account_json(&state, writer, pager, profile).await.unwrap();
+    let mut body = Vec::new();
+    reader.read_to_end(&mut body).await.unwrap();
+
+    let document: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(document["profile"]["username"], user.username);
+    assert_eq!(document["memberships"][0]["room_name"], room.name);
+    assert_eq!(document["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(document["messages"][0]["content"], "hello");
+
+    // The second page continues after the last message seen rather than by offset.
+    let statements = test_support::statements(state);
+    let last = statements.last().unwrap();
+    assert!(last.contains("`messages`.`id` >"), "{}", last);
+    assert!(!last.contains("OFFSET"), "{}", last);
+  }
+}
@@ -0,0 +1,468 @@
+use std::{
+  collections::HashMap,
+  fmt,
+  sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::filter::{FilterAction, RegexRule, RoomFilterConfig, RoomFilterResponse},
+  repositories::room_filter_setting as filter_repo,
+  response::ApiError,
+  services::{moderation, unfurl},
+};
+
+const MAX_PATTERN_LEN: usize = 200;
+const MAX_REPLACEMENT_LEN: usize = 100;
+// Upper bound for one compiled pattern, so a rule can't blow up memory or matching time.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+// Short messages ("OK", "LOL") are not shouting.
+const MIN_LETTERS_FOR_CAPS: usize = 10;
+
+// Outcome of one filter over the current content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterVerdict {
+  Allow,
+  Rewrite(String),
+  Reject(String),
+}
+
+// A step of the content pipeline run on every message before it is stored.
+pub trait MessageFilter: Send + Sync + fmt::Debug {
+  fn apply(&self, content: &str) -> FilterVerdict;
+}
+
+// Filters run in order; each sees the content as rewritten by the previous ones.
+#[derive(Debug, Default)]
+pub struct FilterPipeline {
+  filters: Vec<Box<dyn MessageFilter>>,
+}
+
+impl FilterPipeline {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn push(&mut self, filter: impl MessageFilter + 'static) {
+    self.filters.push(Box::new(filter));
+  }
+
+  // Build the built-in filters a room has enabled. Fails on invalid rules.
+  pub fn from_config(config: &RoomFilterConfig) -> Result<Self, String> {
+    let mut pipeline = Self::new();
+
+    if let Some(filter) = WordListFilter::new(&config.blocked_words, config.blocked_words_action)? {
+        pipeline.push(filter);
+    }
+    if !config.regex_rules.is_empty() {
+        pipeline.push(RegexFilter::new(&config.regex_rules)?);
+    }
+    if !config.link_allowlist.is_empty() || !config.link_denylist.is_empty() {
+        pipeline.push(LinkFilter::new(&config.link_allowlist, &config.link_denylist));
+    }
+    if let Some(max) = config.max_repeated_chars {
+        pipeline.push(RepetitionFilter { max: max as usize });
+    }
+    if let Some(max_ratio) = config.max_caps_ratio {
+        pipeline.push(CapsFilter { max_ratio });
+    }
+
+    Ok(pipeline)
+  }
+
+  // Returns the content to store, or the reason it was rejected.
+  pub fn run(&self, content: &str) -> Result<String, String> {
+    let mut current = content.to_string();
+    for filter in &self.filters {
+        match filter.apply(&current) {
+            FilterVerdict::Allow => {}
+            FilterVerdict::Rewrite(rewritten) => current = rewritten,
+            FilterVerdict::Reject(reason) => return Err(reason),
+        }
+    }
+    Ok(current)
+  }
+}
+
+// Blocks or masks whole words, case-insensitively.
+#[derive(Debug)]
+pub struct WordListFilter {
+  pattern: Regex,
+  action: FilterAction,
+}
+
+impl WordListFilter {
+  pub fn new(words: &[String], action: FilterAction) -> Result<Option<Self>, String> {
+    let words: Vec<String> = words
+        .iter()
+        .map(|w| w.trim())
+        .filter(|w| !w.is_empty())
+        .map(regex::escape)
+        .collect();
+    if words.is_empty() {
+        return Ok(None);
+    }
+
+    let pattern = RegexBuilder::new(&format!(r"\b(?:{})\b", words.join("|")))
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid blocked word list: {}", e))?;
+    Ok(Some(Self { pattern, action }))
+  }
+}
+
+impl MessageFilter for WordListFilter {
+  fn apply(&self, content: &str) -> FilterVerdict {
+    if !self.pattern.is_match(content) {
+        return FilterVerdict::Allow;
+    }
+    match self.action {
+        FilterAction::Reject => FilterVerdict::Reject("Message contains a blocked word".into()),
+        FilterAction::Mask => FilterVerdict::Rewrite(mask(&self.pattern, content, None)),
+    }
+  }
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+  pattern: Regex,
+  action: FilterAction,
+  replacement: Option<String>,
+}
+
+// Moderator-supplied regular expressions, applied in order.
+#[derive(Debug)]
+pub struct RegexFilter {
+  rules: Vec<CompiledRule>,
+}
+
+impl RegexFilter {
+  pub fn new(rules: &[RegexRule]) -> Result<Self, String> {
+    let rules = rules
+        .iter()
+        .map(|rule| {
+          if rule.pattern.is_empty() || rule.pattern.len() > MAX_PATTERN_LEN {
+              return Err(format!("Patterns must be between 1 and {} characters", MAX_PATTERN_LEN));
+          }
+          if rule.replacement.as_ref().is_some_and(|r| r.len() > MAX_REPLACEMENT_LEN) {
+              return Err(format!("Replacements must be at most {} characters", MAX_REPLACEMENT_LEN));
+          }
+          let pattern = RegexBuilder::new(&rule.pattern)
+              .size_limit(REGEX_SIZE_LIMIT)
+              .build()
+              .map_err(|e| format!("Invalid pattern '{}': {}", rule.pattern, e))?;
+          Ok(CompiledRule { pattern, action: rule.action, replacement: rule.replacement.clone() })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(Self { rules })
+  }
+}
+
+impl MessageFilter for RegexFilter {
+  fn apply(&self, content: &str) -> FilterVerdict {
+    let mut current = content.to_string();
+    for rule in &self.rules {
+        if !rule.pattern.is_match(&current) {
+            continue;
+        }
+        match rule.action {
+            FilterAction::Reject => return FilterVerdict::Reject("Message matches a blocked pattern".into()),
+            FilterAction::Mask => current = mask(&rule.pattern, &current, rule.replacement.as_deref()),
+        }
+    }
+    if current == content { FilterVerdict::Allow } else { FilterVerdict::Rewrite(current) }
+  }
+}
+
+// Rejects links to denied hosts, or to any host outside a non-empty allow-list.
+#[derive(Debug)]
+pub struct LinkFilter {
+  allow: Vec<String>,
+  deny: Vec<String>,
+}
+
+impl LinkFilter {
+  pub fn new(allow: &[String], deny: &[String]) -> Self {
+    let normalize = |hosts: &[String]| {
+        hosts
+            .iter()
+            .map(|h| h.trim().trim_start_matches("*.").trim_end_matches('.').to_lowercase())
+            .filter(|h| !h.is_empty())
+            .collect()
+    };
+    Self { allow: normalize(allow), deny: normalize(deny) }
+  }
+}
+
+fn host_matches(host: &str, entry: &str) -> bool {
+  host == entry || host.strip_suffix(entry).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+impl MessageFilter for LinkFilter {
+  fn apply(&self, content: &str) -> FilterVerdict {
+    for url in unfurl::find_urls(content, usize::MAX) {
+        let Some(host) = url.host_str().map(str::to_lowercase) else {
+            continue;
+        };
+        if self.deny.iter().any(|entry| host_matches(&host, entry)) {
+            return FilterVerdict::Reject(format!("Links to {} are not allowed in this room", host));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|entry| host_matches(&host, entry)) {
+            return FilterVerdict::Reject(format!("Links to {} are not allowed in this room", host));
+        }
+    }
+    FilterVerdict::Allow
+  }
+}
+
+// Rejects messages that are mostly capital letters.
+#[derive(Debug)]
+pub struct CapsFilter {
+  max_ratio: f32,
+}
+
+impl MessageFilter for CapsFilter {
+  fn apply(&self, content: &str) -> FilterVerdict {
+    let letters = content.chars().filter(|c| c.is_alphabetic()).count();
+    if letters < MIN_LETTERS_FOR_CAPS {
+        return FilterVerdict::Allow;
+    }
+    let upper = content.chars().filter(|c| c.is_uppercase()).count();
+    if upper as f32 / letters as f32 > self.max_ratio {
+        return FilterVerdict::Reject("Message has too many capital letters".into());
+    }
+    FilterVerdict::Allow
+  }
+}
+
+// Shortens runs of the same character ("sooooo" -> "sooo").
+#[derive(Debug)]
+pub struct RepetitionFilter {
+  max: usize,
+}
+
+impl MessageFilter for RepetitionFilter {
+  fn apply(&self, content: &str) -> FilterVerdict {
+    let mut result = String::with_capacity(content.len());
+    let mut previous = None;
+    let mut run = 0;
+    for c in content.chars() {
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        previous = Some(c);
+        if run <= self.max {
+            result.push(c);
+        }
+    }
+    if result.len() == content.len() { FilterVerdict::Allow } else { FilterVerdict::Rewrite(result) }
+  }
+}
+
+fn mask(pattern: &Regex, content: &str, replacement: Option<&str>) -> String {
+  pattern
+      .replace_all(content, |caps: &regex::Captures| match replacement {
+          Some(replacement) => replacement.to_string(),
+          None => "*".repeat(caps[0].chars().count()),
+      })
+      .into_owned()
+}
+
+// A compiled pipeline and the settings version it was built from.
+type CachedPipeline = (DateTime<Utc>, Arc<FilterPipeline>);
+
+// Compiled pipelines per room, reused until the room's settings change.
+#[derive(Clone, Default)]
+pub struct FilterCache {
+  rooms: Arc<Mutex<HashMap<String, CachedPipeline>>>,
+}
+
+impl fmt::Debug for FilterCache {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let rooms = self.rooms.lock().map(|rooms| rooms.len()).unwrap_or_default();
+    f.debug_struct("FilterCache").field("rooms", &rooms).finish()
+  }
+}
+
+async fn pipeline_for_room(state: &AppState, room_id: Uuid) -> Result<Option<Arc<FilterPipeline>>, ApiError> {
+  let room_id = room_id.to_string();
+  let Some(settings) = filter_repo::find_by_room_id(&state.db, &room_id).await? else {
+      return Ok(None);
+  };
+
+  if let Some((updated_at, pipeline)) = state.filters.rooms.lock().expect("filter cache lock poisoned").get(&room_id)
+      && *updated_at == settings.updated_at
+  {
+      return Ok(Some(pipeline.clone()));
+  }
+
+  let config: RoomFilterConfig = serde_json::from_str(&settings.config)
+      .map_err(|e| ApiError::InternalServerError(format!("Invalid filter settings: {}", e)))?;
+  let pipeline = Arc::new(FilterPipeline::from_config(&config).map_err(ApiError::InternalServerError)?);
+  state
+      .filters
+      .rooms
+      .lock()
+      .expect("filter cache lock poisoned")
+      .insert(room_id, (settings.updated_at, pipeline.clone()));
+  Ok(Some(pipeline))
+}
+
+// Run the room's filters over message content. Returns the content to store.
+pub async fn apply(state: &AppState, room_id: Uuid, content: &str) -> Result<String, ApiError> {
+  let Some(pipeline) = pipeline_for_room(state, room_id).await? else {
+      return Ok(content.to_string());
+  };
+
+  let filtered = pipeline.run(content).map_err(ApiError::BadRequest)?;
+  if filtered.trim().is_empty() {
+      return Err(ApiError::BadRequest("Message content cannot be empty".into()));
+  }
+  Ok(filtered)
+}
+
+pub async fn get_room_filters(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<RoomFilterResponse, ApiError> {
+  moderation::ensure_moderator(state, room_id, user_id).await?;
+
+  let settings = filter_repo::find_by_room_id(&state.db, &room_id.to_string()).await?;
+  Ok(match settings {
+      Some(settings) => RoomFilterResponse {
+          config: serde_json::from_str(&settings.config)
+              .map_err(|e| ApiError::InternalServerError(format!("Invalid filter settings: {}", e)))?,
+          updated_at: Some(settings.updated_at),
+      },
+      None => RoomFilterResponse { config: RoomFilterConfig::default(), updated_at: None },
+  })
+}
+
+pub async fn update_room_filters(
+  state: &AppState,
+  room_id: Uuid,
+  user_id: Uuid,
+  config: RoomFilterConfig,
+) -> Result<RoomFilterResponse, ApiError> {
+  moderation::ensure_moderator(state, room_id, user_id).await?;
+
+  // Reject rules that would not compile now rather than on the next message.
+  FilterPipeline::from_config(&config).map_err(ApiError::BadRequest)?;
+
+  let stored = serde_json::to_string(&config)
+      .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+  let settings = filter_repo::upsert(&state.db, &room_id.to_string(), stored, &user_id.to_string()).await?;
+  // Timestamps only have second precision, so don't rely on them to notice this change.
+  state.filters.rooms.lock().expect("filter cache lock poisoned").remove(&room_id.to_string());
+
+  Ok(RoomFilterResponse { config, updated_at: Some(settings.updated_at) })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{entities::room_filter_setting::Model as RoomFilterSettingModel, test_support};
+
+  fn words(list: &[&str]) -> Vec<String> {
+    list.iter().map(|w| w.to_string()).collect()
+  }
+
+  fn rule(pattern: &str, action: FilterAction, replacement: Option<&str>) -> RegexRule {
+    RegexRule { pattern: pattern.into(), action, replacement: replacement.map(str::to_string) }
+  }
+
+  #[test]
+  fn masks_or_rejects_whole_blocked_words() {
+    let mask = WordListFilter::new(&words(&["darn", " "]), FilterAction::Mask).unwrap().unwrap();
+    assert_eq!(mask.apply("Darn it"), FilterVerdict::Rewrite("**** it".into()));
+    assert_eq!(mask.apply("darnation"), FilterVerdict::Allow);
+
+    let reject = WordListFilter::new(&words(&["darn"]), FilterAction::Reject).unwrap().unwrap();
+    assert!(matches!(reject.apply("oh DARN"), FilterVerdict::Reject(_)));
+    assert!(WordListFilter::new(&words(&["", "  "]), FilterAction::Reject).unwrap().is_none());
+  }
+
+  #[test]
+  fn regex_rules_apply_in_order() {
+    let filter = RegexFilter::new(&[
+        rule(r"\d{4}-\d{4}", FilterAction::Mask, Some("[card]")),
+        rule(r"(?i)free money", FilterAction::Reject, None),
+    ])
+    .unwrap();
+    assert_eq!(filter.apply("pay 1234-5678"), FilterVerdict::Rewrite("pay [card]".into()));
+    assert!(matches!(filter.apply("FREE MONEY 1234-5678"), FilterVerdict::Reject(_)));
+    assert_eq!(filter.apply("hello"), FilterVerdict::Allow);
+  }
+
+  #[test]
+  fn refuses_invalid_or_oversized_patterns() {
+    assert!(RegexFilter::new(&[rule("(", FilterAction::Mask, None)]).is_err());
+    assert!(RegexFilter::new(&[rule("", FilterAction::Mask, None)]).is_err());
+    assert!(RegexFilter::new(&[rule(&"a".repeat(MAX_PATTERN_LEN + 1), FilterAction::Mask, None)]).is_err());
+    assert!(RegexFilter::new(&[rule("a", FilterAction::Mask, Some(&"b".repeat(MAX_REPLACEMENT_LEN + 1)))]).is_err());
+    // Compiles to far more than the size limit.
+    assert!(RegexFilter::new(&[rule(r"\w{1000}\w{1000}", FilterAction::Mask, None)]).is_err());
+  }
+
+  #[test]
+  fn links_follow_the_allow_and_deny_lists() {
+    let deny = LinkFilter::new(&[], &words(&["*.evil.example"]));
+    assert!(matches!(deny.apply("see https://cdn.evil.example/x"), FilterVerdict::Reject(_)));
+    assert_eq!(deny.apply("see https://notevil.example/x"), FilterVerdict::Allow);
+
+    let allow = LinkFilter::new(&words(&["Docs.Example.com."]), &[]);
+    assert_eq!(allow.apply("read https://api.docs.example.com/v1"), FilterVerdict::Allow);
+    assert!(matches!(allow.apply("read https://example.org"), FilterVerdict::Reject(_)));
+    assert_eq!(allow.apply("no links here"), FilterVerdict::Allow);
+  }
+
+  #[test]
+  fn caps_and_repetition() {
+    let caps = CapsFilter { max_ratio: 0.7 };
+    assert!(matches!(caps.apply("WHY IS NOBODY ANSWERING"), FilterVerdict::Reject(_)));
+    assert_eq!(caps.apply("OK LOL"), FilterVerdict::Allow);
+    assert_eq!(caps.apply("Meeting with NASA and the ESA today"), FilterVerdict::Allow);
+
+    let repetition = RepetitionFilter { max: 3 };
+    assert_eq!(repetition.apply("sooooo gooood"), FilterVerdict::Rewrite("sooo goood".into()));
+    assert_eq!(repetition.apply("ééééé"), FilterVerdict::Rewrite("ééé".into()));
+    assert_eq!(repetition.apply("fine"), FilterVerdict::Allow);
+  }
+
+  #[test]
+  fn the_pipeline_feeds_each_filter_the_previous_output() {
+    let config = RoomFilterConfig {
+        blocked_words: words(&["heck"]),
+        max_repeated_chars: Some(2),
+        regex_rules: vec![rule(r"\*{4}", FilterAction::Reject, None)],
+        ..Default::default()
+    };
+    let pipeline = FilterPipeline::from_config(&config).unwrap();
+    assert_eq!(pipeline.run("whooo"), Ok("whoo".into()));
+    // The masked word becomes four asterisks, which the regex rule then rejects.
+    assert!(pipeline.run("what the heck").is_err());
+    assert_eq!(FilterPipeline::from_config(&RoomFilterConfig::default()).unwrap().run("as is"), Ok("as is".into()));
+  }
+
+  #[tokio::test]
+  async fn rooms_without_settings_are_not_filtered_and_compiled_pipelines_are_cached() {
+    let room_id = Uuid::new_v4();
+    let settings = RoomFilterSettingModel {
+      room_id: room_id.to_string(),
+      config: serde_json::to_string(&RoomFilterConfig { blocked_words: words(&["heck"]), ..Default::default() }).unwrap(),
+      updated_by: None,
+      updated_at: Utc::now(),
+    };
+    let db = test_support::mock_db()
+        .append_query_results([Vec::<RoomFilterSettingModel>::new(), vec![settings.clone()], vec![settings]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    assert_eq!(apply(&state, room_id, "heck").await.unwrap(), "heck");
+    assert_eq!(apply(&state, room_id, "heck yes").await.unwrap(), "**** yes");
+    let cached = state.filters.rooms.lock().unwrap().get(&room_id.to_string()).unwrap().1.clone();
+    assert_eq!(apply(&state, room_id, "oh heck").await.unwrap(), "oh ****");
+    let reused = state.filters.rooms.lock().unwrap().get(&room_id.to_string()).unwrap().1.clone();
+    assert!(Arc::ptr_eq(&cached, &reused));
+  }
+}
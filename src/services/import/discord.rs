@@ -0,0 +1,156 @@
+use std::{path::PathBuf, sync::LazyLock};
+
+use chrono::{DateTime, Utc};
+use regex::{Captures, Regex};
+use serde::Deserialize;
+
+use super::{invalid, ExternalMessage, ExternalUser, Importer};
+use crate::{database::DbPool, dtos::{import::ImportReport, room::RoomRole}, response::ApiError};
+
+const SOURCE: &str = "discord";
+
+#[derive(Deserialize)]
+struct DiscordExport {
+  channel: DiscordChannel,
+  #[serde(default)]
+  messages: Vec<DiscordMessage>,
+}
+
+#[derive(Deserialize)]
+struct DiscordChannel {
+  id: String,
+  name: String,
+  topic: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscordMessage {
+  id: String,
+  #[serde(rename = "type")]
+  kind: String,
+  timestamp: DateTime<Utc>,
+  #[serde(default)]
+  content: String,
+  author: DiscordUser,
+  #[serde(default)]
+  attachments: Vec<DiscordAttachment>,
+  reference: Option<DiscordReference>,
+  #[serde(default)]
+  mentions: Vec<DiscordUser>,
+}
+
+#[derive(Clone, Deserialize)]
+struct DiscordUser {
+  id: String,
+  name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscordAttachment {
+  url: String,
+  file_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscordReference {
+  message_id: Option<String>,
+}
+
+// Channel exports in DiscordChatExporter's JSON format, one file per channel.
+// Discord exports carry no emails, so authors only match accounts from earlier imports.
+pub async fn import_discord(db: &DbPool, paths: &[PathBuf]) -> Result<ImportReport, ApiError> {
+  let mut importer = Importer::new(db, SOURCE);
+
+  for path in paths {
+      let raw = tokio::fs::read(path)
+          .await
+          .map_err(|e| ApiError::BadRequest(format!("Cannot open {}: {}", path.display(), e)))?;
+      let mut export: DiscordExport =
+          serde_json::from_slice(&raw).map_err(|e| invalid(SOURCE, format!("{}: {}", path.display(), e)))?;
+      export.messages.sort_by_key(|m| m.timestamp);
+
+      // Nobody owns a channel in Discord terms; whoever spoke first owns the room.
+      let created_at = export.messages.first().map(|m| m.timestamp).unwrap_or_else(Utc::now);
+      let Some(room_id) = importer
+          .room(&export.channel.id, &export.channel.name, export.channel.topic.as_deref(), created_at)
+          .await?
+      else {
+          continue;
+      };
+      if let Some(first) = export.messages.first() {
+          let author = first.author.clone();
+          if let Some(owner_id) = user(&mut importer, author).await? {
+              importer.member(&room_id, &owner_id, RoomRole::Owner).await?;
+          }
+      }
+
+      for message in export.messages {
+          import_message(&mut importer, &room_id, message).await?;
+      }
+  }
+
+  Ok(importer.report)
+}
+
+async fn user(importer: &mut Importer<'_>, author: DiscordUser) -> Result<Option<String>, ApiError> {
+  importer.user(ExternalUser { id: author.id, username: author.name, email: None }).await
+}
+
+async fn import_message(importer: &mut Importer<'_>, room_id: &str, message: DiscordMessage) -> Result<(), ApiError> {
+  // Joins, pins, boosts and similar system entries aren't conversation.
+  if message.kind != "Default" && message.kind != "Reply" {
+      return Ok(());
+  }
+  let Some(sender_id) = user(importer, message.author).await? else {
+      return Ok(());
+  };
+  for mentioned in &message.mentions {
+      user(importer, mentioned.clone()).await?;
+  }
+
+  let mut content = convert_mentions(importer, &message.content);
+  for attachment in &message.attachments {
+      content.push_str(&format!("\n{}: {}", attachment.file_name.as_deref().unwrap_or("file"), attachment.url));
+  }
+
+  importer
+      .message(room_id, ExternalMessage {
+          id: message.id,
+          sender_id,
+          content,
+          created_at: message.timestamp,
+          reply_to: message.reference.and_then(|r| r.message_id),
+      })
+      .await
+}
+
+static MENTION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<@!?(\d+)>").unwrap());
+
+// Exports usually render mentions already; raw <@id> ones left over become @username.
+fn convert_mentions(importer: &Importer<'_>, content: &str) -> String {
+  MENTION
+      .replace_all(content, |caps: &Captures| match importer.username(&caps[1]) {
+        Some(username) => format!("@{}", username),
+        None => caps[0].to_string(),
+      })
+      .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_support;
+
+  #[test]
+  fn raw_mentions_of_known_users_get_their_name() {
+    let db = test_support::mock_db().into_connection();
+    let mut importer = Importer::new(&db, SOURCE);
+    importer.users.insert("123".into(), Some(("local-1".into(), "alice".into())));
+
+    assert_eq!(convert_mentions(&importer, "hi <@123> and <@!123>"), "hi @alice and @alice");
+    assert_eq!(convert_mentions(&importer, "hi <@456>"), "hi <@456>");
+  }
+}
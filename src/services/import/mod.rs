@@ -0,0 +1,451 @@
+mod discord;
+mod slack;
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+  database::DbPool,
+  dtos::{
+    admin::{AccountStatus, UserRole},
+    chat::MessageFormat,
+    import::{ImportConflict, ImportReport},
+    room::RoomRole,
+  },
+  entities::{message::Model as MessageModel, room::Model as RoomModel, user::Model as UserModel},
+  repositories::{
+    import_mapping as mapping_repo, message as message_repo, room as room_repo, room_member as member_repo,
+    user as user_repo,
+  },
+  response::ApiError,
+  security::{generate_token, hash_password},
+};
+
+pub use discord::import_discord;
+pub use slack::import_slack;
+
+const USERNAME_MAX_CHARS: usize = 50;
+const ROOM_NAME_MAX_CHARS: usize = 255;
+const TOPIC_MAX_CHARS: usize = 250;
+
+// A person as the export describes them.
+struct ExternalUser {
+  id: String,
+  username: String,
+  email: Option<String>,
+}
+
+// A message whose sender and room are already resolved to local ids.
+struct ExternalMessage {
+  id: String,
+  sender_id: String,
+  content: String,
+  created_at: DateTime<Utc>,
+  // External id of the message this one replies to.
+  reply_to: Option<String>,
+}
+
+// Resolves export records to local rows through `import_mappings`, so running the same
+// archive again finds everything it created the first time instead of duplicating it.
+struct Importer<'a> {
+  db: &'a DbPool,
+  source: &'static str,
+  report: ImportReport,
+  // External user id to local id and username; `None` when the user could not be imported.
+  users: HashMap<String, Option<(String, String)>>,
+  members: HashSet<(String, String)>,
+}
+
+impl<'a> Importer<'a> {
+  fn new(db: &'a DbPool, source: &'static str) -> Self {
+    Self {
+        db,
+        source,
+        report: ImportReport { source: source.to_string(), ..Default::default() },
+        users: HashMap::new(),
+        members: HashSet::new(),
+    }
+  }
+
+  fn conflict(&mut self, kind: &str, external_id: &str, detail: impl Into<String>) {
+    self.report.conflicts.push(ImportConflict {
+        kind: kind.to_string(),
+        external_id: external_id.to_string(),
+        detail: detail.into(),
+    });
+  }
+
+  async fn mapped(&self, kind: &str, external_id: &str) -> Result<Option<String>, ApiError> {
+    Ok(mapping_repo::find(self.db, self.source, kind, external_id).await?.map(|m| m.local_id))
+  }
+
+  async fn remember(&self, kind: &str, external_id: &str, local_id: &str) -> Result<(), ApiError> {
+    mapping_repo::insert(self.db, self.source, kind, external_id, local_id).await?;
+    Ok(())
+  }
+
+  // Local id of an imported user, if `user` has been called for them.
+  fn user_id(&self, external_id: &str) -> Option<String> {
+    self.users.get(external_id).cloned().flatten().map(|(id, _)| id)
+  }
+
+  fn username(&self, external_id: &str) -> Option<&str> {
+    self.users.get(external_id)?.as_ref().map(|(_, username)| username.as_str())
+  }
+
+  // Existing accounts are matched by email; everyone else gets a new account without a usable password.
+  async fn user(&mut self, external: ExternalUser) -> Result<Option<String>, ApiError> {
+    if let Some(cached) = self.users.get(&external.id) {
+        return Ok(cached.clone().map(|(id, _)| id));
+    }
+    let resolved = self.resolve_user(&external).await?;
+    self.users.insert(external.id, resolved.clone());
+    Ok(resolved.map(|(id, _)| id))
+  }
+
+  async fn resolve_user(&mut self, external: &ExternalUser) -> Result<Option<(String, String)>, ApiError> {
+    if let Some(local_id) = self.mapped("user", &external.id).await? {
+        return match user_repo::find_by_id(self.db, &local_id).await? {
+            Some(user) => {
+              self.report.users_matched += 1;
+              Ok(Some((user.id, user.username)))
+            }
+            None => {
+              self.conflict("user", &external.id, "Account from an earlier import was deleted; their messages are skipped");
+              Ok(None)
+            }
+        };
+    }
+
+    let email = match external.email.as_deref().map(str::trim).filter(|e| !e.is_empty()) {
+        Some(email) => email.to_lowercase(),
+        None => {
+          self.conflict("user", &external.id, "No email in the export; created with a placeholder address");
+          format!("{}-{}@import.invalid", self.source, external.id.to_lowercase())
+        }
+    };
+
+    let user = match user_repo::find_by_email(self.db, &email).await? {
+        Some(user) => {
+          self.report.users_matched += 1;
+          user
+        }
+        None => {
+          let username = self.free_username(external).await?;
+          let password = hash_password(&generate_token("import"))
+              .map_err(|e| ApiError::InternalServerError(format!("Failed to hash password: {}", e)))?;
+          let user = user_repo::insert(self.db, UserModel {
+              id: Uuid::new_v4().to_string(),
+              username,
+              email,
+              password,
+              created_at: Utc::now(),
+              email_verified_at: None,
+              role: UserRole::User.as_str().to_string(),
+              status: AccountStatus::Active.as_str().to_string(),
+              suspended_until: None,
+              status_reason: None,
+              delete_after: None,
+          })
+          .await?;
+          self.report.users_created += 1;
+          user
+        }
+    };
+
+    self.remember("user", &external.id, &user.id).await?;
+    Ok(Some((user.id, user.username)))
+  }
+
+  // Usernames aren't unique in the schema, but two people sharing one is confusing; suffix and report.
+  async fn free_username(&mut self, external: &ExternalUser) -> Result<String, ApiError> {
+    let base: String = external.username.trim().chars().take(USERNAME_MAX_CHARS).collect();
+    let base = if base.chars().count() < 3 { format!("{}-{}", self.source, external.id) } else { base };
+
+    let candidates = [
+        base.clone(),
+        format!("{}-{}", base, self.source),
+        format!("{}-{}-{}", base, self.source, external.id),
+    ];
+    for candidate in &candidates {
+        let candidate: String = candidate.chars().take(USERNAME_MAX_CHARS).collect();
+        if user_repo::find_by_username(self.db, &candidate).await?.is_empty() {
+            if candidate != base {
+                self.conflict("user", &external.id, format!("Username {} is taken; imported as {}", base, candidate));
+            }
+            return Ok(candidate);
+        }
+    }
+
+    self.conflict("user", &external.id, format!("Username {} is taken; imported with a duplicate name", base));
+    Ok(base)
+  }
+
+  async fn room(
+    &mut self,
+    external_id: &str,
+    name: &str,
+    topic: Option<&str>,
+    created_at: DateTime<Utc>,
+  ) -> Result<Option<String>, ApiError> {
+    if let Some(local_id) = self.mapped("room", external_id).await? {
+        return match room_repo::find_by_id(self.db, &local_id).await {
+            Ok(room) => {
+              self.report.rooms_matched += 1;
+              Ok(Some(room.id))
+            }
+            Err(sea_orm::DbErr::RecordNotFound(_)) => {
+              self.conflict("room", external_id, "Room from an earlier import was deleted; its messages are skipped");
+              Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        };
+    }
+
+    let topic = topic
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| t.chars().take(TOPIC_MAX_CHARS).collect());
+    let room = room_repo::insert(self.db, RoomModel {
+        id: Uuid::new_v4().to_string(),
+        name: name.trim().chars().take(ROOM_NAME_MAX_CHARS).collect(),
+        topic,
+        slow_mode_seconds: 0,
+        retention_max_age_days: None,
+        retention_max_messages: None,
+        legal_hold: false,
+        is_direct: false,
+        created_at,
+    })
+    .await?;
+    self.report.rooms_created += 1;
+
+    self.remember("room", external_id, &room.id).await?;
+    Ok(Some(room.id))
+  }
+
+  // Adds the membership unless it exists; roles of existing members are left alone.
+  async fn member(&mut self, room_id: &str, user_id: &str, role: RoomRole) -> Result<(), ApiError> {
+    if !self.members.insert((room_id.to_string(), user_id.to_string())) {
+        return Ok(());
+    }
+    match member_repo::find_by_room_and_user(self.db, room_id, user_id).await {
+        Ok(_) => Ok(()),
+        Err(sea_orm::DbErr::RecordNotFound(_)) => {
+          member_repo::insert(self.db, room_id.to_string(), user_id.to_string(), role.as_str()).await?;
+          self.report.memberships_added += 1;
+          Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+  }
+
+  // Inserted directly with its original timestamp: no filters, notifications or live events for history.
+  async fn message(&mut self, room_id: &str, message: ExternalMessage) -> Result<(), ApiError> {
+    if self.mapped("message", &message.id).await?.is_some() {
+        self.report.messages_skipped += 1;
+        return Ok(());
+    }
+    let content = message.content.trim();
+    if content.is_empty() {
+        return Ok(());
+    }
+    self.member(room_id, &message.sender_id, RoomRole::Member).await?;
+
+    // Replies join the thread of the message they answer, which is the root's own id for a first reply.
+    let thread_id = match &message.reply_to {
+        Some(parent) => match self.mapped("message", parent).await? {
+            Some(parent_id) => message_repo::find_by_id(self.db, &parent_id)
+                .await?
+                .map(|parent| parent.thread_id.unwrap_or(parent.id)),
+            None => {
+              self.conflict("message", &message.id, "Replied-to message is not in the export; imported outside a thread");
+              None
+            }
+        },
+        None => None,
+    };
+
+    let stored = message_repo::insert(self.db, MessageModel {
+        id: Uuid::new_v4().to_string(),
+        room_id: room_id.to_string(),
+        sender_id: message.sender_id,
+        content: content.to_string(),
+        format: MessageFormat::Plain.as_str().to_string(),
+        rendered_html: None,
+        thread_id,
+        created_at: message.created_at,
+    })
+    .await?;
+    self.report.messages_imported += 1;
+
+    self.remember("message", &message.id, &stored.id).await
+  }
+}
+
+fn invalid(source: &str, detail: impl std::fmt::Display) -> ApiError {
+  ApiError::BadRequest(format!("Invalid {} export: {}", source, detail))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    entities::import_mapping::Model as ImportMappingModel,
+    test_support::{self, rows_affected},
+  };
+
+  fn mapping(kind: &str, external_id: &str, local_id: &str) -> ImportMappingModel {
+    ImportMappingModel {
+        source: "slack".into(),
+        kind: kind.into(),
+        external_id: external_id.into(),
+        local_id: local_id.into(),
+        created_at: Utc::now(),
+    }
+  }
+
+  fn external(id: &str, username: &str, email: Option<&str>) -> ExternalUser {
+    ExternalUser { id: id.into(), username: username.into(), email: email.map(String::from) }
+  }
+
+  fn reply(id: &str, sender_id: &str, reply_to: &str) -> ExternalMessage {
+    ExternalMessage {
+        id: id.into(),
+        sender_id: sender_id.into(),
+        content: "  me too  ".into(),
+        created_at: Utc::now(),
+        reply_to: Some(reply_to.into()),
+    }
+  }
+
+  #[tokio::test]
+  async fn messages_from_an_earlier_run_are_skipped() {
+    let db = test_support::mock_db()
+        .append_query_results([vec![mapping("message", "C1:1.0", &Uuid::new_v4().to_string())]])
+        .into_connection();
+    let mut importer = Importer::new(&db, "slack");
+
+    importer.message("room", reply("C1:1.0", "user", "C1:0.5")).await.unwrap();
+    assert_eq!(importer.report.messages_skipped, 1);
+    assert_eq!(importer.report.messages_imported, 0);
+    assert!(!db.into_transaction_log().iter().any(|t| format!("{:?}", t).contains("INSERT")));
+  }
+
+  #[tokio::test]
+  async fn mapped_users_are_matched_once() {
+    let user = test_support::user(Uuid::new_v4(), UserRole::User);
+    let db = test_support::mock_db()
+        .append_query_results([vec![mapping("user", "U1", &user.id)]])
+        .append_query_results([vec![user.clone()]])
+        .into_connection();
+    let mut importer = Importer::new(&db, "slack");
+
+    for _ in 0..2 {
+      let local = importer.user(external("U1", "alice", Some("alice@example.com"))).await.unwrap();
+      assert_eq!(local, Some(user.id.clone()));
+    }
+    assert_eq!(importer.report.users_matched, 1);
+    assert_eq!(importer.username("U1"), Some(user.username.as_str()));
+    assert_eq!(db.into_transaction_log().len(), 2);
+  }
+
+  #[tokio::test]
+  async fn users_deleted_since_an_earlier_run_are_reported() {
+    let db = test_support::mock_db()
+        .append_query_results([vec![mapping("user", "U1", &Uuid::new_v4().to_string())]])
+        .append_query_results([Vec::<UserModel>::new()])
+        .into_connection();
+    let mut importer = Importer::new(&db, "slack");
+
+    assert_eq!(importer.user(external("U1", "alice", None)).await.unwrap(), None);
+    assert_eq!(importer.user_id("U1"), None);
+    assert_eq!(importer.report.conflicts.len(), 1);
+    assert_eq!(importer.report.conflicts[0].kind, "user");
+  }
+
+  #[tokio::test]
+  async fn existing_accounts_are_matched_by_email() {
+    let user = test_support::user(Uuid::new_v4(), UserRole::User);
+    let db = test_support::mock_db()
+        .append_query_results([Vec::<ImportMappingModel>::new()])
+        .append_query_results([vec![user.clone()]])
+        .append_exec_results([rows_affected(1)])
+        .append_query_results([vec![mapping("user", "U1", &user.id)]])
+        .into_connection();
+    let mut importer = Importer::new(&db, "slack");
+
+    let local = importer.user(external("U1", "alice", Some(" Alice@Example.com "))).await.unwrap();
+    assert_eq!(local, Some(user.id));
+    assert_eq!(importer.report.users_matched, 1);
+    assert_eq!(importer.report.users_created, 0);
+
+    let statements: Vec<String> = db.into_transaction_log().iter().map(|t| format!("{:?}", t)).collect();
+    assert!(statements[1].contains("alice@example.com"), "{}", statements[1]);
+  }
+
+  #[tokio::test]
+  async fn taken_usernames_get_the_source_as_suffix() {
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::user(Uuid::new_v4(), UserRole::User)]])
+        .append_query_results([Vec::<UserModel>::new()])
+        .into_connection();
+    let mut importer = Importer::new(&db, "slack");
+
+    let username = importer.free_username(&external("U1", "alice", None)).await.unwrap();
+    assert_eq!(username, "alice-slack");
+    assert_eq!(importer.report.conflicts.len(), 1);
+  }
+
+  #[tokio::test]
+  async fn replies_join_the_thread_of_their_parent() {
+    let room_id = Uuid::new_v4().to_string();
+    let sender_id = Uuid::new_v4().to_string();
+    let root_id = Uuid::new_v4().to_string();
+    let parent = MessageModel {
+        thread_id: Some(root_id.clone()),
+        ..test_support::message_row(&room_id, Uuid::new_v4(), "first reply")
+    };
+    let stored = test_support::message_row(&room_id, Uuid::parse_str(&sender_id).unwrap(), "me too");
+    let db = test_support::mock_db()
+        .append_query_results([Vec::<ImportMappingModel>::new()])
+        .append_query_results([vec![mapping("message", "C1:0.5", &parent.id)]])
+        .append_query_results([vec![parent]])
+        .append_exec_results([rows_affected(1), rows_affected(1)])
+        .append_query_results([vec![stored.clone()]])
+        .append_query_results([vec![mapping("message", "C1:1.0", &stored.id)]])
+        .into_connection();
+    let mut importer = Importer::new(&db, "slack");
+    importer.members.insert((room_id.clone(), sender_id.clone()));
+
+    importer.message(&room_id, reply("C1:1.0", &sender_id, "C1:0.5")).await.unwrap();
+    assert_eq!(importer.report.messages_imported, 1);
+
+    let statements: Vec<String> = db.into_transaction_log().iter().map(|t| format!("{:?}", t)).collect();
+    let insert = statements.iter().find(|s| s.contains("INSERT INTO `messages`")).expect("message inserted");
+    assert!(insert.contains(&root_id), "{}", insert);
+    assert!(insert.contains("\"me too\""), "{}", insert);
+  }
+
+  #[tokio::test]
+  async fn replies_to_messages_outside_the_export_start_no_thread() {
+    let room_id = Uuid::new_v4().to_string();
+    let sender_id = Uuid::new_v4().to_string();
+    let stored = test_support::message_row(&room_id, Uuid::parse_str(&sender_id).unwrap(), "me too");
+    let db = test_support::mock_db()
+        .append_query_results([Vec::<ImportMappingModel>::new()])
+        .append_query_results([Vec::<ImportMappingModel>::new()])
+        .append_exec_results([rows_affected(1), rows_affected(1)])
+        .append_query_results([vec![stored.clone()]])
+        .append_query_results([vec![mapping("message", "C1:1.0", &stored.id)]])
+        .into_connection();
+    let mut importer = Importer::new(&db, "slack");
+    importer.members.insert((room_id.clone(), sender_id.clone()));
+
+    importer.message(&room_id, reply("C1:1.0", &sender_id, "C1:0.5")).await.unwrap();
+    assert_eq!(importer.report.messages_imported, 1);
+    assert_eq!(importer.report.conflicts[0].kind, "message");
+  }
+}
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, path::Path, sync::LazyLock};
+
+use async_zip::tokio::read::seek::ZipFileReader;
+use chrono::{DateTime, Utc};
+use regex::{Captures, Regex};
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::{fs::File, io::BufReader};
+
+use super::{invalid, ExternalMessage, ExternalUser, Importer};
+use crate::{database::DbPool, dtos::{import::ImportReport, room::RoomRole}, response::ApiError};
+
+const SOURCE: &str = "slack";
+
+#[derive(Deserialize)]
+struct SlackUser {
+  id: String,
+  name: String,
+  #[serde(default)]
+  profile: SlackProfile,
+}
+
+#[derive(Default, Deserialize)]
+struct SlackProfile {
+  email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SlackChannel {
+  id: String,
+  name: String,
+  #[serde(default)]
+  created: i64,
+  creator: Option<String>,
+  #[serde(default)]
+  members: Vec<String>,
+  topic: Option<SlackText>,
+  purpose: Option<SlackText>,
+}
+
+#[derive(Deserialize)]
+struct SlackText {
+  value: String,
+}
+
+#[derive(Deserialize)]
+struct SlackMessage {
+  #[serde(rename = "type")]
+  kind: String,
+  subtype: Option<String>,
+  user: Option<String>,
+  #[serde(default)]
+  text: String,
+  ts: String,
+  thread_ts: Option<String>,
+  #[serde(default)]
+  files: Vec<SlackFile>,
+}
+
+#[derive(Deserialize)]
+struct SlackFile {
+  name: Option<String>,
+  url_private: Option<String>,
+}
+
+// Join/leave notices, topic changes and the like aren't conversation.
+const IMPORTED_SUBTYPES: &[&str] = &["thread_broadcast", "file_share", "me_message"];
+
+// A workspace export zip: `users.json`, `channels.json` (and `groups.json` for private
+// channels), plus one folder per channel holding a JSON file of messages per day.
+pub async fn import_slack(db: &DbPool, path: &Path) -> Result<ImportReport, ApiError> {
+  let file = File::open(path)
+      .await
+      .map_err(|e| ApiError::BadRequest(format!("Cannot open {}: {}", path.display(), e)))?;
+  let mut zip = ZipFileReader::with_tokio(BufReader::new(file)).await.map_err(|e| invalid(SOURCE, e))?;
+
+  let mut entries = HashMap::new();
+  for (index, entry) in zip.file().entries().iter().enumerate() {
+      if let Ok(name) = entry.filename().as_str()
+          && !entry.dir().unwrap_or(false)
+      {
+          entries.insert(name.to_string(), index);
+      }
+  }
+
+  let users: Vec<SlackUser> = read_json(&mut zip, &entries, "users.json").await?.unwrap_or_default();
+  let mut channels: Vec<SlackChannel> = read_json(&mut zip, &entries, "channels.json").await?.unwrap_or_default();
+  channels.extend(read_json::<Vec<SlackChannel>>(&mut zip, &entries, "groups.json").await?.unwrap_or_default());
+  if users.is_empty() && channels.is_empty() {
+      return Err(invalid(SOURCE, "users.json and channels.json are missing"));
+  }
+
+  let mut importer = Importer::new(db, SOURCE);
+  for user in users {
+      importer.user(ExternalUser { id: user.id, username: user.name, email: user.profile.email }).await?;
+  }
+
+  for channel in channels {
+      let topic = channel.topic.or(channel.purpose).map(|t| t.value);
+      let created_at = DateTime::from_timestamp(channel.created, 0).unwrap_or_else(Utc::now);
+      let Some(room_id) = importer.room(&channel.id, &channel.name, topic.as_deref(), created_at).await? else {
+          continue;
+      };
+
+      for member in &channel.members {
+          if let Some(user_id) = importer.user_id(member) {
+              let role = if channel.creator.as_ref() == Some(member) { RoomRole::Owner } else { RoomRole::Member };
+              importer.member(&room_id, &user_id, role).await?;
+          }
+      }
+
+      // Day files are named YYYY-MM-DD.json, so name order is time order.
+      let prefix = format!("{}/", channel.name);
+      let mut days: Vec<&String> = entries.keys().filter(|name| name.starts_with(&prefix)).collect();
+      days.sort();
+      for day in days.into_iter().cloned().collect::<Vec<_>>() {
+          let messages: Vec<SlackMessage> = read_json(&mut zip, &entries, &day).await?.unwrap_or_default();
+          for message in messages {
+              import_message(&mut importer, &channel.id, &room_id, message).await?;
+          }
+      }
+  }
+
+  Ok(importer.report)
+}
+
+async fn import_message(
+  importer: &mut Importer<'_>,
+  channel_id: &str,
+  room_id: &str,
+  message: SlackMessage,
+) -> Result<(), ApiError> {
+  if message.kind != "message" || message.subtype.as_deref().is_some_and(|s| !IMPORTED_SUBTYPES.contains(&s)) {
+      return Ok(());
+  }
+  // Timestamps are only unique within a channel.
+  let external_id = format!("{}:{}", channel_id, message.ts);
+  let Some(sender) = message.user.as_deref() else {
+      return Ok(());
+  };
+  let Some(sender_id) = importer.user_id(sender) else {
+      importer.conflict("message", &external_id, format!("Sender {} is not in users.json", sender));
+      return Ok(());
+  };
+  let Some(created_at) = parse_ts(&message.ts) else {
+      importer.conflict("message", &external_id, "Unreadable timestamp");
+      return Ok(());
+  };
+
+  let mut content = convert_text(importer, &message.text);
+  for file in &message.files {
+      if let Some(url) = &file.url_private {
+          content.push_str(&format!("\n{}: {}", file.name.as_deref().unwrap_or("file"), url));
+      }
+  }
+
+  let reply_to = message
+      .thread_ts
+      .filter(|thread_ts| *thread_ts != message.ts)
+      .map(|thread_ts| format!("{}:{}", channel_id, thread_ts));
+
+  importer
+      .message(room_id, ExternalMessage { id: external_id, sender_id, content, created_at, reply_to })
+      .await
+}
+
+async fn read_json<T: DeserializeOwned>(
+  zip: &mut ZipFileReader<BufReader<File>>,
+  entries: &HashMap<String, usize>,
+  name: &str,
+) -> Result<Option<T>, ApiError> {
+  let Some(&index) = entries.get(name) else {
+      return Ok(None);
+  };
+  let mut raw = String::new();
+  zip.reader_with_entry(index)
+      .await
+      .map_err(|e| invalid(SOURCE, e))?
+      .read_to_string_checked(&mut raw)
+      .await
+      .map_err(|e| invalid(SOURCE, format!("{}: {}", name, e)))?;
+  serde_json::from_str(&raw).map(Some).map_err(|e| invalid(SOURCE, format!("{}: {}", name, e)))
+}
+
+// "1589381234.000200" is seconds and microseconds.
+fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+  let (secs, micros) = ts.split_once('.').unwrap_or((ts, "0"));
+  DateTime::from_timestamp(secs.parse().ok()?, micros.parse::<u32>().ok()?.checked_mul(1000)?)
+}
+
+static ANGLE_REF: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<([^<>|]+)(?:\|([^<>]*))?>").unwrap());
+
+// Slack's markup: <@U123> mentions, <#C123|general> channels, <!here>, <https://x|label> links,
+// with &, < and > escaped everywhere else.
+fn convert_text(importer: &Importer<'_>, text: &str) -> String {
+  let converted = ANGLE_REF.replace_all(text, |caps: &Captures| {
+    let target = &caps[1];
+    let label = caps.get(2).map(|m| m.as_str());
+    if let Some(user) = target.strip_prefix('@') {
+        let name = importer.username(user).or(label).unwrap_or(user);
+        format!("@{}", name)
+    } else if let Some(channel) = target.strip_prefix('#') {
+        format!("#{}", label.unwrap_or(channel))
+    } else if let Some(special) = target.strip_prefix('!') {
+        label.map(str::to_string).unwrap_or_else(|| format!("@{}", special))
+    } else {
+        match label {
+            Some(label) if label != target => format!("{} ({})", label, target),
+            _ => target.to_string(),
+        }
+    }
+  });
+  converted.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_support;
+
+  #[test]
+  fn timestamps_keep_their_microseconds() {
+    let at = parse_ts("1589381234.000200").unwrap();
+    assert_eq!(at.timestamp(), 1589381234);
+    assert_eq!(at.timestamp_subsec_micros(), 200);
+    assert_eq!(parse_ts("1589381234").unwrap().timestamp(), 1589381234);
+    assert!(parse_ts("yesterday").is_none());
+  }
+
+  #[test]
+  fn markup_becomes_plain_text() {
+    let db = test_support::mock_db().into_connection();
+    let mut importer = Importer::new(&db, SOURCE);
+    importer.users.insert("U1".into(), Some(("local-1".into(), "alice".into())));
+
+    assert_eq!(convert_text(&importer, "<@U1> see <#C1|general>"), "@alice see #general");
+    assert_eq!(convert_text(&importer, "<@U2|bob> <!here>"), "@bob @here");
+    assert_eq!(
+        convert_text(&importer, "<https://example.com|docs> <https://example.com>"),
+        "docs (https://example.com) https://example.com"
+    );
+    assert_eq!(convert_text(&importer, "a &lt;b&gt; &amp;amp;"), "a <b> &amp;");
+  }
+}
@@ -0,0 +1,707 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::Instrument;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+  database::{AppState, SharedState},
+  dtos::{
+    admin::{AccountStatus, UserRole},
+    chat::{MessageDto, MessageFormat, SendMessageRequest, WsOutboundMessage},
+    room::RoomRole,
+    integration::{
+      BotCommandCreatedResponse, BotCommandResponse, BotCreatedResponse, BotResponse,
+      CreateBotCommandRequest, CreateBotRequest, CreateIncomingWebhookRequest,
+      CreateOutgoingWebhookRequest, IncomingWebhookCreatedResponse, IncomingWebhookResponse,
+      OutgoingWebhookCreatedResponse, OutgoingWebhookPayload, OutgoingWebhookReply,
+      OutgoingWebhookResponse, RoomHooksResponse,
+    },
+  },
+  entities::{
+    bot::Model as BotModel, bot_command::Model as BotCommandModel,
+    incoming_webhook::Model as IncomingWebhookModel,
+    outgoing_webhook::Model as OutgoingWebhookModel, user::Model as UserModel,
+  },
+  repositories::{
+    bot as bot_repo, bot_command as bot_command_repo, incoming_webhook as incoming_repo, outgoing_webhook as outgoing_repo,
+    room_member as member_repo, user as user_repo,
+  },
+  response::ApiError,
+  security::{generate_secret, generate_token, hash_password, hash_token, sign_payload},
+  services::{
+    account, admin, auth, chat, moderation,
+    notification::{SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    outbound,
+  },
+};
+
+const OUTGOING_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub async fn create_bot(
+  state: &AppState,
+  owner_id: Uuid,
+  req: CreateBotRequest,
+) -> Result<BotCreatedResponse, ApiError> {
+  auth::ensure_verified(state, owner_id).await?;
+
+  let bot_id = Uuid::new_v4();
+  // Bots never log in with a password, so store a hash of a throwaway random value.
+  let password = hash_password(&generate_token("pw"))?;
+
+  let user = user_repo::insert(
+      &state.db,
+      UserModel {
+        id: bot_id.to_string(),
+        username: req.username,
+        email: format!("{}@bots.invalid", bot_id),
+        password,
+        created_at: Utc::now(),
+        // Bots have no mailbox; they are trusted through their owner.
+        email_verified_at: Some(Utc::now()),
+        role: UserRole::User.as_str().to_string(),
+        status: AccountStatus::Active.as_str().to_string(),
+        suspended_until: None,
+        status_reason: None,
+        delete_after: None,
+      },
+  )
+  .await?;
+
+  let api_key = generate_token("bot");
+  bot_repo::insert(
+      &state.db,
+      BotModel {
+        user_id: user.id.clone(),
+        owner_id: owner_id.to_string(),
+        api_key_hash: hash_token(&api_key),
+        created_at: Utc::now(),
+      },
+  )
+  .await?;
+
+  Ok(BotCreatedResponse {
+      id: bot_id,
+      username: user.username,
+      api_key,
+      created_at: user.created_at,
+  })
+}
+
+pub async fn list_bots(state: &AppState, owner_id: Uuid) -> Result<Vec<BotResponse>, ApiError> {
+  let bots = bot_repo::list_by_owner(&state.db, &owner_id.to_string()).await?;
+
+  let mut responses = Vec::new();
+  for bot in bots {
+      let user = user_repo::find_by_id(&state.db, &bot.user_id)
+          .await?
+          .ok_or_else(|| ApiError::NotFound("Bot user not found".into()))?;
+      responses.push(BotResponse {
+          id: parse_id(&bot.user_id, "bot")?,
+          username: user.username,
+          created_at: bot.created_at,
+      });
+  }
+
+  Ok(responses)
+}
+
+pub async fn delete_bot(state: &AppState, owner_id: Uuid, bot_id: Uuid) -> Result<(), ApiError> {
+  let bot = find_owned_bot(state, owner_id, bot_id).await?;
+  // Its messages stay in their rooms; deleting the user cascades to the bot row, its memberships and its hooks.
+  account::anonymize_messages(state, &bot.user_id).await?;
+  user_repo::delete(&state.db, &bot.user_id).await?;
+  Ok(())
+}
+
+// Register a slash command handled by the bot's own HTTP endpoint.
+pub async fn create_bot_command(
+  state: &AppState,
+  owner_id: Uuid,
+  bot_id: Uuid,
+  req: CreateBotCommandRequest,
+) -> Result<BotCommandCreatedResponse, ApiError> {
+  if !(req.url.starts_with("http://") || req.url.starts_with("https://")) {
+      return Err(ApiError::BadRequest("Command url must use http or https".into()));
+  }
+  let name = req.name.trim_start_matches('/').to_lowercase();
+  if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+      return Err(ApiError::BadRequest("Command name may only contain letters, digits, '-' and '_'".into()));
+  }
+  if state.commands.is_builtin(&name) {
+      return Err(ApiError::BadRequest(format!("/{} is a built-in command", name)));
+  }
+  find_owned_bot(state, owner_id, bot_id).await?;
+
+  let command = bot_command_repo::insert(
+      &state.db,
+      BotCommandModel {
+        id: Uuid::new_v4().to_string(),
+        bot_id: bot_id.to_string(),
+        name,
+        description: req.description.unwrap_or_default(),
+        url: req.url,
+        secret: generate_secret(),
+        created_at: Utc::now(),
+      },
+  )
+  .await
+  .map_err(|_| ApiError::BadRequest("This bot already has a command with that name".into()))?;
+
+  Ok(BotCommandCreatedResponse {
+      id: parse_id(&command.id, "command")?,
+      bot_id,
+      name: command.name,
+      description: command.description,
+      url: command.url,
+      secret: command.secret,
+      created_at: command.created_at,
+  })
+}
+
+pub async fn list_bot_commands(
+  state: &AppState,
+  owner_id: Uuid,
+  bot_id: Uuid,
+) -> Result<Vec<BotCommandResponse>, ApiError> {
+  find_owned_bot(state, owner_id, bot_id).await?;
+
+  bot_command_repo::list_by_bot(&state.db, &bot_id.to_string())
+      .await?
+      .into_iter()
+      .map(|command| {
+        Ok(BotCommandResponse {
+            id: parse_id(&command.id, "command")?,
+            bot_id,
+            name: command.name,
+            description: command.description,
+            url: command.url,
+            created_at: command.created_at,
+        })
+      })
+      .collect()
+}
+
+pub async fn delete_bot_command(
+  state: &AppState,
+  owner_id: Uuid,
+  bot_id: Uuid,
+  command_id: Uuid,
+) -> Result<(), ApiError> {
+  find_owned_bot(state, owner_id, bot_id).await?;
+
+  let command = bot_command_repo::find_by_bot(&state.db, &bot_id.to_string(), &command_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Command not found".into()))?;
+  bot_command_repo::delete(&state.db, command).await?;
+  Ok(())
+}
+
+// Resolve a bot API key to the bot's user id.
+pub async fn authenticate_api_key(state: &AppState, api_key: &str) -> Result<Uuid, ApiError> {
+  let bot = bot_repo::find_by_api_key_hash(&state.db, &hash_token(api_key))
+      .await?
+      .ok_or_else(|| ApiError::Unauthorized("Invalid API key".into()))?;
+
+  // Bots are regular accounts, so admin bans apply to them as well.
+  let user = user_repo::find_by_id(&state.db, &bot.user_id)
+      .await?
+      .ok_or_else(|| ApiError::Unauthorized("Invalid API key".into()))?;
+  admin::ensure_active(&user)?;
+
+  parse_id(&bot.user_id, "bot")
+}
+
+pub async fn create_incoming_webhook(
+  state: &AppState,
+  room_id: Uuid,
+  requester_id: Uuid,
+  req: CreateIncomingWebhookRequest,
+) -> Result<IncomingWebhookCreatedResponse, ApiError> {
+  // Hooks speak for the room and outgoing ones see all of its messages, so only moderators add them.
+  moderation::ensure_moderator(state, room_id, requester_id).await?;
+  find_owned_bot(state, requester_id, req.bot_id).await?;
+  add_bot_to_room(state, room_id, req.bot_id).await?;
+
+  let token = generate_token("hook");
+  let hook = incoming_repo::insert(
+      &state.db,
+      IncomingWebhookModel {
+        id: Uuid::new_v4().to_string(),
+        room_id: room_id.to_string(),
+        bot_id: req.bot_id.to_string(),
+        token_hash: hash_token(&token),
+        created_at: Utc::now(),
+      },
+  )
+  .await?;
+
+  Ok(IncomingWebhookCreatedResponse {
+      id: parse_id(&hook.id, "webhook")?,
+      room_id,
+      bot_id: req.bot_id,
+      token,
+      created_at: hook.created_at,
+  })
+}
+
+pub async fn create_outgoing_webhook(
+  state: &AppState,
+  room_id: Uuid,
+  requester_id: Uuid,
+  req: CreateOutgoingWebhookRequest,
+) -> Result<OutgoingWebhookCreatedResponse, ApiError> {
+  if !(req.url.starts_with("http://") || req.url.starts_with("https://")) {
+      return Err(ApiError::BadRequest("Webhook url must use http or https".into()));
+  }
+  // Hooks speak for the room and outgoing ones see all of its messages, so only moderators add them.
+  moderation::ensure_moderator(state, room_id, requester_id).await?;
+  find_owned_bot(state, requester_id, req.bot_id).await?;
+  add_bot_to_room(state, room_id, req.bot_id).await?;
+
+  let hook = outgoing_repo::insert(
+      &state.db,
+      OutgoingWebhookModel {
+        id: Uuid::new_v4().to_string(),
+        room_id: room_id.to_string(),
+        bot_id: req.bot_id.to_string(),
+        url: req.url,
+        secret: generate_secret(),
+        created_at: Utc::now(),
+      },
+  )
+  .await?;
+
+  Ok(OutgoingWebhookCreatedResponse {
+      id: parse_id(&hook.id, "webhook")?,
+      room_id,
+      bot_id: req.bot_id,
+      url: hook.url,
+      secret: hook.secret,
+      created_at: hook.created_at,
+  })
+}
+
+pub async fn list_room_hooks(
+  state: &AppState,
+  room_id: Uuid,
+  requester_id: Uuid,
+) -> Result<RoomHooksResponse, ApiError> {
+  chat::ensure_membership(state, room_id, requester_id).await?;
+
+  let incoming = incoming_repo::list_by_room(&state.db, &room_id.to_string())
+      .await?
+      .into_iter()
+      .map(|hook| {
+        Ok(IncomingWebhookResponse {
+            id: parse_id(&hook.id, "webhook")?,
+            room_id,
+            bot_id: parse_id(&hook.bot_id, "bot")?,
+            created_at: hook.created_at,
+        })
+      })
+      .collect::<Result<Vec<_>, ApiError>>()?;
+
+  let outgoing = outgoing_repo::list_by_room(&state.db, &room_id.to_string())
+      .await?
+      .into_iter()
+      .map(|hook| {
+        Ok(OutgoingWebhookResponse {
+            id: parse_id(&hook.id, "webhook")?,
+            room_id,
+            bot_id: parse_id(&hook.bot_id, "bot")?,
+            url: hook.url,
+            created_at: hook.created_at,
+        })
+      })
+      .collect::<Result<Vec<_>, ApiError>>()?;
+
+  Ok(RoomHooksResponse { incoming, outgoing })
+}
+
+pub async fn delete_room_hook(
+  state: &AppState,
+  room_id: Uuid,
+  hook_id: Uuid,
+  requester_id: Uuid,
+) -> Result<(), ApiError> {
+  chat::ensure_membership(state, room_id, requester_id).await?;
+
+  let (room, hook_id) = (room_id.to_string(), hook_id.to_string());
+  if let Some(hook) = incoming_repo::find_in_room(&state.db, &room, &hook_id).await? {
+      ensure_can_remove_hook(state, room_id, requester_id, &hook.bot_id).await?;
+      incoming_repo::delete(&state.db, hook).await?;
+      return Ok(());
+  }
+  if let Some(hook) = outgoing_repo::find_in_room(&state.db, &room, &hook_id).await? {
+      ensure_can_remove_hook(state, room_id, requester_id, &hook.bot_id).await?;
+      outgoing_repo::delete(&state.db, hook).await?;
+      return Ok(());
+  }
+
+  Err(ApiError::NotFound("Webhook not found".into()))
+}
+
+// Moderators manage every hook in the room; a bot's owner may also remove that bot's hooks.
+async fn ensure_can_remove_hook(state: &AppState, room_id: Uuid, requester_id: Uuid, bot_id: &str) -> Result<(), ApiError> {
+  let owns_bot = bot_repo::find_by_user_id(&state.db, bot_id)
+      .await?
+      .is_some_and(|bot| bot.owner_id == requester_id.to_string());
+  if owns_bot {
+      return Ok(());
+  }
+  moderation::ensure_moderator(state, room_id, requester_id).await
+}
+
+// Post a message through an incoming webhook token, as the hook's bot.
+pub async fn post_incoming(state: &AppState, token: &str, req: SendMessageRequest) -> Result<MessageDto, ApiError> {
+  let hook = incoming_repo::find_by_token_hash(&state.db, &hash_token(token))
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Webhook not found".into()))?;
+
+  chat::send_message(
+      state,
+      parse_id(&hook.room_id, "room")?,
+      parse_id(&hook.bot_id, "bot")?,
+      req.content,
+      req.format,
+  )
+  .await
+}
+
+// Background task forwarding every new room message to the room's outgoing webhooks.
+pub fn spawn_outgoing_dispatcher(state: SharedState) -> tokio::task::JoinHandle<()> {
+  tokio::spawn(async move {
+    let mut rx = state.chat_tx.subscribe();
+    loop {
+        let message = match rx.recv().await {
+            Ok(WsOutboundMessage::MessageCreated(message)) => message,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "Outgoing webhooks fell behind and skipped messages");
+                state.metrics.events_dropped("outgoing_webhooks", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        // Never forward bot messages, otherwise two bots could reply to each other forever.
+        match bot_repo::find_by_user_id(&state.db, &message.sender_id.to_string()).await {
+            Ok(None) => {}
+            Ok(Some(_)) => continue,
+            Err(e) => {
+                tracing::error!(error = %e, "Outgoing webhook lookup failed");
+                continue;
+            }
+        }
+
+        let hooks = match outgoing_repo::list_by_room(&state.db, &message.room_id.to_string()).await {
+            Ok(hooks) => hooks,
+            Err(e) => {
+                tracing::error!(error = %e, "Outgoing webhook lookup failed");
+                continue;
+            }
+        };
+
+        for hook in hooks {
+            let state = state.clone();
+            let message = message.clone();
+            tokio::spawn(async move {
+              let hook_id = hook.id.clone();
+              if let Err(e) = deliver_outgoing(state, hook, message).await {
+                  tracing::warn!(hook_id = %hook_id, error = %e, "Outgoing webhook delivery failed");
+              }
+            }
+            .in_current_span());
+        }
+    }
+  }
+  .instrument(tracing::info_span!("outgoing_webhooks")))
+}
+
+async fn deliver_outgoing(
+  state: SharedState,
+  hook: OutgoingWebhookModel,
+  message: MessageDto,
+) -> Result<(), ApiError> {
+  // The url is user-supplied: only public addresses, unless the operator allows private ones.
+  let url = Url::parse(&hook.url).map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+  let client = outbound::pinned_client(&url, state.config.integrations.allow_private_networks)
+      .await
+      .and_then(|builder| Ok(builder.timeout(OUTGOING_TIMEOUT).build()?))
+      .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+  let body = serde_json::to_string(&OutgoingWebhookPayload {
+      hook_id: parse_id(&hook.id, "webhook")?,
+      message,
+  })
+  .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+  let timestamp = Utc::now().timestamp();
+  let response = client
+      .post(url)
+      .header(reqwest::header::CONTENT_TYPE, "application/json")
+      .header(SIGNATURE_HEADER, sign_payload(&hook.secret, timestamp, &body))
+      .header(TIMESTAMP_HEADER, timestamp.to_string())
+      .body(body)
+      .send()
+      .await
+      .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+  if !response.status().is_success() {
+      return Err(ApiError::InternalServerError(format!(
+          "Webhook responded with status {}",
+          response.status()
+      )));
+  }
+
+  let body = outbound::read_capped(response, outbound::MAX_REPLY_BYTES)
+      .await
+      .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+  // An empty or non-JSON body simply means "no reply".
+  let reply = match serde_json::from_slice::<OutgoingWebhookReply>(&body) {
+      Ok(reply) => reply,
+      Err(_) => return Ok(()),
+  };
+  let Some(content) = reply.content.filter(|content| !content.trim().is_empty()) else {
+      return Ok(());
+  };
+
+  let message = chat::send_message(
+      state.as_ref(),
+      parse_id(&hook.room_id, "room")?,
+      parse_id(&hook.bot_id, "bot")?,
+      content,
+      MessageFormat::Plain,
+  )
+  .await?;
+  let _ = state.chat_tx.send(WsOutboundMessage::MessageCreated(message));
+
+  Ok(())
+}
+
+async fn find_owned_bot(state: &AppState, owner_id: Uuid, bot_id: Uuid) -> Result<BotModel, ApiError> {
+  let bot = bot_repo::find_by_user_id(&state.db, &bot_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Bot not found".into()))?;
+  if bot.owner_id != owner_id.to_string() {
+      return Err(ApiError::Forbidden("You do not own this bot".into()));
+  }
+  Ok(bot)
+}
+
+// A hook's bot posts into the room, so it joins as a plain member when the hook is created.
+// Callers have already checked that the requester moderates the room and owns the bot.
+async fn add_bot_to_room(state: &AppState, room_id: Uuid, bot_id: Uuid) -> Result<(), ApiError> {
+  if chat::ensure_membership(state, room_id, bot_id).await.is_ok() {
+      return Ok(());
+  }
+  moderation::ensure_not_banned(state, room_id, bot_id).await?;
+  member_repo::insert(&state.db, room_id.to_string(), bot_id.to_string(), RoomRole::Member.as_str()).await?;
+  Ok(())
+}
+
+fn parse_id(value: &str, label: &str) -> Result<Uuid, ApiError> {
+  Uuid::parse_str(value).map_err(|_| ApiError::InternalServerError(format!("Invalid {} id", label)))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use sea_orm::MockDatabase;
+  use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+  use crate::{
+    config::Config,
+    dtos::admin::UserRole,
+    entities::{room_ban::Model as RoomBanModel, room_member::Model as RoomMemberModel},
+    test_support::{self, rows_affected},
+  };
+
+  fn outgoing_hook(url: String) -> OutgoingWebhookModel {
+    OutgoingWebhookModel {
+      id: Uuid::new_v4().to_string(),
+      room_id: Uuid::new_v4().to_string(),
+      bot_id: Uuid::new_v4().to_string(),
+      url,
+      secret: "hook-secret".into(),
+      created_at: Utc::now(),
+    }
+  }
+
+  fn state(allow_private_networks: bool) -> SharedState {
+    let mut config = Config::default();
+    config.integrations.allow_private_networks = allow_private_networks;
+    Arc::new(test_support::state_with_config(test_support::mock_db().into_connection(), config))
+  }
+
+  #[tokio::test]
+  async fn outgoing_delivery_refuses_private_addresses() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(200)).expect(0).mount(&server).await;
+    let hook = outgoing_hook(server.uri());
+    let message = test_support::message(Uuid::new_v4(), Uuid::new_v4(), "hi");
+
+    assert!(deliver_outgoing(state(false), hook, message).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn outgoing_delivery_posts_signed_message() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(200)).expect(1).mount(&server).await;
+    let hook = outgoing_hook(server.uri());
+    let message = test_support::message(Uuid::new_v4(), Uuid::new_v4(), "hi");
+
+    // An empty reply body means the bot has nothing to say, so no message is posted back.
+    deliver_outgoing(state(true), hook.clone(), message).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let timestamp: i64 = requests[0].headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert_eq!(requests[0].headers[SIGNATURE_HEADER].to_str().unwrap(), sign_payload(&hook.secret, timestamp, &body));
+  }
+
+  #[tokio::test]
+  async fn outgoing_delivery_refuses_oversized_replies() {
+    let server = MockServer::start().await;
+    let reply = serde_json::json!({ "content": "x".repeat(outbound::MAX_REPLY_BYTES) });
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(reply))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let hook = outgoing_hook(server.uri());
+    let message = test_support::message(Uuid::new_v4(), Uuid::new_v4(), "hi");
+
+    // Nothing is posted back either: the mock database would fail any query.
+    assert!(deliver_outgoing(state(true), hook, message).await.is_err());
+  }
+
+  fn bot(owner_id: Uuid) -> BotModel {
+    BotModel {
+      user_id: Uuid::new_v4().to_string(),
+      owner_id: owner_id.to_string(),
+      api_key_hash: "hash".into(),
+      created_at: Utc::now(),
+    }
+  }
+
+  fn incoming_hook(room_id: Uuid, bot: &BotModel) -> IncomingWebhookModel {
+    IncomingWebhookModel {
+      id: Uuid::new_v4().to_string(),
+      room_id: room_id.to_string(),
+      bot_id: bot.user_id.clone(),
+      token_hash: "hash".into(),
+      created_at: Utc::now(),
+    }
+  }
+
+  #[tokio::test]
+  async fn incoming_webhook_adds_its_bot_to_the_room() {
+    let (room_id, owner) = (Uuid::new_v4(), Uuid::new_v4());
+    let bot = bot(owner);
+    let bot_id = Uuid::parse_str(&bot.user_id).unwrap();
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::user(owner, UserRole::User)]])
+        .append_query_results([vec![test_support::member(&room_id.to_string(), owner, RoomRole::Moderator)]])
+        .append_query_results([vec![bot.clone()]])
+        .append_query_results([Vec::<RoomMemberModel>::new()])
+        .append_query_results([Vec::<RoomBanModel>::new()])
+        .append_query_results([vec![test_support::member(&room_id.to_string(), bot_id, RoomRole::Member)]])
+        .append_query_results([vec![incoming_hook(room_id, &bot)]])
+        .append_exec_results([rows_affected(1), rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    create_incoming_webhook(&state, room_id, owner, CreateIncomingWebhookRequest { bot_id }).await.unwrap();
+
+    let statements = test_support::statements(state);
+    assert!(statements.iter().any(|s| s.contains("INSERT INTO `room_members`") && s.contains(&bot.user_id)));
+  }
+
+  #[tokio::test]
+  async fn incoming_webhook_keeps_an_existing_membership() {
+    let (room_id, owner) = (Uuid::new_v4(), Uuid::new_v4());
+    let bot = bot(owner);
+    let bot_id = Uuid::parse_str(&bot.user_id).unwrap();
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::user(owner, UserRole::User)]])
+        .append_query_results([vec![test_support::member(&room_id.to_string(), owner, RoomRole::Moderator)]])
+        .append_query_results([vec![bot.clone()]])
+        .append_query_results([vec![test_support::member(&room_id.to_string(), bot_id, RoomRole::Member)]])
+        .append_query_results([vec![incoming_hook(room_id, &bot)]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    create_incoming_webhook(&state, room_id, owner, CreateIncomingWebhookRequest { bot_id }).await.unwrap();
+
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT INTO `room_members`")));
+  }
+
+  // A plain member: not a global admin, and no room role above member.
+  fn plain_member_db(room_id: Uuid, user_id: Uuid) -> MockDatabase {
+    test_support::mock_db()
+        .append_query_results([vec![test_support::user(user_id, UserRole::User)]])
+        .append_query_results([vec![test_support::member(&room_id.to_string(), user_id, RoomRole::Member)]])
+  }
+
+  #[tokio::test]
+  async fn members_cannot_create_webhooks() {
+    let (room_id, owner) = (Uuid::new_v4(), Uuid::new_v4());
+    let bot_id = Uuid::parse_str(&bot(owner).user_id).unwrap();
+
+    let state = test_support::state(plain_member_db(room_id, owner).into_connection());
+    let result = create_incoming_webhook(&state, room_id, owner, CreateIncomingWebhookRequest { bot_id }).await;
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT")));
+
+    let state = test_support::state(plain_member_db(room_id, owner).into_connection());
+    let req = CreateOutgoingWebhookRequest { bot_id, url: "https://bots.example.com/hook".into() };
+    let result = create_outgoing_webhook(&state, room_id, owner, req).await;
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT")));
+  }
+
+  #[tokio::test]
+  async fn members_cannot_delete_hooks_of_bots_they_do_not_own() {
+    let (room_id, member, owner) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let bot = bot(owner);
+    let hook = incoming_hook(room_id, &bot);
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::member(&room_id.to_string(), member, RoomRole::Member)]])
+        .append_query_results([vec![hook.clone()]])
+        .append_query_results([vec![bot]])
+        .append_query_results([vec![test_support::user(member, UserRole::User)]])
+        .append_query_results([vec![test_support::member(&room_id.to_string(), member, RoomRole::Member)]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let result = delete_room_hook(&state, room_id, Uuid::parse_str(&hook.id).unwrap(), member).await;
+
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("DELETE")));
+  }
+
+  #[tokio::test]
+  async fn bot_owners_can_delete_their_own_hooks() {
+    let (room_id, owner) = (Uuid::new_v4(), Uuid::new_v4());
+    let bot = bot(owner);
+    let hook = incoming_hook(room_id, &bot);
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::member(&room_id.to_string(), owner, RoomRole::Member)]])
+        .append_query_results([vec![hook.clone()]])
+        .append_query_results([vec![bot]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    delete_room_hook(&state, room_id, Uuid::parse_str(&hook.id).unwrap(), owner).await.unwrap();
+
+    assert!(test_support::statements(state).iter().any(|s| s.contains("DELETE FROM `incoming_webhooks`")));
+  }
+}
@@ -0,0 +1,94 @@
+use std::{fmt, path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+use lettre::{
+  AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+  message::Mailbox,
+  transport::smtp::authentication::Credentials,
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::MailConfig;
+
+#[derive(Debug, Clone)]
+pub struct Mail {
+  pub to: String,
+  pub subject: String,
+  pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync + fmt::Debug {
+  async fn send(&self, mail: Mail) -> anyhow::Result<()>;
+}
+
+// Pick the SMTP mailer when an SMTP host is configured, otherwise write mails to the log path or stdout.
+pub fn from_config(config: &MailConfig) -> anyhow::Result<Arc<dyn Mailer>> {
+  match &config.smtp_host {
+      Some(host) => Ok(Arc::new(SmtpMailer::new(host, config)?)),
+      None => Ok(Arc::new(LogMailer { path: config.log_path.clone() })),
+  }
+}
+
+pub struct SmtpMailer {
+  transport: AsyncSmtpTransport<Tokio1Executor>,
+  from: Mailbox,
+}
+
+impl SmtpMailer {
+  fn new(host: &str, config: &MailConfig) -> anyhow::Result<Self> {
+    let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?;
+    if let Some(port) = config.smtp_port {
+        builder = builder.port(port);
+    }
+    if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.expose().to_string()));
+    }
+
+    Ok(Self { transport: builder.build(), from: config.from.parse()? })
+  }
+}
+
+impl fmt::Debug for SmtpMailer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("SmtpMailer").field("from", &self.from).finish_non_exhaustive()
+  }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+  async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+    let message = Message::builder()
+        .from(self.from.clone())
+        .to(mail.to.parse()?)
+        .subject(mail.subject)
+        .body(mail.body)?;
+    self.transport.send(message).await?;
+    Ok(())
+  }
+}
+
+// Development/test mailer: appends every mail to a file (or stdout) instead of delivering it.
+#[derive(Debug)]
+pub struct LogMailer {
+  path: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Mailer for LogMailer {
+  async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+    let entry = format!("To: {}\nSubject: {}\n\n{}\n---\n", mail.to, mail.subject, mail.body);
+    match &self.path {
+        Some(path) => {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?;
+            file.write_all(entry.as_bytes()).await?;
+        }
+        None => println!("{}", entry),
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,206 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use ammonia::{Builder, UrlRelative};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+const ALLOWED_TAGS: &[&str] = &[
+  "p", "br", "hr", "strong", "em", "del", "code", "pre", "blockquote", "ul", "ol", "li", "a",
+  "h1", "h2", "h3", "h4", "h5", "h6", "span", "table", "thead", "tbody", "tr", "th", "td",
+];
+
+// Render markdown into HTML that is safe to inject as-is on every client.
+// Raw HTML in the source is shown as text, `@username` becomes a mention span,
+// and the result is passed through an allowlist sanitizer as a second line of defence.
+pub fn render(source: &str) -> String {
+  let mut options = Options::empty();
+  options.insert(Options::ENABLE_STRIKETHROUGH);
+  options.insert(Options::ENABLE_TABLES);
+
+  let mut in_code_block = false;
+  let mut events = Vec::new();
+  for event in Parser::new_ext(source, options) {
+      match event {
+          Event::Start(Tag::CodeBlock(_)) => {
+              in_code_block = true;
+              events.push(event);
+          }
+          Event::End(TagEnd::CodeBlock) => {
+              in_code_block = false;
+              events.push(event);
+          }
+          Event::Html(raw) | Event::InlineHtml(raw) => events.push(Event::Text(raw)),
+          Event::Text(text) if !in_code_block => push_with_mentions(&mut events, text),
+          other => events.push(other),
+      }
+  }
+
+  let mut unsafe_html = String::new();
+  html::push_html(&mut unsafe_html, events.into_iter());
+  sanitizer().clean(&unsafe_html).to_string()
+}
+
+fn sanitizer() -> &'static Builder<'static> {
+  static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+  SANITIZER.get_or_init(|| {
+    let mut builder = Builder::empty();
+    builder
+        .add_tags(ALLOWED_TAGS.iter().copied())
+        .add_tag_attributes("a", ["href", "title"])
+        .add_tag_attributes("code", ["class"])
+        .add_tag_attributes("span", ["class", "data-mention"])
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .url_relative(UrlRelative::Deny)
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            ("code", "class") => is_language_class(value).then_some(Cow::Borrowed(value)),
+            ("span", "class") => (value == "mention").then_some(Cow::Borrowed(value)),
+            ("span", "data-mention") => is_username(value).then_some(Cow::Borrowed(value)),
+            _ => Some(Cow::Borrowed(value)),
+        });
+    builder
+  })
+}
+
+// Split a text event around `@username` tokens, emitting each mention as a span.
+fn push_with_mentions<'a>(events: &mut Vec<Event<'a>>, text: CowStr<'a>) {
+  let mut rest: &str = &text;
+  let mut pieces = Vec::new();
+
+  while let Some((start, end)) = next_mention(rest) {
+      if start > 0 {
+          pieces.push(Event::Text(CowStr::from(rest[..start].to_owned())));
+      }
+      let username = &rest[start + 1..end];
+      pieces.push(Event::InlineHtml(CowStr::from(format!(
+          "<span class=\"mention\" data-mention=\"{0}\">@{0}</span>",
+          username
+      ))));
+      rest = &rest[end..];
+  }
+
+  if pieces.is_empty() {
+      events.push(Event::Text(text));
+      return;
+  }
+  if !rest.is_empty() {
+      pieces.push(Event::Text(CowStr::from(rest.to_owned())));
+  }
+  events.extend(pieces);
+}
+
+// Byte range of the next `@name` that starts a word.
+fn next_mention(text: &str) -> Option<(usize, usize)> {
+  let mut previous: Option<char> = None;
+  for (index, c) in text.char_indices() {
+      let starts_word = previous.is_none_or(|p| !p.is_alphanumeric() && p != '_');
+      if c == '@' && starts_word {
+          let name_len: usize = text[index + 1..]
+              .chars()
+              .take_while(|c| is_username_char(*c))
+              .map(char::len_utf8)
+              .sum();
+          if name_len > 0 {
+              return Some((index, index + 1 + name_len));
+          }
+      }
+      previous = Some(c);
+  }
+  None
+}
+
+fn is_username_char(c: char) -> bool {
+  c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+fn is_username(value: &str) -> bool {
+  !value.is_empty() && value.chars().all(is_username_char)
+}
+
+fn is_language_class(value: &str) -> bool {
+  value
+      .strip_prefix("language-")
+      .is_some_and(|lang| !lang.is_empty() && lang.chars().all(|c| c.is_ascii_alphanumeric() || "+#-_".contains(c)))
+}
+
+#[cfg(test)]
+mod tests {
+  use proptest::prelude::*;
+  use scraper::Html;
+
+  use super::*;
+
+  // Everything the sanitizer promises, checked on the parsed output rather than its text,
+  // since escaped text may legitimately read `onclick=` or `javascript:`.
+  fn assert_safe(html: &str) {
+    assert!(!html.to_ascii_lowercase().contains("<script"), "script tag in {:?}", html);
+    for node in Html::parse_fragment(html).tree.nodes() {
+        let Some(element) = node.value().as_element() else {
+            continue;
+        };
+        for (name, value) in element.attrs() {
+            assert!(!name.to_ascii_lowercase().starts_with("on"), "event handler in {:?}", html);
+            if name == "href" || name == "src" {
+                let url = value.trim_start().to_ascii_lowercase();
+                assert!(!url.starts_with("javascript:") && !url.starts_with("data:"), "unsafe url in {:?}", html);
+            }
+        }
+    }
+  }
+
+  // Fragments that tend to break sanitizers, glued together in random order.
+  fn hostile_markdown() -> impl Strategy<Value = String> {
+    let fragment = prop_oneof![
+        Just("<script>alert(1)</script>".to_string()),
+        Just("<img src=x onerror=alert(1)>".to_string()),
+        Just("<a href=\"javascript:alert(1)\">x</a>".to_string()),
+        Just("[x](javascript:alert(1))".to_string()),
+        Just("[x](JaVaScRiPt&#58;alert(1))".to_string()),
+        Just("[x](data:text/html;base64,PHNjcmlwdD4=)".to_string()),
+        Just("![x](javascript:alert(1))".to_string()),
+        Just("<https://example.com\" onmouseover=\"x>".to_string()),
+        Just("```js onload=x\ncode\n```".to_string()),
+        Just("@user\" onclick=\"x".to_string()),
+        Just("| a | b |\n|---|---|\n| <svg onload=x> | c |".to_string()),
+        "[ -~\n]{0,12}",
+    ];
+    prop::collection::vec(fragment, 0..12).prop_map(|parts| parts.concat())
+  }
+
+  proptest! {
+    #[test]
+    fn arbitrary_input_renders_safe_html(source in any::<String>()) {
+      assert_safe(&render(&source));
+    }
+
+    #[test]
+    fn hostile_markdown_renders_safe_html(source in hostile_markdown()) {
+      assert_safe(&render(&source));
+    }
+  }
+
+  #[test]
+  fn raw_html_is_shown_as_text() {
+    assert_eq!(render("<b>hi</b>"), "<p>&lt;b&gt;hi&lt;/b&gt;</p>\n");
+  }
+
+  #[test]
+  fn mentions_become_spans_outside_code() {
+    let html = render("hey @alice, see `@bob`");
+    assert!(html.contains("<span class=\"mention\" data-mention=\"alice\">@alice</span>"));
+    assert!(html.contains("<code>@bob</code>"));
+  }
+
+  #[test]
+  fn links_keep_safe_schemes_only() {
+    assert!(render("[x](https://example.com)").contains("href=\"https://example.com\""));
+    assert!(!render("[x](javascript:alert(1))").contains("href"));
+  }
+
+  #[test]
+  fn code_blocks_keep_language_class() {
+    assert!(render("```rust\nfn main() {}\n```").contains("class=\"language-rust\""));
+    assert!(!render("```js\" onclick=\"x\ncode\n```").contains("onclick=\""));
+  }
+}
@@ -0,0 +1,254 @@
+use anyhow::Context;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::auth::{LoginResponse, MfaLoginRequest, RecoveryCodesResponse, TotpCodeRequest, TotpEnrollmentResponse},
+  entities::user_totp::Model as UserTotpModel,
+  repositories::{recovery_code as recovery_code_repo, user as user_repo, user_totp as totp_repo},
+  response::ApiError,
+  services::admin,
+  security::{
+    hash_token,
+    totp::{
+      generate_recovery_code, generate_totp_secret, normalize_recovery_code, open_secret, otpauth_uri, seal_secret,
+      verify_totp,
+    },
+  },
+};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+// After this many attempts without a valid code, codes are refused until the lockout passes.
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+const LOCKOUT: Duration = Duration::minutes(15);
+
+pub async fn is_enabled(state: &AppState, user_id: &str) -> Result<bool, ApiError> {
+  let totp = totp_repo::find_by_user_id(&state.db, user_id).await?;
+  Ok(totp.is_some_and(|t| t.confirmed_at.is_some()))
+}
+
+pub async fn enroll(state: &AppState, user_id: Uuid) -> Result<TotpEnrollmentResponse, ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+  if is_enabled(state, &user.id).await? {
+      return Err(ApiError::BadRequest("Two-factor authentication is already enabled".into()));
+  }
+
+  let secret = generate_totp_secret();
+  totp_repo::replace(&state.db, &user.id, seal(state, &user.id, &secret)?).await?;
+
+  Ok(TotpEnrollmentResponse {
+      otpauth_uri: otpauth_uri(&state.config.auth.totp_issuer, &user.email, &secret),
+      secret,
+  })
+}
+
+// The first valid code proves the authenticator is set up; only then is 2FA enforced.
+pub async fn confirm(state: &AppState, user_id: Uuid, req: TotpCodeRequest) -> Result<RecoveryCodesResponse, ApiError> {
+  let user_id = user_id.to_string();
+  let totp = totp_repo::find_by_user_id(&state.db, &user_id)
+      .await?
+      .ok_or_else(|| ApiError::BadRequest("Start the two-factor enrollment first".into()))?;
+  if totp.confirmed_at.is_some() {
+      return Err(ApiError::BadRequest("Two-factor authentication is already enabled".into()));
+  }
+
+  let step = verify_totp(&open(state, &totp)?, &req.code, Utc::now().timestamp())
+      .ok_or_else(|| ApiError::BadRequest("Invalid code".into()))?;
+  totp_repo::confirm(&state.db, &user_id, step).await?;
+
+  let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_recovery_code()).collect();
+  let hashes = recovery_codes
+      .iter()
+      .map(|code| hash_token(&normalize_recovery_code(code)))
+      .collect();
+  recovery_code_repo::replace_for_user(&state.db, &user_id, hashes).await?;
+
+  Ok(RecoveryCodesResponse { recovery_codes })
+}
+
+pub async fn disable(state: &AppState, user_id: Uuid, req: TotpCodeRequest) -> Result<(), ApiError> {
+  let user_id = user_id.to_string();
+  verify_code(state, &user_id, &req.code).await?;
+
+  totp_repo::delete(&state.db, &user_id).await?;
+  recovery_code_repo::delete_for_user(&state.db, &user_id).await?;
+  Ok(())
+}
+
+// Second step of the login: trade the MFA challenge plus a valid code for a session token.
+pub async fn complete_login(state: &AppState, req: MfaLoginRequest) -> Result<LoginResponse, ApiError> {
+  let user_id = state
+      .jwt
+      .validate_mfa_challenge(&req.mfa_token)
+      .map_err(|_| ApiError::Unauthorized("Invalid or expired MFA token".into()))?;
+
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::Unauthorized("Invalid or expired MFA token".into()))?;
+  admin::ensure_active(&user)?;
+  verify_code(state, &user.id, &req.code).await?;
+
+  let token = state.jwt.generate(user_id).context("Failed to generate JWT")?;
+  Ok(LoginResponse {
+      token: Some(token),
+      email_verified: user.email_verified_at.is_some(),
+      mfa_required: false,
+      mfa_token: None,
+  })
+}
+
+// Accept a TOTP code that has not been used yet, or an unused recovery code.
+async fn verify_code(state: &AppState, user_id: &str, code: &str) -> Result<(), ApiError> {
+  let totp = totp_repo::find_by_user_id(&state.db, user_id)
+      .await?
+      .filter(|t| t.confirmed_at.is_some())
+      .ok_or_else(|| ApiError::BadRequest("Two-factor authentication is not enabled".into()))?;
+
+  let now = Utc::now();
+  if !totp_repo::claim_attempt(&state.db, user_id, MAX_FAILED_ATTEMPTS, now, now - LOCKOUT).await? {
+      tracing::warn!(user_id, "Two-factor code refused: locked out");
+      state.metrics.auth_failure("mfa_locked");
+      return Err(ApiError::Unauthorized("Too many invalid codes, try again later".into()));
+  }
+
+  if let Some(step) = verify_totp(&open(state, &totp)?, code, now.timestamp())
+      && totp.last_used_step.is_none_or(|last| step > last)
+  {
+      if totp_repo::use_step(&state.db, user_id, step).await? {
+          return Ok(());
+      }
+      // A concurrent request spent the same code first.
+      tracing::warn!(user_id, "Two-factor code refused: already used");
+      state.metrics.auth_failure("mfa_replayed");
+      return Err(ApiError::Unauthorized("Invalid code".into()));
+  }
+
+  let code_hash = hash_token(&normalize_recovery_code(code));
+  if recovery_code_repo::consume(&state.db, user_id, &code_hash).await? {
+      totp_repo::record_success(&state.db, user_id).await?;
+      return Ok(());
+  }
+
+  tracing::info!(user_id, "Two-factor code invalid");
+  state.metrics.auth_failure("mfa_invalid");
+  Err(ApiError::Unauthorized("Invalid code".into()))
+}
+
+// Secrets are encrypted at rest when `auth.totp_encryption_key` is set.
+fn seal(state: &AppState, user_id: &str, secret: &str) -> Result<String, ApiError> {
+  match state.config.auth.totp_key() {
+      Some(key) => Ok(seal_secret(&key, user_id, secret)?),
+      None => Ok(secret.to_string()),
+  }
+}
+
+fn open(state: &AppState, totp: &UserTotpModel) -> Result<String, ApiError> {
+  Ok(open_secret(state.config.auth.totp_key().as_ref(), &totp.user_id, &totp.secret)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    config::Config,
+    security::totp::code_at,
+    test_support::{self, rows_affected},
+  };
+
+  fn totp(last_used_step: Option<i64>) -> UserTotpModel {
+    UserTotpModel {
+      user_id: Uuid::new_v4().to_string(),
+      secret: generate_totp_secret(),
+      confirmed_at: Some(Utc::now()),
+      last_used_step,
+      failed_attempts: 0,
+      last_failed_at: None,
+      created_at: Utc::now(),
+    }
+  }
+
+  async fn verify_now(totp: UserTotpModel, spent: u64) -> (Result<(), ApiError>, Vec<String>) {
+    let code = code_at(&totp.secret, Utc::now().timestamp());
+    let db = test_support::mock_db()
+        .append_query_results([vec![totp.clone()]])
+        .append_exec_results([rows_affected(1), rows_affected(spent)])
+        .into_connection();
+    let state = test_support::state(db);
+    let result = verify_code(&state, &totp.user_id, &code).await;
+    (result, test_support::statements(state))
+  }
+
+  #[tokio::test]
+  async fn spends_the_step_with_a_conditional_update() {
+    let (result, statements) = verify_now(totp(None), 1).await;
+    assert!(result.is_ok());
+    let update = statements.iter().find(|s| s.contains("`last_used_step` =")).unwrap();
+    assert!(update.contains("`user_totp`.`last_used_step` IS NULL OR `user_totp`.`last_used_step` <"), "{}", update);
+  }
+
+  #[tokio::test]
+  async fn refuses_a_code_spent_by_a_concurrent_request() {
+    let (result, _) = verify_now(totp(None), 0).await;
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+  }
+
+  #[tokio::test]
+  async fn refuses_a_replayed_step_without_touching_it() {
+    let replayed = totp(Some(Utc::now().timestamp() / 30 + 1));
+    let code = code_at(&replayed.secret, Utc::now().timestamp());
+    let db = test_support::mock_db()
+        .append_query_results([vec![replayed.clone()]])
+        // The attempt is counted, then no recovery code matches.
+        .append_exec_results([rows_affected(1), rows_affected(0)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let result = verify_code(&state, &replayed.user_id, &code).await;
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("`last_used_step` =")));
+  }
+
+  #[tokio::test]
+  async fn locks_out_after_repeated_failures() {
+    let totp = totp(None);
+    let code = code_at(&totp.secret, Utc::now().timestamp());
+    // The window already holds the maximum, so the attempt cannot be counted.
+    let db = test_support::mock_db()
+        .append_query_results([vec![totp.clone()]])
+        .append_exec_results([rows_affected(0)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let result = verify_code(&state, &totp.user_id, &code).await;
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))));
+    assert_eq!(test_support::statements(state).len(), 2);
+  }
+
+  #[tokio::test]
+  async fn counts_attempts_with_a_single_conditional_update() {
+    let (_, statements) = verify_now(totp(None), 1).await;
+    let claim = statements.iter().find(|s| s.contains("`failed_attempts` =")).unwrap();
+    assert!(claim.contains("CASE WHEN"), "{}", claim);
+    assert!(claim.contains("`failed_attempts` + "), "{}", claim);
+    assert!(claim.contains("`user_totp`.`failed_attempts` <"), "{}", claim);
+  }
+
+  #[tokio::test]
+  async fn accepts_codes_for_an_encrypted_secret() {
+    let mut config = Config::default();
+    config.auth.totp_encryption_key = Some("0f".repeat(32).parse().unwrap());
+    let mut sealed = totp(None);
+    let code = code_at(&sealed.secret, Utc::now().timestamp());
+    sealed.secret = seal_secret(&config.auth.totp_key().unwrap(), &sealed.user_id, &sealed.secret).unwrap();
+    let db = test_support::mock_db()
+        .append_query_results([vec![sealed.clone()]])
+        .append_exec_results([rows_affected(1), rows_affected(1)])
+        .into_connection();
+    let state = test_support::state_with_config(db, config);
+
+    assert!(verify_code(&state, &sealed.user_id, &code).await.is_ok());
+  }
+}
@@ -1,4 +1,26 @@
+pub mod account;
+pub mod admin;
 pub mod auth;
 pub mod chat;
+pub mod export;
+pub mod command;
+pub mod filter;
+pub mod import;
+pub mod integration;
+pub mod mailer;
+pub mod markdown;
+pub mod mfa;
+pub mod moderation;
+pub mod notification;
+pub mod oidc;
+pub mod outbound;
+pub mod pin;
+pub mod poll;
+pub mod presence;
+pub mod report;
+pub mod retention;
 pub mod room;
-pub mod user;
\ No newline at end of file
+pub mod saved;
+pub mod schedule;
+pub mod unfurl;
+pub mod user;
@@ -0,0 +1,339 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::{
+    admin::UserRole,
+    chat::{EphemeralDto, MemberEventDto, WsOutboundMessage},
+    room::{RoomResponse, RoomRole, RoomSanctionRequest, RoomSanctionResponse, UpdateMemberRoleRequest, UpdateSlowModeRequest},
+  },
+  entities::{
+    message::{Column as MessageColumn, Entity as MessageEntity},
+    room_ban::Model as RoomBanModel,
+    room_member::Entity as RoomMemberEntity,
+    room_mute::Model as RoomMuteModel,
+  },
+  repositories::{room as room_repo, room_ban as ban_repo, room_member as member_repo, room_mute as mute_repo, user as user_repo},
+  response::ApiError,
+  services::{admin, room},
+};
+
+// How much authority a user has in a room. Global admins outrank every room role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+  Outsider,
+  Room(RoomRole),
+  GlobalAdmin,
+}
+
+async fn rank(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<Rank, ApiError> {
+  let user = user_repo::find_by_id(&state.db, &user_id.to_string()).await?;
+  if user.is_some_and(|u| UserRole::parse(&u.role) == Some(UserRole::Admin)) {
+      return Ok(Rank::GlobalAdmin);
+  }
+
+  let member = RoomMemberEntity::find_by_id((room_id.to_string(), user_id.to_string()))
+      .one(&state.db)
+      .await?;
+  Ok(match member {
+      Some(member) => Rank::Room(RoomRole::parse(&member.role).unwrap_or(RoomRole::Member)),
+      None => Rank::Outsider,
+  })
+}
+
+pub async fn is_moderator(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<bool, ApiError> {
+  Ok(rank(state, room_id, user_id).await? >= Rank::Room(RoomRole::Moderator))
+}
+
+pub async fn ensure_moderator(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  if !is_moderator(state, room_id, user_id).await? {
+      return Err(ApiError::Forbidden("Only room moderators can do this".into()));
+  }
+  Ok(())
+}
+
+pub async fn ensure_owner(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  if rank(state, room_id, user_id).await? < Rank::Room(RoomRole::Owner) {
+      return Err(ApiError::Forbidden("Only the room owner can do this".into()));
+  }
+  Ok(())
+}
+
+// A moderator may act on someone only if they hold a strictly higher rank.
+async fn ensure_outranks(state: &AppState, room_id: Uuid, actor_id: Uuid, target_id: Uuid) -> Result<(), ApiError> {
+  if actor_id == target_id {
+      return Err(ApiError::BadRequest("You cannot moderate yourself".into()));
+  }
+  let actor = rank(state, room_id, actor_id).await?;
+  if actor < Rank::Room(RoomRole::Moderator) {
+      return Err(ApiError::Forbidden("Only room moderators can do this".into()));
+  }
+  if rank(state, room_id, target_id).await? >= actor {
+      return Err(ApiError::Forbidden("You cannot moderate a member with the same or a higher role".into()));
+  }
+  Ok(())
+}
+
+pub async fn ensure_not_banned(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  if let Some(ban) = ban_repo::find_active(&state.db, &room_id.to_string(), &user_id.to_string()).await? {
+      return Err(ApiError::Forbidden(match ban.expires_at {
+          Some(until) => format!("This user is banned from the room until {}", until.to_rfc3339()),
+          None => "This user is banned from the room".into(),
+      }));
+  }
+  Ok(())
+}
+
+// Checks a member's mute and the room's slow mode before a message is stored.
+pub async fn ensure_can_post(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  if let Some(mute) = mute_repo::find_active(&state.db, &room_id.to_string(), &user_id.to_string()).await? {
+      return Err(ApiError::Forbidden(match mute.expires_at {
+          Some(until) => format!("You are muted in this room until {}", until.to_rfc3339()),
+          None => "You are muted in this room".into(),
+      }));
+  }
+
+  let room = room_repo::find_by_id(&state.db, &room_id.to_string()).await?;
+  if room.slow_mode_seconds <= 0 || is_moderator(state, room_id, user_id).await? {
+      return Ok(());
+  }
+
+  let last = MessageEntity::find()
+      .filter(MessageColumn::RoomId.eq(room_id.to_string()))
+      .filter(MessageColumn::SenderId.eq(user_id.to_string()))
+      .order_by_desc(MessageColumn::CreatedAt)
+      .one(&state.db)
+      .await?;
+  if let Some(last) = last {
+      let next_allowed = last.created_at + Duration::seconds(room.slow_mode_seconds.into());
+      let wait = (next_allowed - Utc::now()).num_seconds();
+      if wait >= 0 {
+          return Err(ApiError::TooManyRequests(format!(
+              "Slow mode is on; you can send another message in {} seconds",
+              wait + 1
+          )));
+      }
+  }
+  Ok(())
+}
+
+// Remove another member from the room. They can be re-added unless also banned.
+pub async fn kick_member(state: &AppState, room_id: Uuid, moderator_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  ensure_outranks(state, room_id, moderator_id, user_id).await?;
+
+  member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &user_id.to_string())
+      .await
+      .map_err(|_| ApiError::NotFound("User is not a member of this room".into()))?;
+  member_repo::delete(&state.db, &room_id.to_string(), &user_id.to_string()).await?;
+  let _ = state.chat_tx.send(WsOutboundMessage::MemberRemoved(MemberEventDto { room_id, user_id }));
+
+  admin::record(state, Some(moderator_id), "room.member_kicked", "room", Some(room_id.to_string()), json!({ "user_id": user_id })).await;
+  Ok(())
+}
+
+pub async fn ban_member(
+  state: &AppState,
+  room_id: Uuid,
+  moderator_id: Uuid,
+  req: RoomSanctionRequest,
+) -> Result<RoomSanctionResponse, ApiError> {
+  ensure_outranks(state, room_id, moderator_id, req.user_id).await?;
+  user_repo::find_by_id(&state.db, &req.user_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+
+  let (reason, expires_at) = sanction_terms(&req);
+  let ban = ban_repo::upsert(
+      &state.db,
+      &room_id.to_string(),
+      &req.user_id.to_string(),
+      &moderator_id.to_string(),
+      reason.clone(),
+      expires_at,
+  )
+  .await?;
+
+  // Banned members leave the room; the event closes their open sockets.
+  if member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &req.user_id.to_string()).await.is_ok() {
+      member_repo::delete(&state.db, &room_id.to_string(), &req.user_id.to_string()).await?;
+      let _ = state.chat_tx.send(WsOutboundMessage::MemberRemoved(MemberEventDto { room_id, user_id: req.user_id }));
+  }
+
+  admin::record(
+      state,
+      Some(moderator_id),
+      "room.member_banned",
+      "room",
+      Some(room_id.to_string()),
+      json!({ "user_id": req.user_id, "until": expires_at, "reason": reason }),
+  )
+  .await;
+
+  to_sanction_response(ban.room_id, ban.user_id, ban.created_by, ban.reason, ban.expires_at, ban.created_at)
+}
+
+pub async fn unban_member(state: &AppState, room_id: Uuid, moderator_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  ensure_moderator(state, room_id, moderator_id).await?;
+
+  if !ban_repo::delete(&state.db, &room_id.to_string(), &user_id.to_string()).await? {
+      return Err(ApiError::NotFound("User is not banned from this room".into()));
+  }
+
+  admin::record(state, Some(moderator_id), "room.member_unbanned", "room", Some(room_id.to_string()), json!({ "user_id": user_id })).await;
+  Ok(())
+}
+
+pub async fn list_bans(state: &AppState, room_id: Uuid, moderator_id: Uuid) -> Result<Vec<RoomSanctionResponse>, ApiError> {
+  ensure_moderator(state, room_id, moderator_id).await?;
+
+  ban_repo::list_active_by_room(&state.db, &room_id.to_string())
+      .await?
+      .into_iter()
+      .map(|ban: RoomBanModel| to_sanction_response(ban.room_id, ban.user_id, ban.created_by, ban.reason, ban.expires_at, ban.created_at))
+      .collect()
+}
+
+pub async fn mute_member(
+  state: &AppState,
+  room_id: Uuid,
+  moderator_id: Uuid,
+  req: RoomSanctionRequest,
+) -> Result<RoomSanctionResponse, ApiError> {
+  ensure_outranks(state, room_id, moderator_id, req.user_id).await?;
+  member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &req.user_id.to_string())
+      .await
+      .map_err(|_| ApiError::NotFound("User is not a member of this room".into()))?;
+
+  let (reason, expires_at) = sanction_terms(&req);
+  let mute = mute_repo::upsert(
+      &state.db,
+      &room_id.to_string(),
+      &req.user_id.to_string(),
+      &moderator_id.to_string(),
+      reason.clone(),
+      expires_at,
+  )
+  .await?;
+
+  let content = match expires_at {
+      Some(until) => format!("You have been muted in this room until {}", until.to_rfc3339()),
+      None => "You have been muted in this room".to_string(),
+  };
+  notify(state, room_id, req.user_id, content);
+
+  admin::record(
+      state,
+      Some(moderator_id),
+      "room.member_muted",
+      "room",
+      Some(room_id.to_string()),
+      json!({ "user_id": req.user_id, "until": expires_at, "reason": reason }),
+  )
+  .await;
+
+  to_sanction_response(mute.room_id, mute.user_id, mute.created_by, mute.reason, mute.expires_at, mute.created_at)
+}
+
+pub async fn unmute_member(state: &AppState, room_id: Uuid, moderator_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  ensure_moderator(state, room_id, moderator_id).await?;
+
+  if !mute_repo::delete(&state.db, &room_id.to_string(), &user_id.to_string()).await? {
+      return Err(ApiError::NotFound("User is not muted in this room".into()));
+  }
+  notify(state, room_id, user_id, "You are no longer muted in this room".into());
+
+  admin::record(state, Some(moderator_id), "room.member_unmuted", "room", Some(room_id.to_string()), json!({ "user_id": user_id })).await;
+  Ok(())
+}
+
+pub async fn list_mutes(state: &AppState, room_id: Uuid, moderator_id: Uuid) -> Result<Vec<RoomSanctionResponse>, ApiError> {
+  ensure_moderator(state, room_id, moderator_id).await?;
+
+  mute_repo::list_active_by_room(&state.db, &room_id.to_string())
+      .await?
+      .into_iter()
+      .map(|mute: RoomMuteModel| to_sanction_response(mute.room_id, mute.user_id, mute.created_by, mute.reason, mute.expires_at, mute.created_at))
+      .collect()
+}
+
+pub async fn update_slow_mode(
+  state: &AppState,
+  room_id: Uuid,
+  moderator_id: Uuid,
+  req: UpdateSlowModeRequest,
+) -> Result<RoomResponse, ApiError> {
+  ensure_moderator(state, room_id, moderator_id).await?;
+
+  let current = room_repo::find_by_id(&state.db, &room_id.to_string()).await?;
+  let updated = room_repo::update_slow_mode(&state.db, current, req.seconds as i32).await?;
+  let response = room::to_response(updated)?;
+  let _ = state.chat_tx.send(WsOutboundMessage::RoomUpdated(response.clone()));
+
+  Ok(response)
+}
+
+// Only the owner (or a global admin) hands out or takes away the moderator role.
+pub async fn update_member_role(
+  state: &AppState,
+  room_id: Uuid,
+  requester_id: Uuid,
+  user_id: Uuid,
+  req: UpdateMemberRoleRequest,
+) -> Result<(), ApiError> {
+  if rank(state, room_id, requester_id).await? < Rank::Room(RoomRole::Owner) {
+      return Err(ApiError::Forbidden("Only the room owner can change member roles".into()));
+  }
+  if req.role == RoomRole::Owner {
+      return Err(ApiError::BadRequest("A room has exactly one owner".into()));
+  }
+
+  let member = member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &user_id.to_string())
+      .await
+      .map_err(|_| ApiError::NotFound("User is not a member of this room".into()))?;
+  if RoomRole::parse(&member.role) == Some(RoomRole::Owner) {
+      return Err(ApiError::BadRequest("The owner's role cannot be changed".into()));
+  }
+
+  member_repo::update_role(&state.db, member, req.role.as_str()).await?;
+  notify(state, room_id, user_id, format!("Your role in this room is now {}", req.role.as_str()));
+  Ok(())
+}
+
+fn sanction_terms(req: &RoomSanctionRequest) -> (Option<String>, Option<DateTime<Utc>>) {
+  let reason = req
+      .reason
+      .as_deref()
+      .map(str::trim)
+      .filter(|r| !r.is_empty())
+      .map(str::to_string);
+  let expires_at = req.duration_seconds.map(|secs| Utc::now() + Duration::seconds(secs));
+  (reason, expires_at)
+}
+
+// Tell the affected member's live sockets right away.
+fn notify(state: &AppState, room_id: Uuid, user_id: Uuid, content: String) {
+  let _ = state.chat_tx.send(WsOutboundMessage::Ephemeral(EphemeralDto { room_id, user_id, content }));
+}
+
+fn to_sanction_response(
+  room_id: String,
+  user_id: String,
+  created_by: Option<String>,
+  reason: Option<String>,
+  expires_at: Option<DateTime<Utc>>,
+  created_at: DateTime<Utc>,
+) -> Result<RoomSanctionResponse, ApiError> {
+  Ok(RoomSanctionResponse {
+      room_id: Uuid::parse_str(&room_id)
+          .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
+      user_id: Uuid::parse_str(&user_id)
+          .map_err(|_| ApiError::InternalServerError("Invalid user id".into()))?,
+      created_by: created_by.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+      reason,
+      expires_at,
+      created_at,
+  })
+}
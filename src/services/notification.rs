@@ -0,0 +1,602 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::Instrument;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+  database::{AppState, SharedState},
+  dtos::{
+    chat::MessageDto,
+    notification::{
+      CreateWebhookRequest, ListNotificationsQuery, NotificationKind, NotificationResponse,
+      NotificationStatus, NotifyLevel, NotifySettingsResponse, UpdateNotifySettingsRequest,
+      WebhookCreatedResponse, WebhookPayload, WebhookResponse,
+    },
+  },
+  entities::{
+    notification::Model as NotificationModel, room_member::Model as RoomMemberModel,
+    webhook_endpoint::Model as WebhookEndpointModel,
+  },
+  repositories::{
+    notification as notification_repo, room as room_repo, room_member as member_repo, user as user_repo,
+    user_block as block_repo, webhook_endpoint as webhook_repo,
+  },
+  response::ApiError,
+  security::{generate_secret, sign_payload},
+  services::outbound,
+};
+
+// Attempts before a notification is moved to the dead-letter state.
+pub const MAX_ATTEMPTS: i32 = 8;
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+// Long enough to outlast a delivery, so a leased notification is never sent twice.
+const LEASE_SECS: i64 = 60;
+const BATCH_SIZE: u64 = 50;
+const BASE_BACKOFF_SECS: i64 = 10;
+const MAX_BACKOFF_SECS: i64 = 3600;
+
+pub const SIGNATURE_HEADER: &str = "X-Chat-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Chat-Timestamp";
+
+pub async fn create_webhook(
+  state: &AppState,
+  user_id: Uuid,
+  req: CreateWebhookRequest,
+) -> Result<WebhookCreatedResponse, ApiError> {
+  // Checked again on every delivery, since the name may later resolve somewhere else.
+  let url = Url::parse(&req.url).map_err(|_| ApiError::BadRequest("Webhook url is not a valid URL".into()))?;
+  if let Err(e) = outbound::pinned_client(&url, state.config.integrations.allow_private_networks).await {
+      return Err(ApiError::BadRequest(format!("Webhook url is not allowed: {}", e)));
+  }
+
+  let endpoint = WebhookEndpointModel {
+    id: Uuid::new_v4().to_string(),
+    user_id: user_id.to_string(),
+    url: req.url,
+    secret: generate_secret(),
+    created_at: Utc::now(),
+  };
+  let endpoint = webhook_repo::insert(&state.db, endpoint).await?;
+
+  Ok(WebhookCreatedResponse {
+      id: parse_id(&endpoint.id, "webhook")?,
+      url: endpoint.url,
+      secret: endpoint.secret,
+      created_at: endpoint.created_at,
+  })
+}
+
+pub async fn list_webhooks(state: &AppState, user_id: Uuid) -> Result<Vec<WebhookResponse>, ApiError> {
+  let endpoints = webhook_repo::list_by_user(&state.db, &user_id.to_string()).await?;
+
+  endpoints
+      .into_iter()
+      .map(|endpoint| {
+        Ok(WebhookResponse {
+            id: parse_id(&endpoint.id, "webhook")?,
+            url: endpoint.url,
+            created_at: endpoint.created_at,
+        })
+      })
+      .collect()
+}
+
+pub async fn delete_webhook(state: &AppState, user_id: Uuid, webhook_id: Uuid) -> Result<(), ApiError> {
+  webhook_repo::delete(&state.db, &webhook_id.to_string(), &user_id.to_string())
+      .await
+      .map_err(|_| ApiError::NotFound("Webhook not found".into()))
+}
+
+pub async fn list_notifications(
+  state: &AppState,
+  user_id: Uuid,
+  params: ListNotificationsQuery,
+) -> Result<Vec<NotificationResponse>, ApiError> {
+  let limit = params.limit.unwrap_or(50).min(200);
+  let models = notification_repo::list_by_user(&state.db, &user_id.to_string(), limit).await?;
+
+  models
+      .into_iter()
+      .map(|model| {
+        Ok(NotificationResponse {
+            id: parse_id(&model.id, "notification")?,
+            room_id: parse_id(&model.room_id, "room")?,
+            message_id: parse_id(&model.message_id, "message")?,
+            kind: model.kind,
+            status: model.status,
+            attempts: model.attempts,
+            last_error: model.last_error,
+            created_at: model.created_at,
+            delivered_at: model.delivered_at,
+        })
+      })
+      .collect()
+}
+
+pub async fn update_notify_settings(
+  state: &AppState,
+  room_id: Uuid,
+  user_id: Uuid,
+  req: UpdateNotifySettingsRequest,
+) -> Result<NotifySettingsResponse, ApiError> {
+  let member = member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &user_id.to_string())
+      .await
+      .map_err(|_| ApiError::Forbidden("You are not a member of this room".into()))?;
+
+  let level = req
+      .level
+      .or_else(|| NotifyLevel::parse(&member.notify_level))
+      .unwrap_or(NotifyLevel::Mentions);
+  let muted = req.muted.unwrap_or(member.notifications_muted);
+
+  member_repo::update_notify_settings(&state.db, member, level.as_str().to_string(), muted).await?;
+
+  Ok(NotifySettingsResponse { room_id, level, muted })
+}
+
+// Queue webhook deliveries for members who are offline and whose room settings allow it.
+pub async fn enqueue_for_message(state: &AppState, message: &MessageDto) -> Result<(), ApiError> {
+  let room = room_repo::find_by_id(&state.db, &message.room_id.to_string()).await?;
+  let members = member_repo::list_by_room(&state.db, &room.id).await?;
+  let mentions = extract_mentions(&message.content);
+
+  let mut recipients = Vec::new();
+  for member in members {
+      let user_id = parse_id(&member.user_id, "user")?;
+      if user_id == message.sender_id || state.presence.is_online(user_id) || member.notifications_muted {
+          continue;
+      }
+      recipients.push((user_id, member));
+  }
+
+  // Blocks and mentions are looked up for all recipients at once rather than one by one.
+  let blockers: HashSet<String> =
+      block_repo::list_blockers_among(&state.db, &message.sender_id.to_string(), member_ids(&recipients))
+          .await?
+          .into_iter()
+          .map(|block| block.blocker_id)
+          .collect();
+  recipients.retain(|(_, member)| !blockers.contains(&member.user_id));
+
+  let mentioned: HashSet<String> = if room.is_direct || mentions.is_empty() {
+      HashSet::new()
+  } else {
+      user_repo::list_by_ids(&state.db, member_ids(&recipients))
+          .await?
+          .into_iter()
+          .filter(|user| mentions.contains(&user.username.to_lowercase()))
+          .map(|user| user.id)
+          .collect()
+  };
+
+  for (user_id, member) in recipients {
+      let kind = if room.is_direct {
+          NotificationKind::Dm
+      } else if mentioned.contains(&member.user_id) {
+          NotificationKind::Mention
+      } else {
+          NotificationKind::Message
+      };
+
+      let level = NotifyLevel::parse(&member.notify_level).unwrap_or(NotifyLevel::Mentions);
+      if !level.allows(kind) {
+          continue;
+      }
+
+      enqueue_for_user(state, user_id, kind, message).await?;
+  }
+
+  Ok(())
+}
+
+// A due reminder reaches the user's webhooks when no socket is open to show it.
+// The user asked for it, so room notification levels don't apply.
+pub async fn enqueue_reminder(state: &AppState, user_id: Uuid, message: &MessageDto) -> Result<(), ApiError> {
+  if state.presence.is_online(user_id) {
+      return Ok(());
+  }
+  enqueue_for_user(state, user_id, NotificationKind::Reminder, message).await
+}
+
+async fn enqueue_for_user(
+  state: &AppState,
+  user_id: Uuid,
+  kind: NotificationKind,
+  message: &MessageDto,
+) -> Result<(), ApiError> {
+  let endpoints = webhook_repo::list_by_user(&state.db, &user_id.to_string()).await?;
+  for endpoint in endpoints {
+      let id = Uuid::new_v4();
+      let payload = serde_json::to_string(&WebhookPayload {
+          id,
+          kind,
+          user_id,
+          message: message.clone(),
+      })
+      .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+      let now = Utc::now();
+      notification_repo::insert(
+          &state.db,
+          NotificationModel {
+            id: id.to_string(),
+            user_id: user_id.to_string(),
+            endpoint_id: endpoint.id,
+            room_id: message.room_id.to_string(),
+            message_id: message.id.to_string(),
+            kind: kind.as_str().to_string(),
+            payload,
+            status: NotificationStatus::Pending.as_str().to_string(),
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_at: now,
+            delivered_at: None,
+          },
+      )
+      .await?;
+  }
+  Ok(())
+}
+
+// Background task delivering due notifications until the process exits.
+pub fn spawn_dispatcher(state: SharedState) -> tokio::task::JoinHandle<()> {
+  tokio::spawn(async move {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = dispatch_due(state.as_ref()).await {
+            tracing::error!(error = %e, "Notification dispatch failed");
+        }
+    }
+  }
+  .instrument(tracing::info_span!("notification_dispatcher")))
+}
+
+async fn dispatch_due(state: &AppState) -> Result<(), ApiError> {
+  let due = notification_repo::list_due(&state.db, Utc::now(), BATCH_SIZE).await?;
+
+  for notification in due {
+      let now = Utc::now();
+      let leased_until = now + chrono::Duration::seconds(LEASE_SECS);
+      if !notification_repo::claim(&state.db, &notification.id, now, leased_until).await? {
+          // Another dispatcher took it between the listing and the claim.
+          continue;
+      }
+
+      let endpoint = webhook_repo::find_by_id(&state.db, &notification.endpoint_id).await?;
+      let result = match endpoint {
+          Some(endpoint) => deliver(state, &endpoint, &notification.payload).await,
+          None => Err("Webhook endpoint no longer exists".to_string()),
+      };
+
+      match result {
+          Ok(()) => {
+              notification_repo::mark_delivered(&state.db, notification, Utc::now()).await?;
+          }
+          Err(error) => {
+              let attempts = notification.attempts + 1;
+              let next_attempt_at = (attempts < MAX_ATTEMPTS)
+                  .then(|| Utc::now() + chrono::Duration::seconds(backoff_secs(attempts)));
+              notification_repo::mark_failed(&state.db, notification, error, next_attempt_at).await?;
+          }
+      }
+  }
+
+  Ok(())
+}
+
+async fn deliver(
+  state: &AppState,
+  endpoint: &WebhookEndpointModel,
+  payload: &str,
+) -> Result<(), String> {
+  // The url is user-supplied: only public addresses, unless the operator allows private ones.
+  let url = Url::parse(&endpoint.url).map_err(|e| e.to_string())?;
+  let client = outbound::pinned_client(&url, state.config.integrations.allow_private_networks)
+      .await
+      .and_then(|builder| Ok(builder.timeout(DELIVERY_TIMEOUT).build()?))
+      .map_err(|e| e.to_string())?;
+
+  let timestamp = Utc::now().timestamp();
+  let signature = sign_payload(&endpoint.secret, timestamp, payload);
+
+  let response = client
+      .post(url)
+      .header(reqwest::header::CONTENT_TYPE, "application/json")
+      .header(SIGNATURE_HEADER, signature)
+      .header(TIMESTAMP_HEADER, timestamp.to_string())
+      .body(payload.to_owned())
+      .send()
+      .await
+      .map_err(|e| e.to_string())?;
+
+  if response.status().is_success() {
+      Ok(())
+  } else {
+      Err(format!("Webhook responded with status {}", response.status()))
+  }
+}
+
+// Exponential backoff: 10s, 20s, 40s, ... capped at one hour.
+fn backoff_secs(attempts: i32) -> i64 {
+  let exponent = (attempts - 1).clamp(0, 16) as u32;
+  (BASE_BACKOFF_SECS * 2i64.pow(exponent)).min(MAX_BACKOFF_SECS)
+}
+
+// Collect lower-cased `@username` tokens from message content.
+fn extract_mentions(content: &str) -> HashSet<String> {
+  content
+      .split_whitespace()
+      .filter_map(|word| word.strip_prefix('@'))
+      .map(|name| name.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_' && c != '-'))
+      .filter(|name| !name.is_empty())
+      .map(|name| name.to_lowercase())
+      .collect()
+}
+
+fn member_ids(recipients: &[(Uuid, RoomMemberModel)]) -> Vec<String> {
+  recipients.iter().map(|(_, member)| member.user_id.clone()).collect()
+}
+
+fn parse_id(value: &str, label: &str) -> Result<Uuid, ApiError> {
+  Uuid::parse_str(value).map_err(|_| ApiError::InternalServerError(format!("Invalid {} id", label)))
+}
+
+#[cfg(test)]
+mod tests {
+  use sea_orm::DatabaseConnection;
+  use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+  use crate::{
+    config::Config,
+    dtos::{admin::UserRole, room::RoomRole},
+    entities::user_block::Model as UserBlockModel,
+    test_support::{self, rows_affected},
+  };
+
+  const SECRET: &str = "endpoint-secret";
+
+  fn endpoint(url: String) -> WebhookEndpointModel {
+    WebhookEndpointModel {
+      id: Uuid::new_v4().to_string(),
+      user_id: Uuid::new_v4().to_string(),
+      url,
+      secret: SECRET.to_string(),
+      created_at: Utc::now(),
+    }
+  }
+
+  fn pending(endpoint: &WebhookEndpointModel, attempts: i32) -> NotificationModel {
+    NotificationModel {
+      id: Uuid::new_v4().to_string(),
+      user_id: endpoint.user_id.clone(),
+      endpoint_id: endpoint.id.clone(),
+      room_id: Uuid::new_v4().to_string(),
+      message_id: Uuid::new_v4().to_string(),
+      kind: NotificationKind::Mention.as_str().to_string(),
+      payload: r#"{"hello":"world"}"#.to_string(),
+      status: NotificationStatus::Pending.as_str().to_string(),
+      attempts,
+      next_attempt_at: Utc::now(),
+      last_error: None,
+      created_at: Utc::now(),
+      delivered_at: None,
+    }
+  }
+
+  // One due notification that this instance manages to claim, followed by its outcome update.
+  fn claimed_db(endpoint: &WebhookEndpointModel, notification: &NotificationModel) -> DatabaseConnection {
+    test_support::mock_db()
+        .append_query_results([vec![notification.clone()]])
+        .append_query_results([vec![endpoint.clone()]])
+        .append_query_results([vec![notification.clone()]])
+        .append_exec_results([rows_affected(1), rows_affected(1)])
+        .into_connection()
+  }
+
+  // The mock servers listen on loopback, which deliveries refuse unless private networks are allowed.
+  fn local_state(db: DatabaseConnection) -> AppState {
+    let mut config = Config::default();
+    config.integrations.allow_private_networks = true;
+    test_support::state_with_config(db, config)
+  }
+
+  async fn dispatch(state: &AppState) {
+    dispatch_due(state).await.expect("dispatch");
+  }
+
+  #[tokio::test]
+  async fn delivers_payload_signed_with_the_endpoint_secret() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(204)).expect(1).mount(&server).await;
+    let endpoint = endpoint(server.uri());
+    let notification = pending(&endpoint, 0);
+    let state = local_state(claimed_db(&endpoint, &notification));
+
+    dispatch(&state).await;
+
+    let requests = server.received_requests().await.unwrap();
+    let request = &requests[0];
+    let timestamp: i64 = request.headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+    let signature = request.headers[SIGNATURE_HEADER].to_str().unwrap();
+    assert_eq!(signature, sign_payload(SECRET, timestamp, &notification.payload));
+    assert_eq!(String::from_utf8_lossy(&request.body), notification.payload);
+    assert!(test_support::statements(state).iter().any(|s| s.contains("\"delivered\"")));
+  }
+
+  #[tokio::test]
+  async fn skips_notifications_claimed_by_another_dispatcher() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(204)).expect(0).mount(&server).await;
+    let endpoint = endpoint(server.uri());
+    let notification = pending(&endpoint, 0);
+    let db = test_support::mock_db()
+        .append_query_results([vec![notification]])
+        .append_exec_results([rows_affected(0)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    dispatch(&state).await;
+
+    // The listing and the lost claim, nothing else.
+    assert_eq!(test_support::statements(state).len(), 2);
+  }
+
+  #[tokio::test]
+  async fn failed_delivery_is_rescheduled_with_backoff() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(500)).expect(1).mount(&server).await;
+    let endpoint = endpoint(server.uri());
+    let notification = pending(&endpoint, 2);
+    let state = local_state(claimed_db(&endpoint, &notification));
+
+    dispatch(&state).await;
+
+    let statements = test_support::statements(state);
+    let update = statements.iter().rev().find(|s| s.contains("UPDATE")).unwrap();
+    assert!(update.contains("next_attempt_at"));
+    assert!(update.contains("status 500"));
+    assert!(!update.contains("\"dead\""));
+  }
+
+  #[tokio::test]
+  async fn last_failed_attempt_moves_to_dead_letter() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(503)).expect(1).mount(&server).await;
+    let endpoint = endpoint(server.uri());
+    let notification = pending(&endpoint, MAX_ATTEMPTS - 1);
+    let state = local_state(claimed_db(&endpoint, &notification));
+
+    dispatch(&state).await;
+
+    let statements = test_support::statements(state);
+    let update = statements.iter().rev().find(|s| s.contains("UPDATE")).unwrap();
+    assert!(update.contains("\"dead\""));
+    assert!(!update.contains("next_attempt_at"));
+  }
+
+  #[tokio::test]
+  async fn refuses_to_deliver_to_private_addresses() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(204)).expect(0).mount(&server).await;
+    let endpoint = endpoint(server.uri());
+    let notification = pending(&endpoint, 0);
+    let state = test_support::state(claimed_db(&endpoint, &notification));
+
+    dispatch(&state).await;
+
+    let statements = test_support::statements(state);
+    let update = statements.iter().rev().find(|s| s.contains("UPDATE")).unwrap();
+    assert!(update.contains("non-public address"));
+  }
+
+  #[tokio::test]
+  async fn refuses_private_and_link_local_webhook_urls() {
+    let state = test_support::state(test_support::mock_db().into_connection());
+
+    for url in ["http://10.0.0.5/hook", "http://169.254.169.254/latest/meta-data", "http://[fe80::1]/hook", "ftp://example.com/"] {
+        let req = CreateWebhookRequest { url: url.to_string() };
+        let result = create_webhook(&state, Uuid::new_v4(), req).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(_))), "{} should be refused", url);
+    }
+    assert!(test_support::statements(state).is_empty());
+  }
+
+  #[test]
+  fn backoff_doubles_up_to_an_hour() {
+    assert_eq!(backoff_secs(1), 10);
+    assert_eq!(backoff_secs(2), 20);
+    assert_eq!(backoff_secs(4), 80);
+    assert_eq!(backoff_secs(MAX_ATTEMPTS), 1280);
+    assert_eq!(backoff_secs(30), MAX_BACKOFF_SECS);
+  }
+
+  #[test]
+  fn extracts_mentions_without_trailing_punctuation() {
+    let mentions = extract_mentions("hey @Alice, ask @bob_2! and @ alone");
+    assert_eq!(mentions, HashSet::from(["alice".to_string(), "bob_2".to_string()]));
+  }
+
+  #[tokio::test]
+  async fn direct_room_notifies_the_other_side_as_a_dm() {
+    let (sender, recipient) = (Uuid::new_v4(), Uuid::new_v4());
+    let room = test_support::room(true);
+    let endpoint = endpoint("http://127.0.0.1:9/hook".into());
+    let db = test_support::mock_db()
+        .append_query_results([vec![room.clone()]])
+        .append_query_results([vec![
+            test_support::member(&room.id, sender, RoomRole::Member),
+            test_support::member(&room.id, recipient, RoomRole::Member),
+        ]])
+        .append_query_results([Vec::<UserBlockModel>::new()])
+        .append_query_results([vec![endpoint.clone()]])
+        .append_query_results([vec![pending(&endpoint, 0)]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let message = test_support::message(Uuid::parse_str(&room.id).unwrap(), sender, "are you around?");
+    enqueue_for_message(&state, &message).await.unwrap();
+
+    let statements = test_support::statements(state);
+    assert!(statements.iter().any(|s| s.contains("INSERT") && s.contains("\"dm\"")));
+  }
+
+  #[tokio::test]
+  async fn two_member_group_room_is_not_a_dm() {
+    let (sender, recipient) = (Uuid::new_v4(), Uuid::new_v4());
+    let room = test_support::room(false);
+    let db = test_support::mock_db()
+        .append_query_results([vec![room.clone()]])
+        .append_query_results([vec![
+            test_support::member(&room.id, sender, RoomRole::Member),
+            test_support::member(&room.id, recipient, RoomRole::Member),
+        ]])
+        .append_query_results([Vec::<UserBlockModel>::new()])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let message = test_support::message(Uuid::parse_str(&room.id).unwrap(), sender, "are you around?");
+    enqueue_for_message(&state, &message).await.unwrap();
+
+    // A plain message is below the default "mentions" level, so nothing is queued.
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT")));
+  }
+
+  #[tokio::test]
+  async fn looks_up_blocks_and_mentions_once_per_message() {
+    let (sender, mentioned, other, blocker) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let room = test_support::room(false);
+    let mentioned_user = test_support::user(mentioned, UserRole::User);
+    let endpoint = endpoint("http://127.0.0.1:9/hook".into());
+    let block = UserBlockModel { blocker_id: blocker.to_string(), blocked_id: sender.to_string(), created_at: Utc::now() };
+    let db = test_support::mock_db()
+        .append_query_results([vec![room.clone()]])
+        .append_query_results([[sender, mentioned, other, blocker].map(|id| test_support::member(&room.id, id, RoomRole::Member))])
+        .append_query_results([vec![block]])
+        .append_query_results([vec![mentioned_user.clone(), test_support::user(other, UserRole::User)]])
+        .append_query_results([vec![endpoint.clone()]])
+        .append_query_results([vec![pending(&endpoint, 0)]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    // The blocker is mentioned too, but never hears from the sender.
+    let content = format!("@{} and @user-{}", mentioned_user.username, &blocker.simple().to_string()[..8]);
+    let message = test_support::message(Uuid::parse_str(&room.id).unwrap(), sender, &content);
+    enqueue_for_message(&state, &message).await.unwrap();
+
+    let statements = test_support::statements(state);
+    // Room, members, blocks, users, the mentioned user's endpoints, then the insert and its read-back.
+    assert_eq!(statements.len(), 7);
+    let inserts: Vec<_> = statements.iter().filter(|s| s.contains("INSERT")).collect();
+    assert_eq!(inserts.len(), 1);
+    assert!(inserts[0].contains("\"mention\"") && inserts[0].contains(&mentioned.to_string()));
+  }
+}
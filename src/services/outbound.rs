@@ -0,0 +1,189 @@
+use std::{
+  net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+  time::Duration,
+};
+
+use reqwest::{redirect::Policy, ClientBuilder, Response};
+use url::{Host, Url};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+// Replies from webhooks and bots are small JSON documents; anything bigger is refused.
+pub const MAX_REPLY_BYTES: usize = 64 * 1024;
+
+// Requests to user-supplied URLs (link previews, notification and outgoing webhooks, bot commands)
+// go through a client built here. Its connection is pinned to the addresses we validated, so a
+// second DNS lookup inside reqwest cannot point the request somewhere else, and redirects are not
+// followed.
+pub async fn pinned_client(url: &Url, allow_private: bool) -> anyhow::Result<ClientBuilder> {
+  if !matches!(url.scheme(), "http" | "https") {
+      anyhow::bail!("unsupported scheme {}", url.scheme());
+  }
+  let port = url
+      .port_or_known_default()
+      .ok_or_else(|| anyhow::anyhow!("missing port"))?;
+
+  let builder = reqwest::Client::builder()
+      .redirect(Policy::none())
+      .connect_timeout(CONNECT_TIMEOUT);
+
+  match url.host() {
+      Some(Host::Ipv4(ip)) => {
+          check_address(IpAddr::V4(ip), allow_private)?;
+          Ok(builder)
+      }
+      Some(Host::Ipv6(ip)) => {
+          check_address(IpAddr::V6(ip), allow_private)?;
+          Ok(builder)
+      }
+      Some(Host::Domain(domain)) => {
+          let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port)).await?.collect();
+          if addrs.is_empty() {
+              anyhow::bail!("{} did not resolve", domain);
+          }
+          for addr in &addrs {
+              check_address(addr.ip(), allow_private)?;
+          }
+          Ok(builder.resolve(domain, addrs[0]))
+      }
+      None => anyhow::bail!("missing host"),
+  }
+}
+
+// Reads a reply body, failing once it grows past `max` bytes instead of buffering all of it.
+pub async fn read_capped(mut response: Response, max: usize) -> anyhow::Result<Vec<u8>> {
+  if response.content_length().is_some_and(|len| len > max as u64) {
+      anyhow::bail!("reply body is larger than {} bytes", max);
+  }
+  let mut body = Vec::new();
+  while let Some(chunk) = response.chunk().await? {
+      if body.len() + chunk.len() > max {
+          anyhow::bail!("reply body is larger than {} bytes", max);
+      }
+      body.extend_from_slice(&chunk);
+  }
+  Ok(body)
+}
+
+fn check_address(ip: IpAddr, allow_private: bool) -> anyhow::Result<()> {
+  if !allow_private && !is_public(ip) {
+      anyhow::bail!("refusing to connect to non-public address {}", ip);
+  }
+  Ok(())
+}
+
+fn is_public(ip: IpAddr) -> bool {
+  match ip {
+      IpAddr::V4(ip) => is_public_v4(ip),
+      IpAddr::V6(ip) => match embedded_v4(ip) {
+          Some(v4) => is_public_v4(v4),
+          None => is_public_v6(ip),
+      },
+  }
+}
+
+// An IPv4 address carried inside an IPv6 one reaches that IPv4 host, so it gets the IPv4 checks:
+// mapped (::ffff:0:0/96), compatible (::/96), NAT64 (64:ff9b::/96) and 6to4 (2002::/16).
+fn embedded_v4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+  let segments = ip.segments();
+  let v4 = |high: u16, low: u16| Ipv4Addr::from((u32::from(high) << 16) | u32::from(low));
+  match segments {
+      [0, 0, 0, 0, 0, 0xffff, high, low] | [0, 0, 0, 0, 0, 0, high, low] => Some(v4(high, low)),
+      [0x64, 0xff9b, 0, 0, 0, 0, high, low] => Some(v4(high, low)),
+      [0x2002, high, low, ..] => Some(v4(high, low)),
+      _ => None,
+  }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+  let [a, b, c, _] = ip.octets();
+  !(ip.is_private()
+      || ip.is_loopback()
+      || ip.is_link_local()
+      || ip.is_broadcast()
+      || ip.is_documentation()
+      || ip.is_unspecified()
+      || ip.is_multicast()
+      || a == 0
+      || (a == 100 && (64..128).contains(&b))
+      || (a == 192 && b == 0 && c == 0)
+      || (a == 198 && (b == 18 || b == 19))
+      || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+  let [first, second, third, fourth, ..] = ip.segments();
+  !(ip.is_loopback()
+      || ip.is_unspecified()
+      || ip.is_multicast()
+      || (first & 0xfe00) == 0xfc00
+      || (first & 0xffc0) == 0xfe80
+      // Deprecated site-local.
+      || (first & 0xffc0) == 0xfec0
+      || (first == 0x2001 && second == 0x0db8)
+      // Teredo hides its IPv4 endpoint, so it cannot be checked.
+      || (first == 0x2001 && second == 0)
+      // Local-use NAT64 and the discard prefix.
+      || (first == 0x64 && second == 0xff9b && third == 1)
+      || (first == 0x100 && second == 0 && third == 0 && fourth == 0))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn public(ip: &str) -> bool {
+    is_public(ip.parse().unwrap())
+  }
+
+  #[test]
+  fn rejects_private_and_reserved_v4() {
+    for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "255.255.255.255"] {
+        assert!(!public(ip), "{} should not be public", ip);
+    }
+    assert!(public("93.184.216.34"));
+  }
+
+  #[test]
+  fn rejects_local_v6() {
+    for ip in ["::1", "::", "fe80::1", "fd00::1", "fec0::1", "2001:db8::1", "2001:0:4136:e378::1", "64:ff9b:1::1", "100::1"] {
+        assert!(!public(ip), "{} should not be public", ip);
+    }
+    assert!(public("2606:2800:220:1:248:1893:25c8:1946"));
+  }
+
+  #[test]
+  fn checks_ipv4_embedded_in_ipv6() {
+    // Mapped, compatible, NAT64 and 6to4 forms of loopback, private and metadata addresses.
+    for ip in [
+        "::ffff:127.0.0.1",
+        "::ffff:10.0.0.1",
+        "::127.0.0.1",
+        "::169.254.169.254",
+        "64:ff9b::a9fe:a9fe",
+        "64:ff9b::7f00:1",
+        "2002:7f00:1::",
+        "2002:c0a8:101::1",
+        "2002:a9fe:a9fe:1::1",
+    ] {
+        assert!(!public(ip), "{} should not be public", ip);
+    }
+    for ip in ["::ffff:93.184.216.34", "64:ff9b::5db8:d822", "2002:5db8:d822::1"] {
+        assert!(public(ip), "{} should be public", ip);
+    }
+  }
+
+  #[tokio::test]
+  async fn refuses_private_hosts_unless_allowed() {
+    for url in ["http://127.0.0.1:8080/hook", "http://[::ffff:7f00:1]/", "http://[2002:7f00:1::]/", "http://localhost/"] {
+        let url = Url::parse(url).unwrap();
+        assert!(pinned_client(&url, false).await.is_err(), "{} should be refused", url);
+        assert!(pinned_client(&url, true).await.is_ok(), "{} should be allowed", url);
+    }
+  }
+
+  #[tokio::test]
+  async fn refuses_other_schemes() {
+    let url = Url::parse("file:///etc/passwd").unwrap();
+    assert!(pinned_client(&url, true).await.is_err());
+  }
+}
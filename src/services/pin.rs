@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use sea_orm::EntityTrait;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::chat::{MessageUnpinnedDto, PinnedMessageDto, WsOutboundMessage},
+  entities::message::Entity as MessageEntity,
+  repositories::room_pin as pin_repo,
+  response::ApiError,
+  services::{admin, chat, moderation, user},
+};
+
+pub const MAX_PINS_PER_ROOM: u64 = 50;
+
+pub async fn list_pins(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<Vec<PinnedMessageDto>, ApiError> {
+  chat::ensure_membership(state, room_id, user_id).await?;
+
+  let pins = pin_repo::list_by_room(&state.db, &room_id.to_string()).await?;
+  // Pins by users the reader blocked stay hidden, as they are in the message list.
+  let blocked = user::blocked_ids(state, user_id).await?;
+  let mut messages: HashMap<Uuid, _> = chat::load_messages(state, pins.iter().map(|p| p.message_id.clone()).collect())
+      .await?
+      .into_iter()
+      .filter(|message| !blocked.contains(&message.sender_id))
+      .map(|message| (message.id, message))
+      .collect();
+
+  let mut result = Vec::new();
+  for pin in pins {
+      let Some(message) = Uuid::parse_str(&pin.message_id).ok().and_then(|id| messages.remove(&id)) else {
+          continue;
+      };
+      result.push(PinnedMessageDto {
+          room_id,
+          message,
+          pinned_by: pin.pinned_by.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+          pinned_at: pin.created_at,
+      });
+  }
+  Ok(result)
+}
+
+// Only room moderators pin, and each room keeps at most `MAX_PINS_PER_ROOM`.
+pub async fn pin_message(
+  state: &AppState,
+  room_id: Uuid,
+  message_id: Uuid,
+  user_id: Uuid,
+) -> Result<PinnedMessageDto, ApiError> {
+  moderation::ensure_moderator(state, room_id, user_id).await?;
+
+  let message = MessageEntity::find_by_id(message_id.to_string())
+      .one(&state.db)
+      .await?
+      .filter(|m| m.room_id == room_id.to_string())
+      .ok_or_else(|| ApiError::NotFound("Message not found in this room".into()))?;
+
+  if pin_repo::find(&state.db, &message.room_id, &message.id).await?.is_some() {
+      return Err(ApiError::BadRequest("Message is already pinned".into()));
+  }
+  if pin_repo::count_by_room(&state.db, &message.room_id).await? >= MAX_PINS_PER_ROOM {
+      return Err(ApiError::BadRequest(format!("A room can have at most {} pinned messages", MAX_PINS_PER_ROOM)));
+  }
+
+  let pin = pin_repo::insert(&state.db, &message.room_id, &message.id, &user_id.to_string()).await?;
+  let message = chat::load_messages(state, vec![pin.message_id.clone()])
+      .await?
+      .pop()
+      .ok_or_else(|| ApiError::NotFound("Message not found".into()))?;
+
+  let pinned = PinnedMessageDto {
+      room_id,
+      message,
+      pinned_by: Some(user_id),
+      pinned_at: pin.created_at,
+  };
+  let _ = state.chat_tx.send(WsOutboundMessage::MessagePinned(pinned.clone()));
+
+  admin::record(state, Some(user_id), "message.pinned", "message", Some(pin.message_id), json!({ "room_id": room_id })).await;
+  Ok(pinned)
+}
+
+pub async fn unpin_message(state: &AppState, room_id: Uuid, message_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  moderation::ensure_moderator(state, room_id, user_id).await?;
+
+  if !pin_repo::delete(&state.db, &room_id.to_string(), &message_id.to_string()).await? {
+      return Err(ApiError::NotFound("Message is not pinned".into()));
+  }
+  let _ = state.chat_tx.send(WsOutboundMessage::MessageUnpinned(MessageUnpinnedDto { room_id, message_id }));
+
+  admin::record(state, Some(user_id), "message.unpinned", "message", Some(message_id.to_string()), json!({ "room_id": room_id })).await;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use chrono::Utc;
+  use sea_orm::Value;
+
+  use super::*;
+  use crate::{
+    dtos::{admin::UserRole, room::RoomRole},
+    entities::{
+      message_link_preview::Model as MessageLinkPreviewModel, poll::Model as PollModel, room_pin::Model as RoomPinModel,
+      user_block::Model as UserBlockModel,
+    },
+    test_support::{self, rows_affected},
+  };
+
+  fn pin(message_id: &str) -> RoomPinModel {
+    RoomPinModel { room_id: String::new(), message_id: message_id.to_string(), pinned_by: None, created_at: Utc::now() }
+  }
+
+  #[tokio::test]
+  async fn members_cannot_pin() {
+    let room = test_support::room(false);
+    let user_id = Uuid::new_v4();
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::user(user_id, UserRole::User)]])
+        .append_query_results([vec![test_support::member(&room.id, user_id, RoomRole::Member)]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let result = pin_message(&state, Uuid::parse_str(&room.id).unwrap(), Uuid::new_v4(), user_id).await;
+    assert!(matches!(result, Err(ApiError::Forbidden(_))));
+  }
+
+  #[tokio::test]
+  async fn only_messages_of_the_room_can_be_pinned() {
+    let admin_id = Uuid::new_v4();
+    let elsewhere = test_support::message_row(&Uuid::new_v4().to_string(), Uuid::new_v4(), "hi");
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::user(admin_id, UserRole::Admin)]])
+        .append_query_results([vec![elsewhere.clone()]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let message_id = Uuid::parse_str(&elsewhere.id).unwrap();
+    let result = pin_message(&state, Uuid::new_v4(), message_id, admin_id).await;
+    assert!(matches!(result, Err(ApiError::NotFound(_))));
+  }
+
+  #[tokio::test]
+  async fn rooms_keep_a_bounded_number_of_pins() {
+    let admin_id = Uuid::new_v4();
+    let room_id = Uuid::new_v4();
+    let message = test_support::message_row(&room_id.to_string(), Uuid::new_v4(), "hi");
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::user(admin_id, UserRole::Admin)]])
+        .append_query_results([vec![message.clone()]])
+        .append_query_results([Vec::<RoomPinModel>::new()])
+        .append_query_results([[BTreeMap::from([("num_items".to_string(), Value::from(MAX_PINS_PER_ROOM as i32))])]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let result = pin_message(&state, room_id, Uuid::parse_str(&message.id).unwrap(), admin_id).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT")));
+  }
+
+  #[tokio::test]
+  async fn unpinning_tells_live_sockets_only_when_something_was_pinned() {
+    for (removed, expected_event) in [(0, false), (1, true)] {
+        let admin_id = Uuid::new_v4();
+        let db = test_support::mock_db()
+            .append_query_results([vec![test_support::user(admin_id, UserRole::Admin)]])
+            .append_exec_results([rows_affected(removed)])
+            .into_connection();
+        let state = test_support::state(db);
+        let mut events = state.chat_tx.subscribe();
+
+        let result = unpin_message(&state, Uuid::new_v4(), Uuid::new_v4(), admin_id).await;
+        assert_eq!(result.is_ok(), expected_event);
+        assert_eq!(matches!(events.try_recv(), Ok(WsOutboundMessage::MessageUnpinned(_))), expected_event);
+    }
+  }
+
+  #[tokio::test]
+  async fn pins_by_blocked_users_stay_hidden() {
+    let room = test_support::room(false);
+    let (reader_id, blocked_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let hidden = test_support::message_row(&room.id, blocked_id, "spam");
+    let shown = test_support::message_row(&room.id, Uuid::new_v4(), "rules");
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::member(&room.id, reader_id, RoomRole::Member)]])
+        .append_query_results([vec![pin(&hidden.id), pin(&shown.id)]])
+        .append_query_results([vec![UserBlockModel {
+            blocker_id: reader_id.to_string(),
+            blocked_id: blocked_id.to_string(),
+            created_at: Utc::now(),
+        }]])
+        .append_query_results([vec![hidden, shown.clone()]])
+        .append_query_results([Vec::<MessageLinkPreviewModel>::new()])
+        .append_query_results([Vec::<PollModel>::new()])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let pins = list_pins(&state, Uuid::parse_str(&room.id).unwrap(), reader_id).await.unwrap();
+    assert_eq!(pins.len(), 1);
+    assert_eq!(pins[0].message.id.to_string(), shown.id);
+  }
+}
@@ -0,0 +1,430 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use sea_orm::{EntityTrait, SqlErr};
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::{
+    chat::{MessageDto, MessageFormat, WsOutboundMessage},
+    poll::{CreatePollRequest, PollDto, PollOptionDto, PollUpdatedDto, VotePollRequest},
+  },
+  entities::{
+    message::Entity as MessageEntity,
+    poll::Model as PollModel,
+    poll_option::Model as PollOptionModel,
+    poll_vote::Model as PollVoteModel,
+  },
+  repositories::poll as poll_repo,
+  response::ApiError,
+  services::{chat, filter},
+};
+
+const MAX_OPTION_CHARS: usize = 200;
+
+// A poll is a regular message (the question) with options attached, so it goes through
+// the same membership, mute, slow mode and filter checks as any other message.
+pub async fn create_poll(
+  state: &AppState,
+  room_id: Uuid,
+  user_id: Uuid,
+  req: CreatePollRequest,
+) -> Result<MessageDto, ApiError> {
+  chat::ensure_membership(state, room_id, user_id).await?;
+  if req.closes_at.is_some_and(|at| at <= Utc::now()) {
+      return Err(ApiError::BadRequest("Closing time must be in the future".into()));
+  }
+
+  let mut texts = Vec::with_capacity(req.options.len());
+  let mut seen = HashSet::new();
+  for option in &req.options {
+      let text = option.trim();
+      if text.is_empty() || text.chars().count() > MAX_OPTION_CHARS {
+          return Err(ApiError::BadRequest(format!(
+              "Options must be between 1 and {} characters",
+              MAX_OPTION_CHARS
+          )));
+      }
+      if !seen.insert(text.to_lowercase()) {
+          return Err(ApiError::BadRequest("Options must be distinct".into()));
+      }
+      texts.push(filter::apply(state, room_id, text).await?.trim().to_string());
+  }
+
+  let mut message = chat::send_message(state, room_id, user_id, req.question, MessageFormat::Plain).await?;
+  let message_id = message.id.to_string();
+
+  let poll = PollModel {
+      message_id: message_id.clone(),
+      multiple_choice: req.multiple_choice,
+      anonymous: req.anonymous,
+      closes_at: req.closes_at,
+      created_at: Utc::now(),
+  };
+  let options: Vec<PollOptionModel> = texts
+      .into_iter()
+      .enumerate()
+      .map(|(position, text)| PollOptionModel {
+          id: Uuid::new_v4().to_string(),
+          poll_id: message_id.clone(),
+          position: position as i32,
+          text,
+      })
+      .collect();
+
+  let poll = match poll_repo::insert(&state.db, poll, options.clone()).await {
+      Ok(poll) => poll,
+      Err(e) => {
+          // Don't leave a bare question behind in the room.
+          let _ = MessageEntity::delete_by_id(message_id).exec(&state.db).await;
+          return Err(e.into());
+      }
+  };
+
+  message.poll = Some(tally(&poll, &options, &[], None)?);
+  let _ = state.chat_tx.send(WsOutboundMessage::MessageCreated(message.clone()));
+
+  message.poll = Some(tally(&poll, &options, &[], Some(user_id))?);
+  Ok(message)
+}
+
+pub async fn vote(
+  state: &AppState,
+  message_id: Uuid,
+  user_id: Uuid,
+  req: VotePollRequest,
+) -> Result<PollDto, ApiError> {
+  let (poll, room_id) = load_open_poll(state, message_id, user_id).await?;
+
+  let mut option_ids = req.option_ids;
+  option_ids.sort();
+  option_ids.dedup();
+  if !poll.multiple_choice && option_ids.len() > 1 {
+      return Err(ApiError::BadRequest("This poll allows a single choice".into()));
+  }
+
+  let options = poll_repo::list_options(&state.db, vec![poll.message_id.clone()]).await?;
+  let now = Utc::now();
+  let mut votes = Vec::with_capacity(option_ids.len());
+  for option_id in option_ids {
+      let option = options
+          .iter()
+          .find(|o| o.id == option_id.to_string())
+          .ok_or_else(|| ApiError::BadRequest("Unknown poll option".into()))?;
+      votes.push(PollVoteModel {
+          poll_id: poll.message_id.clone(),
+          user_id: user_id.to_string(),
+          slot: if poll.multiple_choice { option.position } else { 0 },
+          option_id: option.id.clone(),
+          created_at: now,
+      });
+  }
+
+  match poll_repo::replace_votes(&state.db, &poll.message_id, &user_id.to_string(), votes).await {
+      Ok(()) => {}
+      Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+          return Err(ApiError::BadRequest("Your vote changed while this one was recorded; try again".into()));
+      }
+      Err(e) => return Err(e.into()),
+  }
+
+  publish(state, &poll, room_id, user_id).await
+}
+
+pub async fn retract_vote(state: &AppState, message_id: Uuid, user_id: Uuid) -> Result<PollDto, ApiError> {
+  let (poll, room_id) = load_open_poll(state, message_id, user_id).await?;
+
+  if !poll_repo::delete_votes(&state.db, &poll.message_id, &user_id.to_string()).await? {
+      return Err(ApiError::BadRequest("You have not voted in this poll".into()));
+  }
+
+  publish(state, &poll, room_id, user_id).await
+}
+
+// Fill in `poll` for the messages that are polls; `viewer` gets their own votes marked.
+pub async fn attach_polls(state: &AppState, messages: &mut [MessageDto], viewer: Option<Uuid>) -> Result<(), ApiError> {
+  let message_ids = messages.iter().map(|m| m.id.to_string()).collect();
+  let polls = poll_repo::list_by_message_ids(&state.db, message_ids).await?;
+  if polls.is_empty() {
+      return Ok(());
+  }
+
+  let poll_ids: Vec<String> = polls.iter().map(|p| p.message_id.clone()).collect();
+  let options = poll_repo::list_options(&state.db, poll_ids.clone()).await?;
+  let votes = poll_repo::list_votes(&state.db, poll_ids).await?;
+
+  for message in messages.iter_mut() {
+      let message_id = message.id.to_string();
+      let Some(poll) = polls.iter().find(|p| p.message_id == message_id) else {
+          continue;
+      };
+      let options: Vec<PollOptionModel> = options.iter().filter(|o| o.poll_id == message_id).cloned().collect();
+      let votes: Vec<PollVoteModel> = votes.iter().filter(|v| v.poll_id == message_id).cloned().collect();
+      message.poll = Some(tally(poll, &options, &votes, viewer)?);
+  }
+  Ok(())
+}
+
+// Only members vote, and only until the poll closes.
+async fn load_open_poll(state: &AppState, message_id: Uuid, user_id: Uuid) -> Result<(PollModel, Uuid), ApiError> {
+  let poll = poll_repo::find_by_message_id(&state.db, &message_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Poll not found".into()))?;
+  let message = MessageEntity::find_by_id(poll.message_id.clone())
+      .one(&state.db)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Poll not found".into()))?;
+  let room_id = Uuid::parse_str(&message.room_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?;
+
+  chat::ensure_membership(state, room_id, user_id).await?;
+  if is_closed(&poll) {
+      return Err(ApiError::BadRequest("This poll is closed".into()));
+  }
+  Ok((poll, room_id))
+}
+
+// Broadcast the new tally to the room and return it with the voter's own picks.
+async fn publish(state: &AppState, poll: &PollModel, room_id: Uuid, user_id: Uuid) -> Result<PollDto, ApiError> {
+  let options = poll_repo::list_options(&state.db, vec![poll.message_id.clone()]).await?;
+  let votes = poll_repo::list_votes(&state.db, vec![poll.message_id.clone()]).await?;
+
+  let _ = state.chat_tx.send(WsOutboundMessage::PollUpdated(PollUpdatedDto {
+      room_id,
+      message_id: parse_id(&poll.message_id, "message")?,
+      poll: tally(poll, &options, &votes, None)?,
+  }));
+
+  tally(poll, &options, &votes, Some(user_id))
+}
+
+fn tally(
+  poll: &PollModel,
+  options: &[PollOptionModel],
+  votes: &[PollVoteModel],
+  viewer: Option<Uuid>,
+) -> Result<PollDto, ApiError> {
+  let options = options
+      .iter()
+      .map(|option| {
+        let voters = votes
+            .iter()
+            .filter(|v| v.option_id == option.id)
+            .map(|v| parse_id(&v.user_id, "user"))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PollOptionDto {
+            id: parse_id(&option.id, "option")?,
+            text: option.text.clone(),
+            votes: voters.len() as u32,
+            voters: (!poll.anonymous).then_some(voters),
+        })
+      })
+      .collect::<Result<Vec<_>, ApiError>>()?;
+
+  let total_voters = votes.iter().map(|v| v.user_id.as_str()).collect::<HashSet<_>>().len() as u32;
+  let my_votes = viewer
+      .map(|viewer| {
+        votes
+            .iter()
+            .filter(|v| v.user_id == viewer.to_string())
+            .map(|v| parse_id(&v.option_id, "option"))
+            .collect::<Result<Vec<_>, _>>()
+      })
+      .transpose()?;
+
+  Ok(PollDto {
+      options,
+      multiple_choice: poll.multiple_choice,
+      anonymous: poll.anonymous,
+      closes_at: poll.closes_at,
+      closed: is_closed(poll),
+      total_voters,
+      my_votes,
+  })
+}
+
+fn is_closed(poll: &PollModel) -> bool {
+  poll.closes_at.is_some_and(|at| at <= Utc::now())
+}
+
+fn parse_id(value: &str, label: &str) -> Result<Uuid, ApiError> {
+  Uuid::parse_str(value).map_err(|_| ApiError::InternalServerError(format!("Invalid {} id", label)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    dtos::room::RoomRole,
+    entities::{message::Model as MessageModel, room_filter_setting::Model as RoomFilterSettingModel},
+    test_support::{self, rows_affected},
+  };
+
+  fn poll(message_id: &str, multiple_choice: bool) -> PollModel {
+    PollModel {
+        message_id: message_id.to_string(),
+        multiple_choice,
+        anonymous: false,
+        closes_at: None,
+        created_at: Utc::now(),
+    }
+  }
+
+  fn option(poll_id: &str, position: i32) -> PollOptionModel {
+    PollOptionModel {
+        id: Uuid::new_v4().to_string(),
+        poll_id: poll_id.to_string(),
+        position,
+        text: format!("option {}", position),
+    }
+  }
+
+  fn vote_row(option: &PollOptionModel, user_id: Uuid, slot: i32) -> PollVoteModel {
+    PollVoteModel {
+        poll_id: option.poll_id.clone(),
+        user_id: user_id.to_string(),
+        slot,
+        option_id: option.id.clone(),
+        created_at: Utc::now(),
+    }
+  }
+
+  // A member looking at an open poll: the lookups `load_open_poll` makes.
+  fn open_poll_db(poll: &PollModel, user_id: Uuid) -> sea_orm::MockDatabase {
+    let message = test_support::message_row(&Uuid::new_v4().to_string(), Uuid::new_v4(), "Lunch?");
+    let message = MessageModel { id: poll.message_id.clone(), ..message };
+    let member = test_support::member(&message.room_id, user_id, RoomRole::Member);
+    test_support::mock_db()
+        .append_query_results([vec![poll.clone()]])
+        .append_query_results([vec![message]])
+        .append_query_results([vec![member]])
+  }
+
+  #[tokio::test]
+  async fn single_choice_polls_take_one_option() {
+    let user_id = Uuid::new_v4();
+    let poll = poll(&Uuid::new_v4().to_string(), false);
+    let state = test_support::state(open_poll_db(&poll, user_id).into_connection());
+
+    let req = VotePollRequest { option_ids: vec![Uuid::new_v4(), Uuid::new_v4()] };
+    let result = vote(&state, Uuid::parse_str(&poll.message_id).unwrap(), user_id, req).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+  }
+
+  #[tokio::test]
+  async fn unknown_options_are_rejected_before_touching_votes() {
+    let user_id = Uuid::new_v4();
+    let poll = poll(&Uuid::new_v4().to_string(), true);
+    let db = open_poll_db(&poll, user_id)
+        .append_query_results([vec![option(&poll.message_id, 0)]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let req = VotePollRequest { option_ids: vec![Uuid::new_v4()] };
+    let result = vote(&state, Uuid::parse_str(&poll.message_id).unwrap(), user_id, req).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("DELETE")));
+  }
+
+  #[tokio::test]
+  async fn closed_polls_take_no_votes() {
+    let user_id = Uuid::new_v4();
+    let poll = PollModel {
+        closes_at: Some(Utc::now() - chrono::Duration::minutes(1)),
+        ..poll(&Uuid::new_v4().to_string(), false)
+    };
+    let state = test_support::state(open_poll_db(&poll, user_id).into_connection());
+
+    let req = VotePollRequest { option_ids: vec![Uuid::new_v4()] };
+    let result = vote(&state, Uuid::parse_str(&poll.message_id).unwrap(), user_id, req).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+  }
+
+  // The (poll, user, slot) primary key is what keeps concurrent votes from stacking up:
+  // a single-choice vote always lands in slot 0, a multiple-choice one in the option's slot.
+  #[tokio::test]
+  async fn votes_are_keyed_by_slot() {
+    for (multiple_choice, expected_slot) in [(false, 0), (true, 2)] {
+      let user_id = Uuid::new_v4();
+      let poll = poll(&Uuid::new_v4().to_string(), multiple_choice);
+      let options = vec![option(&poll.message_id, 0), option(&poll.message_id, 1), option(&poll.message_id, 2)];
+      let picked = options[2].clone();
+      let db = open_poll_db(&poll, user_id)
+          .append_query_results([options.clone()])
+          .append_exec_results([rows_affected(1), rows_affected(1)])
+          .append_query_results([vec![vote_row(&picked, user_id, expected_slot)]])
+          .append_query_results([options.clone()])
+          .append_query_results([vec![vote_row(&picked, user_id, expected_slot)]])
+          .into_connection();
+      let state = test_support::state(db);
+      let mut events = state.chat_tx.subscribe();
+
+      let req = VotePollRequest { option_ids: vec![Uuid::parse_str(&picked.id).unwrap()] };
+      let dto = vote(&state, Uuid::parse_str(&poll.message_id).unwrap(), user_id, req).await.unwrap();
+      assert_eq!(dto.total_voters, 1);
+      assert_eq!(dto.my_votes, Some(vec![Uuid::parse_str(&picked.id).unwrap()]));
+      assert!(matches!(events.try_recv(), Ok(WsOutboundMessage::PollUpdated(update)) if update.poll.my_votes.is_none()));
+
+      let statements = test_support::statements(state);
+      let insert = statements.iter().find(|s| s.contains("INSERT INTO `poll_votes`")).expect("vote inserted");
+      assert!(insert.contains(&format!("Int(Some({}))", expected_slot)), "{}", insert);
+    }
+  }
+
+  #[tokio::test]
+  async fn retracting_needs_a_vote() {
+    let user_id = Uuid::new_v4();
+    let poll = poll(&Uuid::new_v4().to_string(), false);
+    let db = open_poll_db(&poll, user_id).append_exec_results([rows_affected(0)]).into_connection();
+    let state = test_support::state(db);
+
+    let result = retract_vote(&state, Uuid::parse_str(&poll.message_id).unwrap(), user_id).await;
+    assert!(matches!(result, Err(ApiError::BadRequest(_))));
+  }
+
+  #[tokio::test]
+  async fn options_must_be_distinct_and_non_empty() {
+    let room_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    for options in [vec!["Yes", " yes "], vec!["Yes", "  "]] {
+      let db = test_support::mock_db()
+          .append_query_results([vec![test_support::member(&room_id.to_string(), user_id, RoomRole::Member)]])
+          .append_query_results([Vec::<RoomFilterSettingModel>::new()])
+          .into_connection();
+      let state = test_support::state(db);
+
+      let req = CreatePollRequest {
+          question: "Lunch?".into(),
+          options: options.into_iter().map(String::from).collect(),
+          multiple_choice: false,
+          anonymous: false,
+          closes_at: None,
+      };
+      let result = create_poll(&state, room_id, user_id, req).await;
+      assert!(matches!(result, Err(ApiError::BadRequest(_))));
+      assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT")));
+    }
+  }
+
+  #[test]
+  fn tallies_count_each_voter_once_and_hide_anonymous_voters() {
+    let poll_id = Uuid::new_v4().to_string();
+    let (first, second) = (option(&poll_id, 0), option(&poll_id, 1));
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    let votes = vec![vote_row(&first, alice, 0), vote_row(&second, alice, 1), vote_row(&first, bob, 0)];
+    let options = vec![first.clone(), second.clone()];
+
+    let open = tally(&poll(&poll_id, true), &options, &votes, Some(bob)).unwrap();
+    assert_eq!(open.total_voters, 2);
+    assert_eq!(open.options[0].votes, 2);
+    assert_eq!(open.options[1].voters, Some(vec![alice]));
+    assert_eq!(open.my_votes, Some(vec![Uuid::parse_str(&first.id).unwrap()]));
+
+    let anonymous = PollModel { anonymous: true, ..poll(&poll_id, true) };
+    let hidden = tally(&anonymous, &options, &votes, None).unwrap();
+    assert!(hidden.options.iter().all(|o| o.voters.is_none()));
+    assert_eq!(hidden.options[0].votes, 2);
+    assert!(hidden.my_votes.is_none());
+  }
+}
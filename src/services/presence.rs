@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+// Tracks how many live WebSocket connections each user has open.
+#[derive(Debug, Clone)]
+pub struct Presence {
+  connections: Arc<Mutex<HashMap<Uuid, usize>>>,
+  // Users whose sockets must close right away, e.g. after a ban.
+  // In-process only, like the chat channel: with several app instances, a ban closes the sockets
+  // on the instance that handled it. Sockets elsewhere stay open until they reconnect.
+  kicks: broadcast::Sender<Uuid>,
+  // Users whose block list changed; their sockets reload it.
+  block_changes: broadcast::Sender<Uuid>,
+}
+
+impl Default for Presence {
+  fn default() -> Self {
+    let (kicks, _) = broadcast::channel(64);
+    let (block_changes, _) = broadcast::channel(64);
+    Self {
+      connections: Arc::default(),
+      kicks,
+      block_changes,
+    }
+  }
+}
+
+// Decrements the user's connection count when the socket task ends.
+pub struct PresenceGuard {
+  presence: Presence,
+  user_id: Uuid,
+}
+
+impl Presence {
+  pub fn connect(&self, user_id: Uuid) -> PresenceGuard {
+    let mut connections = self.connections.lock().expect("presence lock poisoned");
+    *connections.entry(user_id).or_insert(0) += 1;
+    PresenceGuard {
+      presence: self.clone(),
+      user_id,
+    }
+  }
+
+  pub fn is_online(&self, user_id: Uuid) -> bool {
+    let connections = self.connections.lock().expect("presence lock poisoned");
+    connections.get(&user_id).is_some_and(|count| *count > 0)
+  }
+
+  pub fn online_users(&self) -> usize {
+    self.connections.lock().expect("presence lock poisoned").len()
+  }
+
+  pub fn open_connections(&self) -> usize {
+    self.connections.lock().expect("presence lock poisoned").values().sum()
+  }
+
+  // Ask every live socket of the user to close.
+  pub fn kick(&self, user_id: Uuid) {
+    let _ = self.kicks.send(user_id);
+  }
+
+  pub fn subscribe_kicks(&self) -> broadcast::Receiver<Uuid> {
+    self.kicks.subscribe()
+  }
+
+  pub fn blocks_changed(&self, user_id: Uuid) {
+    let _ = self.block_changes.send(user_id);
+  }
+
+  pub fn subscribe_block_changes(&self) -> broadcast::Receiver<Uuid> {
+    self.block_changes.subscribe()
+  }
+
+  fn disconnect(&self, user_id: Uuid) {
+    let mut connections = self.connections.lock().expect("presence lock poisoned");
+    if let Some(count) = connections.get_mut(&user_id) {
+      *count -= 1;
+      if *count == 0 {
+        connections.remove(&user_id);
+      }
+    }
+  }
+}
+
+impl Drop for PresenceGuard {
+  fn drop(&mut self) {
+    self.presence.disconnect(self.user_id);
+  }
+}
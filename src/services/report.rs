@@ -0,0 +1,307 @@
+use chrono::Utc;
+use sea_orm::EntityTrait;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::{
+    chat::MessageFormat,
+    report::{
+      CreateReportRequest, ListReportsQuery, ReportAction, ReportResponse, ReportStatus, ReportedMessageDto,
+      ResolveReportRequest,
+    },
+    room::RoomSanctionRequest,
+  },
+  entities::{message::Entity as MessageEntity, message_report::Model as MessageReportModel},
+  repositories::message_report as report_repo,
+  response::ApiError,
+  services::{admin, chat, moderation},
+};
+
+// File a report against a message, keeping a copy of it as it reads right now.
+pub async fn create_report(
+  state: &AppState,
+  message_id: Uuid,
+  reporter_id: Uuid,
+  req: CreateReportRequest,
+) -> Result<ReportResponse, ApiError> {
+  let message = MessageEntity::find_by_id(message_id.to_string())
+      .one(&state.db)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Message not found".into()))?;
+  let room_id = Uuid::parse_str(&message.room_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?;
+  chat::ensure_membership(state, room_id, reporter_id).await?;
+
+  if message.sender_id == reporter_id.to_string() {
+      return Err(ApiError::BadRequest("You cannot report your own message".into()));
+  }
+  if report_repo::find_by_message_and_reporter(&state.db, &message.id, &reporter_id.to_string())
+      .await?
+      .is_some()
+  {
+      return Err(ApiError::BadRequest("You have already reported this message".into()));
+  }
+
+  let report = report_repo::insert(
+      &state.db,
+      MessageReportModel {
+          id: Uuid::new_v4().to_string(),
+          room_id: message.room_id,
+          message_id: Some(message.id),
+          reporter_id: Some(reporter_id.to_string()),
+          author_id: Some(message.sender_id),
+          reason: req.reason.trim().to_string(),
+          content_snapshot: message.content,
+          format_snapshot: message.format,
+          message_created_at: message.created_at,
+          status: ReportStatus::Open.as_str().to_string(),
+          resolution: None,
+          resolved_by: None,
+          resolved_at: None,
+          created_at: Utc::now(),
+      },
+  )
+  .await?;
+
+  to_response(report)
+}
+
+// Review queue of one room, for its moderators.
+pub async fn list_room_reports(
+  state: &AppState,
+  room_id: Uuid,
+  user_id: Uuid,
+  params: ListReportsQuery,
+) -> Result<Vec<ReportResponse>, ApiError> {
+  moderation::ensure_moderator(state, room_id, user_id).await?;
+  list(state, Some(room_id), params).await
+}
+
+// Review queue across every room, for global admins.
+pub async fn list_all_reports(state: &AppState, params: ListReportsQuery) -> Result<Vec<ReportResponse>, ApiError> {
+  list(state, None, params).await
+}
+
+pub async fn resolve_report(
+  state: &AppState,
+  report_id: Uuid,
+  moderator_id: Uuid,
+  req: ResolveReportRequest,
+) -> Result<ReportResponse, ApiError> {
+  let report = report_repo::find_by_id(&state.db, &report_id.to_string())
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Report not found".into()))?;
+  let room_id = Uuid::parse_str(&report.room_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?;
+  moderation::ensure_moderator(state, room_id, moderator_id).await?;
+
+  if ReportStatus::parse(&report.status) != Some(ReportStatus::Open) {
+      return Err(ApiError::BadRequest("This report has already been resolved".into()));
+  }
+
+  // Every open report on the same message is settled by one decision.
+  let mut ids = vec![report.id.clone()];
+  if let Some(message_id) = &report.message_id {
+      for other in report_repo::list_open_for_message(&state.db, message_id, ReportStatus::Open.as_str()).await? {
+          if other.id != report.id {
+              ids.push(other.id);
+          }
+      }
+  }
+
+  match req.action {
+    ReportAction::Dismiss => {}
+    ReportAction::DeleteMessage => {
+        let message_id = report
+            .message_id
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| ApiError::BadRequest("The message has already been deleted".into()))?;
+        admin::delete_message(state, moderator_id, message_id).await?;
+    }
+    ReportAction::MuteAuthor | ReportAction::BanAuthor => {
+        let author_id = report
+            .author_id
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| ApiError::BadRequest("The author no longer exists".into()))?;
+        let sanction = RoomSanctionRequest {
+            user_id: author_id,
+            duration_seconds: req.duration_seconds,
+            reason: req.reason.clone().or_else(|| Some(report.reason.chars().take(255).collect())),
+        };
+        if req.action == ReportAction::MuteAuthor {
+            moderation::mute_member(state, room_id, moderator_id, sanction).await?;
+        } else {
+            moderation::ban_member(state, room_id, moderator_id, sanction).await?;
+        }
+    }
+  }
+
+  let status = match req.action {
+      ReportAction::Dismiss => ReportStatus::Dismissed,
+      _ => ReportStatus::Actioned,
+  };
+  let count = ids.len();
+  report_repo::resolve(&state.db, ids, status.as_str(), req.action.as_str(), &moderator_id.to_string()).await?;
+
+  admin::record(
+      state,
+      Some(moderator_id),
+      "report.resolved",
+      "report",
+      Some(report.id.clone()),
+      json!({ "action": req.action.as_str(), "room_id": report.room_id, "reports": count }),
+  )
+  .await;
+
+  let report = report_repo::find_by_id(&state.db, &report.id)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Report not found".into()))?;
+  to_response(report)
+}
+
+async fn list(state: &AppState, room_id: Option<Uuid>, params: ListReportsQuery) -> Result<Vec<ReportResponse>, ApiError> {
+  let status = params.status.unwrap_or(ReportStatus::Open);
+  let reports = report_repo::list(
+      &state.db,
+      room_id.map(|id| id.to_string()).as_deref(),
+      Some(status.as_str()),
+      params.limit.unwrap_or(50).min(200),
+  )
+  .await?;
+
+  reports.into_iter().map(to_response).collect()
+}
+
+fn to_response(report: MessageReportModel) -> Result<ReportResponse, ApiError> {
+  Ok(ReportResponse {
+      id: Uuid::parse_str(&report.id)
+          .map_err(|_| ApiError::InternalServerError("Invalid report id".into()))?,
+      room_id: Uuid::parse_str(&report.room_id)
+          .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
+      reporter_id: report.reporter_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+      reason: report.reason,
+      message: ReportedMessageDto {
+          id: report.message_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+          author_id: report.author_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+          content: report.content_snapshot,
+          format: MessageFormat::parse(&report.format_snapshot).unwrap_or_default(),
+          created_at: report.message_created_at,
+      },
+      status: ReportStatus::parse(&report.status).unwrap_or(ReportStatus::Open),
+      resolution: report.resolution.as_deref().and_then(ReportAction::parse),
+      resolved_by: report.resolved_by.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+      resolved_at: report.resolved_at,
+      created_at: report.created_at,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    dtos::{admin::UserRole, room::RoomRole},
+    entities::{audit_log::Model as AuditLogModel, message::Model as MessageModel},
+    test_support::{self, rows_affected},
+  };
+
+  fn message(sender_id: Uuid) -> MessageModel {
+    MessageModel {
+      id: Uuid::new_v4().to_string(),
+      room_id: Uuid::new_v4().to_string(),
+      sender_id: sender_id.to_string(),
+      content: "buy cheap watches".into(),
+      format: "plain".into(),
+      rendered_html: None,
+      thread_id: None,
+      created_at: Utc::now(),
+    }
+  }
+
+  fn report(message: &MessageModel, reporter_id: Option<Uuid>) -> MessageReportModel {
+    MessageReportModel {
+      id: Uuid::new_v4().to_string(),
+      room_id: message.room_id.clone(),
+      message_id: Some(message.id.clone()),
+      reporter_id: reporter_id.map(|id| id.to_string()),
+      author_id: Some(message.sender_id.clone()),
+      reason: "spam".into(),
+      content_snapshot: message.content.clone(),
+      format_snapshot: message.format.clone(),
+      message_created_at: message.created_at,
+      status: ReportStatus::Open.as_str().into(),
+      resolution: None,
+      resolved_by: None,
+      resolved_at: None,
+      created_at: Utc::now(),
+    }
+  }
+
+  #[tokio::test]
+  async fn refuses_own_messages_and_second_reports() {
+    let reporter_id = Uuid::new_v4();
+    let own = message(reporter_id);
+    let db = test_support::mock_db()
+        .append_query_results([vec![own.clone()]])
+        .append_query_results([vec![test_support::member(&own.room_id, reporter_id, RoomRole::Member)]])
+        .into_connection();
+    let state = test_support::state(db);
+    let req = CreateReportRequest { reason: "spam".into() };
+    let message_id = Uuid::parse_str(&own.id).unwrap();
+    assert!(matches!(create_report(&state, message_id, reporter_id, req).await, Err(ApiError::BadRequest(_))));
+
+    let other = message(Uuid::new_v4());
+    let db = test_support::mock_db()
+        .append_query_results([vec![other.clone()]])
+        .append_query_results([vec![test_support::member(&other.room_id, reporter_id, RoomRole::Member)]])
+        .append_query_results([vec![report(&other, Some(reporter_id))]])
+        .into_connection();
+    let state = test_support::state(db);
+    let req = CreateReportRequest { reason: "spam".into() };
+    let message_id = Uuid::parse_str(&other.id).unwrap();
+    assert!(matches!(create_report(&state, message_id, reporter_id, req).await, Err(ApiError::BadRequest(_))));
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT")));
+  }
+
+  #[test]
+  fn reports_outlive_their_reporter() {
+    let report = report(&message(Uuid::new_v4()), None);
+    assert_eq!(to_response(report).unwrap().reporter_id, None);
+  }
+
+  #[tokio::test]
+  async fn one_decision_settles_every_open_report_on_the_message() {
+    let admin_id = Uuid::new_v4();
+    let reported = message(Uuid::new_v4());
+    let first = report(&reported, Some(Uuid::new_v4()));
+    let second = report(&reported, None);
+    let audit = AuditLogModel {
+      id: Uuid::new_v4().to_string(),
+      actor_id: Some(admin_id.to_string()),
+      action: "report.resolved".into(),
+      target_type: "report".into(),
+      target_id: Some(first.id.clone()),
+      details: None,
+      created_at: Utc::now(),
+    };
+    // The room is gone; only a global admin can still act on the report.
+    let db = test_support::mock_db()
+        .append_query_results([vec![first.clone()]])
+        .append_query_results([vec![test_support::user(admin_id, UserRole::Admin)]])
+        .append_query_results([vec![first.clone(), second.clone()]])
+        .append_query_results([vec![audit]])
+        .append_query_results([vec![first.clone()]])
+        .append_exec_results([rows_affected(2), rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let req = ResolveReportRequest { action: ReportAction::Dismiss, duration_seconds: None, reason: None };
+    resolve_report(&state, Uuid::parse_str(&first.id).unwrap(), admin_id, req).await.unwrap();
+    let statements = test_support::statements(state);
+    let update = statements.iter().find(|s| s.contains("UPDATE `message_reports`")).unwrap();
+    assert!(update.contains(&first.id) && update.contains(&second.id), "{}", update);
+  }
+}
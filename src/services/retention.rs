@@ -0,0 +1,372 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+  sea_query::{Query, SelectStatement}, ColumnTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+};
+use serde_json::json;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::{
+  database::{AppState, SharedState},
+  dtos::{
+    chat::WsOutboundMessage,
+    retention::{
+      GlobalRetentionResponse, RetentionPolicy, RetentionPreviewQuery, RetentionPreviewResponse, RoomRetentionPreview,
+      UpdateLegalHoldRequest,
+    },
+    room::RoomResponse,
+  },
+  entities::{
+    message::{Column as MessageColumn, Entity as MessageEntity},
+    room::Model as RoomModel,
+    room_pin::{Column as RoomPinColumn, Entity as RoomPinEntity},
+  },
+  repositories::{link_preview as link_preview_repo, room as room_repo, server_setting as setting_repo},
+  response::ApiError,
+  services::{admin, moderation, room},
+};
+
+const SETTING_NAME: &str = "retention";
+const PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+const BATCH_SIZE: u64 = 500;
+// Bounds one room's share of a run; the rest waits for the next one.
+const MAX_BATCHES_PER_ROOM: u32 = 20;
+const BATCH_PAUSE: Duration = Duration::from_millis(200);
+
+pub async fn get_global_policy(state: &AppState) -> Result<GlobalRetentionResponse, ApiError> {
+  let setting = setting_repo::find(&state.db, SETTING_NAME).await?;
+  Ok(match setting {
+      Some(setting) => GlobalRetentionResponse {
+          policy: serde_json::from_str(&setting.value)
+              .map_err(|e| ApiError::InternalServerError(format!("Invalid retention settings: {}", e)))?,
+          updated_at: Some(setting.updated_at),
+      },
+      None => GlobalRetentionResponse { policy: RetentionPolicy::default(), updated_at: None },
+  })
+}
+
+pub async fn update_global_policy(
+  state: &AppState,
+  admin_id: Uuid,
+  policy: RetentionPolicy,
+) -> Result<GlobalRetentionResponse, ApiError> {
+  let value = serde_json::to_string(&policy).map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+  let setting = setting_repo::upsert(&state.db, SETTING_NAME, value, &admin_id.to_string()).await?;
+
+  admin::record(state, Some(admin_id), "retention.updated", "server", None, json!(policy)).await;
+  Ok(GlobalRetentionResponse { policy, updated_at: Some(setting.updated_at) })
+}
+
+// Room owners choose how long their room keeps history.
+pub async fn update_room_policy(
+  state: &AppState,
+  room_id: Uuid,
+  user_id: Uuid,
+  policy: RetentionPolicy,
+) -> Result<RoomResponse, ApiError> {
+  moderation::ensure_owner(state, room_id, user_id).await?;
+
+  let current = room_repo::find_by_id(&state.db, &room_id.to_string()).await?;
+  let updated = room_repo::update_retention(
+      &state.db,
+      current,
+      policy.max_age_days.map(|days| days as i32),
+      policy.max_messages.map(|count| count as i32),
+  )
+  .await?;
+  let response = room::to_response(updated)?;
+  let _ = state.chat_tx.send(WsOutboundMessage::RoomUpdated(response.clone()));
+
+  Ok(response)
+}
+
+pub async fn update_legal_hold(
+  state: &AppState,
+  admin_id: Uuid,
+  room_id: Uuid,
+  req: UpdateLegalHoldRequest,
+) -> Result<RoomResponse, ApiError> {
+  let current = room_repo::find_by_id(&state.db, &room_id.to_string())
+      .await
+      .map_err(|_| ApiError::NotFound("Room not found".into()))?;
+  let updated = room_repo::update_legal_hold(&state.db, current, req.enabled).await?;
+
+  let action = if req.enabled { "room.legal_hold_placed" } else { "room.legal_hold_released" };
+  admin::record(state, Some(admin_id), action, "room", Some(updated.id.clone()), json!({ "reason": req.reason })).await;
+
+  let response = room::to_response(updated)?;
+  let _ = state.chat_tx.send(WsOutboundMessage::RoomUpdated(response.clone()));
+  Ok(response)
+}
+
+// Dry run of the pruner: what the next run would delete, per room.
+pub async fn preview(state: &AppState, params: RetentionPreviewQuery) -> Result<RetentionPreviewResponse, ApiError> {
+  let global = get_global_policy(state).await?.policy;
+  let rooms = match params.room_id {
+      Some(room_id) => vec![room_repo::find_by_id(&state.db, &room_id.to_string())
+          .await
+          .map_err(|_| ApiError::NotFound("Room not found".into()))?],
+      None => room_repo::list_all(&state.db).await?,
+  };
+
+  let now = Utc::now();
+  let mut result = Vec::new();
+  let mut total = 0;
+  for room in rooms {
+      let policy = room_policy(&room).or(global);
+      let cutoff = if room.legal_hold { None } else { cutoff(state, &room.id, &policy, now).await? };
+      let would_delete = match cutoff {
+          Some(cutoff) => prunable(&room.id, cutoff).count(&state.db).await?,
+          None => 0,
+      };
+      if would_delete == 0 && params.room_id.is_none() && !room.legal_hold {
+          continue;
+      }
+
+      total += would_delete;
+      result.push(RoomRetentionPreview {
+          room_id: Uuid::parse_str(&room.id)
+              .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
+          name: room.name,
+          legal_hold: room.legal_hold,
+          policy,
+          cutoff,
+          would_delete,
+      });
+  }
+
+  Ok(RetentionPreviewResponse { global, rooms: result, total })
+}
+
+// Background task deleting messages that fell out of their room's retention window.
+pub fn spawn_pruner(state: SharedState) -> tokio::task::JoinHandle<()> {
+  tokio::spawn(async move {
+    let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = prune_all(state.as_ref()).await {
+            tracing::error!(error = %e, "Retention pruning failed");
+        }
+    }
+  }
+  .instrument(tracing::info_span!("retention_pruner")))
+}
+
+async fn prune_all(state: &AppState) -> Result<(), ApiError> {
+  let global = get_global_policy(state).await?.policy;
+  let now = Utc::now();
+
+  for room in room_repo::list_all(&state.db).await? {
+      let policy = room_policy(&room).or(global);
+      if room.legal_hold || policy.is_unlimited() {
+          continue;
+      }
+      let Some(cutoff) = cutoff(state, &room.id, &policy, now).await? else {
+          continue;
+      };
+
+      let deleted = prune_room(state, &room.id, cutoff).await?;
+      if deleted > 0 {
+          admin::record(
+              state,
+              None,
+              "retention.pruned",
+              "room",
+              Some(room.id),
+              json!({ "deleted": deleted, "cutoff": cutoff }),
+          )
+          .await;
+      }
+  }
+
+  // Messages carry no attachments: there is no attachment storage in this app, so nothing else
+  // needs cleaning up. Link previews are the only file-like data hanging off messages; drop the
+  // ones nothing uses now.
+  link_preview_repo::delete_orphaned(&state.db, now - chrono::Duration::hours(1)).await?;
+  Ok(())
+}
+
+// Delete in small batches so no single statement holds locks on a busy room for long.
+// Polls, saved entries, preview links and notifications go with the message through their foreign keys.
+async fn prune_room(state: &AppState, room_id: &str, cutoff: DateTime<Utc>) -> Result<u64, ApiError> {
+  let mut deleted = 0;
+
+  for _ in 0..MAX_BATCHES_PER_ROOM {
+      // A legal hold placed (or the room deleted) mid-run stops the room immediately.
+      match room_repo::find_by_id(&state.db, room_id).await {
+          Ok(room) if !room.legal_hold => {}
+          Ok(_) | Err(DbErr::RecordNotFound(_)) => break,
+          Err(e) => return Err(e.into()),
+      }
+
+      let ids: Vec<String> = prunable(room_id, cutoff)
+          .select_only()
+          .column(MessageColumn::Id)
+          .order_by_asc(MessageColumn::CreatedAt)
+          .limit(BATCH_SIZE)
+          .into_tuple()
+          .all(&state.db)
+          .await?;
+      if ids.is_empty() {
+          break;
+      }
+
+      let batch = ids.len() as u64;
+      // The pin check is repeated here so a message pinned since the select survives.
+      let result = MessageEntity::delete_many()
+          .filter(MessageColumn::Id.is_in(ids))
+          .filter(MessageColumn::Id.not_in_subquery(pinned_ids(room_id)))
+          .exec(&state.db)
+          .await?;
+      deleted += result.rows_affected;
+
+      if batch < BATCH_SIZE {
+          break;
+      }
+      tokio::time::sleep(BATCH_PAUSE).await;
+  }
+
+  Ok(deleted)
+}
+
+// Unpinned messages of the room created before `cutoff`.
+fn prunable(room_id: &str, cutoff: DateTime<Utc>) -> Select<MessageEntity> {
+  MessageEntity::find()
+      .filter(MessageColumn::RoomId.eq(room_id))
+      .filter(MessageColumn::CreatedAt.lt(cutoff))
+      .filter(MessageColumn::Id.not_in_subquery(pinned_ids(room_id)))
+}
+
+fn pinned_ids(room_id: &str) -> SelectStatement {
+  Query::select()
+      .column(RoomPinColumn::MessageId)
+      .from(RoomPinEntity)
+      .and_where(RoomPinColumn::RoomId.eq(room_id))
+      .to_owned()
+}
+
+// The later of the age limit and the creation time of the oldest message the count limit keeps.
+async fn cutoff(
+  state: &AppState,
+  room_id: &str,
+  policy: &RetentionPolicy,
+  now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, ApiError> {
+  let by_age = policy.max_age_days.map(|days| now - chrono::Duration::days(i64::from(days)));
+
+  let by_count = match policy.max_messages {
+      Some(max) => MessageEntity::find()
+          .select_only()
+          .column(MessageColumn::CreatedAt)
+          .filter(MessageColumn::RoomId.eq(room_id))
+          .order_by_desc(MessageColumn::CreatedAt)
+          .offset(u64::from(max.saturating_sub(1)))
+          .limit(1)
+          .into_tuple::<DateTime<Utc>>()
+          .one(&state.db)
+          .await?,
+      None => None,
+  };
+
+  Ok(by_age.max(by_count))
+}
+
+fn room_policy(room: &RoomModel) -> RetentionPolicy {
+  RetentionPolicy {
+      max_age_days: room.retention_max_age_days.map(|days| days.max(1) as u32),
+      max_messages: room.retention_max_messages.map(|count| count.max(1) as u32),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use sea_orm::{QueryTrait, Value};
+
+  use super::*;
+  use crate::{
+    entities::server_setting::Model as ServerSettingModel,
+    test_support::{self, rows_affected},
+  };
+
+  fn id_row(id: &str) -> BTreeMap<String, Value> {
+    BTreeMap::from([("id".to_string(), Value::from(id.to_string()))])
+  }
+
+  #[test]
+  fn pinned_messages_are_never_prunable() {
+    let sql = prunable("room-1", Utc::now()).build(sea_orm::DatabaseBackend::MySql).to_string();
+    assert!(sql.contains("NOT IN (SELECT `message_id` FROM `room_pins` WHERE `room_pins`.`room_id` = 'room-1')"), "{}", sql);
+  }
+
+  #[test]
+  fn room_settings_win_over_the_server_policy() {
+    let room = RoomModel { retention_max_messages: Some(100), ..test_support::room(false) };
+    let global = RetentionPolicy { max_age_days: Some(30), max_messages: Some(1000) };
+    assert_eq!(room_policy(&room).or(global), RetentionPolicy { max_age_days: Some(30), max_messages: Some(100) });
+    assert!(room_policy(&test_support::room(false)).is_unlimited());
+  }
+
+  #[tokio::test]
+  async fn the_later_of_both_limits_is_the_cutoff() {
+    let now = Utc::now();
+    let kept_since = now - chrono::Duration::days(2);
+    let db = test_support::mock_db()
+        .append_query_results([[BTreeMap::from([("created_at".to_string(), Value::from(kept_since))])]])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let policy = RetentionPolicy { max_age_days: Some(30), max_messages: Some(10) };
+    assert_eq!(cutoff(&state, "room-1", &policy, now).await.unwrap(), Some(kept_since));
+
+    // An age limit alone needs no lookup.
+    let policy = RetentionPolicy { max_age_days: Some(30), max_messages: None };
+    assert_eq!(cutoff(&state, "room-1", &policy, now).await.unwrap(), Some(now - chrono::Duration::days(30)));
+  }
+
+  #[tokio::test]
+  async fn a_short_batch_ends_the_room() {
+    let room = test_support::room(false);
+    let db = test_support::mock_db()
+        .append_query_results([vec![room.clone()]])
+        .append_query_results([vec![id_row("m1"), id_row("m2")]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    assert_eq!(prune_room(&state, &room.id, Utc::now()).await.unwrap(), 1);
+    let statements = test_support::statements(state);
+    let delete = statements.iter().find(|s| s.contains("DELETE")).expect("batch deleted");
+    assert!(delete.contains("`room_pins`"), "{}", delete);
+  }
+
+  #[tokio::test]
+  async fn a_legal_hold_placed_mid_run_stops_the_room() {
+    let room = RoomModel { legal_hold: true, ..test_support::room(false) };
+    let db = test_support::mock_db().append_query_results([vec![room.clone()]]).into_connection();
+    let state = test_support::state(db);
+
+    assert_eq!(prune_room(&state, &room.id, Utc::now()).await.unwrap(), 0);
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("DELETE")));
+  }
+
+  #[tokio::test]
+  async fn held_and_unlimited_rooms_are_skipped() {
+    let held = RoomModel { legal_hold: true, retention_max_age_days: Some(1), ..test_support::room(false) };
+    let unlimited = test_support::room(false);
+    let db = test_support::mock_db()
+        .append_query_results([Vec::<ServerSettingModel>::new()])
+        .append_query_results([vec![held, unlimited]])
+        .append_exec_results([rows_affected(0)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    prune_all(&state).await.unwrap();
+    let statements = test_support::statements(state);
+    assert_eq!(statements.len(), 3);
+    assert!(statements[2].contains("DELETE FROM `link_previews`"), "{}", statements[2]);
+  }
+}
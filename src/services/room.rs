@@ -3,12 +3,17 @@ use sea_orm::EntityTrait;
 
 use crate::{
     database::AppState,
-    dtos::room::{AddMemberRequest, CreateRoomRequest, RoomDetailResponse, RoomResponse, UserInfo},
-    entities::user::Entity as UserEntity,
+    dtos::{
+        chat::{RoomDeletedDto, WsOutboundMessage},
+        retention::RoomRetentionDto,
+        room::{AddMemberRequest, CreateRoomRequest, RoomDetailResponse, RoomMemberInfo, RoomResponse, RoomRole, UserInfo},
+    },
+    entities::{room::Model as RoomModel, user::Entity as UserEntity},
     repositories::{
-        room as room_repo, room_member as member_repo, user as user_repo,
+        room as room_repo, room_member as member_repo,
     },
     response::ApiError,
+    services::{account, auth, moderation, user},
 };
 
 pub async fn create_room(
@@ -16,31 +21,29 @@ pub async fn create_room(
   creator_id: Uuid,
   req: CreateRoomRequest,
 ) -> Result<RoomResponse, ApiError> {
-  let room = crate::entities::room::Model {
+  auth::ensure_verified(state, creator_id).await?;
+
+  let room = RoomModel {
     id: Uuid::new_v4().to_string(),
     name: req.name,
+    topic: None,
+    slow_mode_seconds: 0,
+    retention_max_age_days: None,
+    retention_max_messages: None,
+    legal_hold: false,
+    is_direct: req.is_direct,
     created_at: chrono::Utc::now(),
 };
   let room = room_repo::insert(&state.db, room).await?;
-  member_repo::insert(&state.db, room.id.clone(), creator_id.to_string()).await?;
+  member_repo::insert(&state.db, room.id.clone(), creator_id.to_string(), RoomRole::Owner.as_str()).await?;
 
-  Ok(RoomResponse {
-      id: Uuid::parse_str(&room.id)
-          .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
-      name: room.name,
-      created_at: room.created_at,
-  })
+  to_response(room)
 }
 
 pub async fn get_room(state: &AppState, room_id: Uuid) -> Result<RoomResponse, ApiError> {
   let room = room_repo::find_by_id(&state.db, &room_id.to_string()).await?;
 
-  Ok(RoomResponse {
-      id: Uuid::parse_str(&room.id)
-          .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
-      name: room.name,
-      created_at: room.created_at,
-  })
+  to_response(room)
 }
 
 pub async fn get_room_detail(
@@ -48,7 +51,7 @@ pub async fn get_room_detail(
   room_id: Uuid,
   user_id: Uuid,
 ) -> Result<RoomDetailResponse, ApiError> {
-  let member = member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &user_id.to_string())
+  member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &user_id.to_string())
       .await?;
 
   let room = room_repo::find_by_id(&state.db, &room_id.to_string()).await?;
@@ -61,11 +64,14 @@ pub async fn get_room_detail(
             .await?
             .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
 
-        user_infos.push(UserInfo {
-            id: Uuid::parse_str(&user.id)
-                .map_err(|_| ApiError::InternalServerError("Invalid user id".into()))?,
-            username: user.username,
-            email: user.email,
+        user_infos.push(RoomMemberInfo {
+            user: UserInfo {
+                id: Uuid::parse_str(&user.id)
+                    .map_err(|_| ApiError::InternalServerError("Invalid user id".into()))?,
+                username: user.username,
+                email: user.email,
+            },
+            role: RoomRole::parse(&member.role).unwrap_or(RoomRole::Member),
         });
     }
 
@@ -73,6 +79,7 @@ pub async fn get_room_detail(
         id: Uuid::parse_str(&room.id)
             .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
         name: room.name,
+        topic: room.topic,
         created_at: room.created_at,
         members: user_infos,
     })
@@ -86,12 +93,7 @@ pub async fn list_rooms(state: &AppState, user_id: Uuid) -> Result<Vec<RoomRespo
   let mut rooms = Vec::new();
   for member in user_members {
       let room = room_repo::find_by_id(&state.db, &member.room_id).await?;
-      rooms.push(RoomResponse {
-          id: Uuid::parse_str(&room.id)
-              .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
-          name: room.name,
-          created_at: room.created_at,
-      });
+      rooms.push(to_response(room)?);
   }
 
   Ok(rooms)
@@ -102,11 +104,16 @@ pub async fn delete_room(
   room_id: Uuid,
   user_id: Uuid,
 ) -> Result<(), ApiError> {
-  // Kiểm tra user có trong room không (có thể thêm logic: chỉ creator mới được xóa)
-  let member = member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &user_id.to_string())
-      .await?;
+  // Only the room owner, or a global admin, may delete a room.
+  moderation::ensure_owner(state, room_id, user_id).await?;
 
+  remove_room(state, room_id).await
+}
+
+// Shared with the admin API: deletes the room and tells live sockets, which close on the event.
+pub(crate) async fn remove_room(state: &AppState, room_id: Uuid) -> Result<(), ApiError> {
   room_repo::delete(&state.db, &room_id.to_string()).await?;
+  let _ = state.chat_tx.send(WsOutboundMessage::RoomDeleted(RoomDeletedDto { room_id }));
   Ok(())
 }
 
@@ -116,8 +123,10 @@ pub async fn add_member(
   requester_id: Uuid,
   req: AddMemberRequest,
 ) -> Result<(), ApiError> {
+  auth::ensure_verified(state, requester_id).await?;
+
   // Kiểm tra requester có trong room không
-  let requester_member = member_repo::find_by_room_and_user(
+  member_repo::find_by_room_and_user(
       &state.db,
       &room_id.to_string(),
       &requester_id.to_string(),
@@ -129,23 +138,26 @@ pub async fn add_member(
   // }
 
   // let _user = user_repo::find_by_email(&state.db, "").await?; 
-  let user = UserEntity::find_by_id(&req.user_id.to_string())
+  UserEntity::find_by_id(req.user_id.to_string())
       .one(&state.db)
       .await?
       .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
   
-  let existing = member_repo::find_by_room_and_user(
-      &state.db,
-      &room_id.to_string(),
-      &req.user_id.to_string(),
-  )
-  .await;
-
   // if existing.is_some() {
   //     return Err(ApiError::BadRequest("User is already a member of this room".into()));
   // }
 
-  member_repo::insert(&state.db, room_id.to_string(), req.user_id.to_string()).await?;
+  let room = room_repo::find_by_id(&state.db, &room_id.to_string()).await?;
+  if room.is_direct && member_repo::list_by_room(&state.db, &room.id).await?.len() >= 2 {
+      return Err(ApiError::BadRequest("A direct conversation has room for two people only".into()));
+  }
+
+  moderation::ensure_not_banned(state, room_id, req.user_id).await?;
+  if user::has_blocked(state, req.user_id, requester_id).await? {
+      return Err(ApiError::Forbidden("This user is not accepting invitations from you".into()));
+  }
+
+  member_repo::insert(&state.db, room_id.to_string(), req.user_id.to_string(), RoomRole::Member.as_str()).await?;
   Ok(())
 }
 
@@ -155,19 +167,147 @@ pub async fn remove_member(
   requester_id: Uuid,
   target_user_id: Uuid,
 ) -> Result<(), ApiError> {
-  // Kiểm tra requester có trong room không
-  let requester_member = member_repo::find_by_room_and_user(
-      &state.db,
-      &room_id.to_string(),
-      &requester_id.to_string(),
-  )
-  .await?;
-
   // Không cho phép tự xóa chính mình (hoặc có thể cho phép)
   if requester_id == target_user_id {
       return Err(ApiError::BadRequest("You cannot remove yourself".into()));
   }
 
-  member_repo::delete(&state.db, &room_id.to_string(), &target_user_id.to_string()).await?;
+  // Removing someone else is a kick and needs a higher room role.
+  moderation::kick_member(state, room_id, requester_id, target_user_id).await
+}
+
+// An owner hands the room over before leaving, so it is never left without one.
+pub async fn leave_room(state: &AppState, room_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  let member = member_repo::find_by_room_and_user(&state.db, &room_id.to_string(), &user_id.to_string())
+      .await
+      .map_err(|_| ApiError::NotFound("You are not a member of this room".into()))?;
+  if RoomRole::parse(&member.role) == Some(RoomRole::Owner) {
+      account::hand_off_ownership(state, room_id, &member.user_id, "The owner left this room; you are the owner now").await?;
+  }
+
+  member_repo::delete(&state.db, &member.room_id, &member.user_id).await?;
   Ok(())
-}
\ No newline at end of file
+}
+
+// Convert a DB model into the API response, parsing the string id.
+pub fn to_response(room: RoomModel) -> Result<RoomResponse, ApiError> {
+  Ok(RoomResponse {
+      id: Uuid::parse_str(&room.id)
+          .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
+      name: room.name,
+      topic: room.topic,
+      slow_mode_seconds: room.slow_mode_seconds.max(0) as u32,
+      retention: RoomRetentionDto {
+          max_age_days: room.retention_max_age_days.map(|days| days.max(1) as u32),
+          max_messages: room.retention_max_messages.map(|count| count.max(1) as u32),
+          legal_hold: room.legal_hold,
+      },
+      is_direct: room.is_direct,
+      created_at: room.created_at,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    dtos::admin::UserRole,
+    entities::{audit_log::Model as AuditLogModel, room_member::Model as RoomMemberModel},
+    test_support::{self, rows_affected},
+  };
+
+  fn deleting(role: UserRole, member: Option<RoomRole>) -> (AppState, Uuid, Uuid) {
+    let room = test_support::room(false);
+    let room_id = Uuid::parse_str(&room.id).unwrap();
+    let user_id = Uuid::new_v4();
+    let mut db = test_support::mock_db().append_query_results([vec![test_support::user(user_id, role)]]);
+    if role != UserRole::Admin {
+        let member = member.map(|role| test_support::member(&room.id, user_id, role));
+        db = db.append_query_results([member.into_iter().collect::<Vec<_>>()]);
+    }
+    let db = db
+        .append_query_results([vec![room]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    (test_support::state(db), room_id, user_id)
+  }
+
+  #[tokio::test]
+  async fn only_owners_and_admins_delete_rooms() {
+    for (role, member) in [(UserRole::User, Some(RoomRole::Member)), (UserRole::User, Some(RoomRole::Moderator)), (UserRole::User, None)] {
+        let (state, room_id, user_id) = deleting(role, member);
+        assert!(matches!(delete_room(&state, room_id, user_id).await, Err(ApiError::Forbidden(_))));
+        assert!(!test_support::statements(state).iter().any(|s| s.contains("DELETE")));
+    }
+    for (role, member) in [(UserRole::User, Some(RoomRole::Owner)), (UserRole::Admin, None)] {
+        let (state, room_id, user_id) = deleting(role, member);
+        delete_room(&state, room_id, user_id).await.unwrap();
+        assert!(test_support::statements(state).iter().any(|s| s.contains("DELETE FROM `rooms`")));
+    }
+  }
+
+  #[tokio::test]
+  async fn deleting_a_room_tells_its_sockets() {
+    let (state, room_id, user_id) = deleting(UserRole::User, Some(RoomRole::Owner));
+    let mut events = state.chat_tx.subscribe();
+
+    delete_room(&state, room_id, user_id).await.unwrap();
+
+    assert!(matches!(events.try_recv(), Ok(WsOutboundMessage::RoomDeleted(event)) if event.room_id == room_id));
+  }
+
+  #[tokio::test]
+  async fn a_leaving_owner_hands_the_room_to_the_longest_serving_moderator() {
+    let room = test_support::room(false);
+    let room_id = Uuid::parse_str(&room.id).unwrap();
+    let (owner_id, moderator_id, member_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let owner = test_support::member(&room.id, owner_id, RoomRole::Owner);
+    let moderator = test_support::member(&room.id, moderator_id, RoomRole::Moderator);
+    let mut veteran = test_support::member(&room.id, member_id, RoomRole::Member);
+    veteran.joined_at = moderator.joined_at - chrono::Duration::days(30);
+    let promoted = RoomMemberModel { role: RoomRole::Owner.as_str().into(), ..moderator.clone() };
+    let audit = AuditLogModel {
+      id: Uuid::new_v4().to_string(),
+      actor_id: None,
+      action: "room.owner_handed_off".into(),
+      target_type: "room".into(),
+      target_id: Some(room.id.clone()),
+      details: None,
+      created_at: chrono::Utc::now(),
+    };
+    let db = test_support::mock_db()
+        .append_query_results([vec![owner.clone()], vec![owner.clone(), moderator, veteran]])
+        .append_query_results([vec![
+            test_support::user(moderator_id, UserRole::User),
+            test_support::user(member_id, UserRole::User),
+        ]])
+        .append_query_results([vec![promoted]])
+        .append_query_results([vec![audit]])
+        .append_query_results([vec![owner]])
+        .append_exec_results([rows_affected(1), rows_affected(1), rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    leave_room(&state, room_id, owner_id).await.unwrap();
+    let statements = test_support::statements(state);
+    let promotion = statements.iter().find(|s| s.contains("UPDATE `room_members`")).unwrap();
+    assert!(promotion.contains("\"owner\"") && promotion.contains(&moderator_id.to_string()), "{}", promotion);
+    let removal = statements.iter().find(|s| s.contains("DELETE FROM `room_members`")).unwrap();
+    assert!(removal.contains(&owner_id.to_string()), "{}", removal);
+  }
+
+  #[tokio::test]
+  async fn a_leaving_member_just_leaves() {
+    let room = test_support::room(false);
+    let user_id = Uuid::new_v4();
+    let member = test_support::member(&room.id, user_id, RoomRole::Member);
+    let db = test_support::mock_db()
+        .append_query_results([vec![member.clone()], vec![member]])
+        .append_exec_results([rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    leave_room(&state, Uuid::parse_str(&room.id).unwrap(), user_id).await.unwrap();
+    assert!(!test_support::statements(state).iter().any(|s| s.contains("UPDATE")));
+  }
+}
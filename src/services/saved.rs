@@ -0,0 +1,134 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::EntityTrait;
+use uuid::Uuid;
+
+use crate::{
+  database::AppState,
+  dtos::chat::{SaveMessageRequest, SavedMessageDto},
+  entities::message::Entity as MessageEntity,
+  repositories::{room_member as member_repo, saved_message as saved_repo},
+  response::ApiError,
+  services::chat,
+};
+
+// Personal bookmarks across rooms. Messages from rooms the user has since left are not shown.
+pub async fn list_saved(state: &AppState, user_id: Uuid, limit: Option<u64>) -> Result<Vec<SavedMessageDto>, ApiError> {
+  let saved = saved_repo::list_by_user(&state.db, &user_id.to_string(), limit.unwrap_or(50).min(200)).await?;
+  let rooms: HashSet<Uuid> = member_repo::list_by_user(&state.db, &user_id.to_string())
+      .await?
+      .into_iter()
+      .filter_map(|member| Uuid::parse_str(&member.room_id).ok())
+      .collect();
+
+  let mut messages: HashMap<Uuid, _> = chat::load_messages(state, saved.iter().map(|s| s.message_id.clone()).collect())
+      .await?
+      .into_iter()
+      .filter(|message| rooms.contains(&message.room_id))
+      .map(|message| (message.id, message))
+      .collect();
+
+  let mut result = Vec::new();
+  for entry in saved {
+      let Some(message) = Uuid::parse_str(&entry.message_id).ok().and_then(|id| messages.remove(&id)) else {
+          continue;
+      };
+      result.push(SavedMessageDto {
+          message,
+          note: entry.note,
+          saved_at: entry.created_at,
+      });
+  }
+  Ok(result)
+}
+
+// Save a message from one of the user's rooms; saving it again replaces the note.
+pub async fn save_message(state: &AppState, user_id: Uuid, req: SaveMessageRequest) -> Result<SavedMessageDto, ApiError> {
+  let message = MessageEntity::find_by_id(req.message_id.to_string())
+      .one(&state.db)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Message not found".into()))?;
+  let room_id = Uuid::parse_str(&message.room_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?;
+  chat::ensure_membership(state, room_id, user_id).await?;
+
+  let note = req.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+  let saved = saved_repo::upsert(&state.db, &user_id.to_string(), &message.id, note).await?;
+  let message = chat::load_messages(state, vec![saved.message_id])
+      .await?
+      .pop()
+      .ok_or_else(|| ApiError::NotFound("Message not found".into()))?;
+
+  Ok(SavedMessageDto {
+      message,
+      note: saved.note,
+      saved_at: saved.created_at,
+  })
+}
+
+pub async fn unsave_message(state: &AppState, user_id: Uuid, message_id: Uuid) -> Result<(), ApiError> {
+  if !saved_repo::delete(&state.db, &user_id.to_string(), &message_id.to_string()).await? {
+      return Err(ApiError::NotFound("Message is not saved".into()));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Utc;
+
+  use super::*;
+  use crate::{
+    dtos::room::RoomRole,
+    entities::{
+      message_link_preview::Model as MessageLinkPreviewModel, poll::Model as PollModel,
+      room_member::Model as RoomMemberModel, saved_message::Model as SavedMessageModel,
+    },
+    test_support::{self, rows_affected},
+  };
+
+  #[tokio::test]
+  async fn only_messages_from_your_rooms_can_be_saved() {
+    let message = test_support::message_row(&Uuid::new_v4().to_string(), Uuid::new_v4(), "hi");
+    let db = test_support::mock_db()
+        .append_query_results([vec![message.clone()]])
+        .append_query_results([Vec::<RoomMemberModel>::new()])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let req = SaveMessageRequest { message_id: Uuid::parse_str(&message.id).unwrap(), note: None };
+    assert!(matches!(save_message(&state, Uuid::new_v4(), req).await, Err(ApiError::Forbidden(_))));
+  }
+
+  #[tokio::test]
+  async fn unsaving_an_unsaved_message_is_not_found() {
+    let db = test_support::mock_db().append_exec_results([rows_affected(0)]).into_connection();
+    let state = test_support::state(db);
+    assert!(matches!(unsave_message(&state, Uuid::new_v4(), Uuid::new_v4()).await, Err(ApiError::NotFound(_))));
+  }
+
+  #[tokio::test]
+  async fn messages_from_rooms_you_left_are_not_listed() {
+    let user_id = Uuid::new_v4();
+    let (current, left) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+    let kept = test_support::message_row(&current, Uuid::new_v4(), "keep");
+    let gone = test_support::message_row(&left, Uuid::new_v4(), "gone");
+    let saved = |message_id: &str| SavedMessageModel {
+      user_id: user_id.to_string(),
+      message_id: message_id.to_string(),
+      note: None,
+      created_at: Utc::now(),
+    };
+    let db = test_support::mock_db()
+        .append_query_results([vec![saved(&kept.id), saved(&gone.id)]])
+        .append_query_results([vec![test_support::member(&current, user_id, RoomRole::Member)]])
+        .append_query_results([vec![kept.clone(), gone]])
+        .append_query_results([Vec::<MessageLinkPreviewModel>::new()])
+        .append_query_results([Vec::<PollModel>::new()])
+        .into_connection();
+    let state = test_support::state(db);
+
+    let listed = list_saved(&state, user_id, None).await.unwrap();
+    assert_eq!(listed.iter().map(|s| s.message.id.to_string()).collect::<Vec<_>>(), vec![kept.id]);
+  }
+}
@@ -0,0 +1,445 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::EntityTrait;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::{
+  database::{AppState, SharedState},
+  dtos::{
+    chat::{EphemeralDto, MessageFormat, WsOutboundMessage},
+    schedule::{
+      CreateReminderRequest, JobKind, JobStatus, ListScheduledJobsQuery, ScheduleMessageRequest, ScheduledJobResponse,
+      UpdateScheduledJobRequest,
+    },
+  },
+  entities::{message::Entity as MessageEntity, scheduled_job::Model as ScheduledJobModel},
+  repositories::{scheduled_job as job_repo, user as user_repo},
+  response::ApiError,
+  services::{admin, auth, chat, notification},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BATCH_SIZE: u64 = 50;
+// How long a claimed job stays with one instance before another may pick it up.
+const CLAIM_LEASE_SECS: i64 = 60;
+const MAX_ATTEMPTS: i32 = 5;
+const MAX_DAYS_AHEAD: i64 = 365;
+const REMINDER_EXCERPT_CHARS: usize = 100;
+
+pub async fn schedule_message(
+  state: &AppState,
+  room_id: Uuid,
+  user_id: Uuid,
+  req: ScheduleMessageRequest,
+) -> Result<ScheduledJobResponse, ApiError> {
+  chat::ensure_membership(state, room_id, user_id).await?;
+  auth::ensure_verified(state, user_id).await?;
+  check_run_at(req.send_at)?;
+
+  let content = req.content.trim();
+  if content.is_empty() {
+      return Err(ApiError::BadRequest("Message content cannot be empty".into()));
+  }
+
+  let job = job_repo::insert(
+      &state.db,
+      new_job(JobKind::Message, user_id, room_id.to_string(), req.send_at, |job| {
+        job.content = Some(content.to_string());
+        job.format = Some(req.format.as_str().to_string());
+      }),
+  )
+  .await?;
+  to_response(job)
+}
+
+pub async fn create_reminder(
+  state: &AppState,
+  message_id: Uuid,
+  user_id: Uuid,
+  req: CreateReminderRequest,
+) -> Result<ScheduledJobResponse, ApiError> {
+  let remind_at = match (req.remind_at, req.in_seconds) {
+      (Some(at), None) => at,
+      (None, Some(secs)) => Utc::now() + chrono::Duration::seconds(secs),
+      _ => return Err(ApiError::BadRequest("Give exactly one of remind_at or in_seconds".into())),
+  };
+  check_run_at(remind_at)?;
+
+  let message = MessageEntity::find_by_id(message_id.to_string())
+      .one(&state.db)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Message not found".into()))?;
+  let room_id = Uuid::parse_str(&message.room_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?;
+  chat::ensure_membership(state, room_id, user_id).await?;
+
+  let note = req.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+  let job = job_repo::insert(
+      &state.db,
+      new_job(JobKind::Reminder, user_id, message.room_id, remind_at, |job| {
+        job.message_id = Some(message.id);
+        job.note = note;
+      }),
+  )
+  .await?;
+  to_response(job)
+}
+
+pub async fn list_jobs(
+  state: &AppState,
+  user_id: Uuid,
+  params: ListScheduledJobsQuery,
+) -> Result<Vec<ScheduledJobResponse>, ApiError> {
+  let jobs = job_repo::list_by_user(
+      &state.db,
+      &user_id.to_string(),
+      params.kind.map(|k| k.as_str()),
+      params.status.unwrap_or(JobStatus::Pending).as_str(),
+  )
+  .await?;
+
+  jobs.into_iter().map(to_response).collect()
+}
+
+pub async fn update_job(
+  state: &AppState,
+  user_id: Uuid,
+  job_id: Uuid,
+  req: UpdateScheduledJobRequest,
+) -> Result<ScheduledJobResponse, ApiError> {
+  let job = find_own_pending(state, user_id, job_id).await?;
+  let kind = JobKind::parse(&job.kind).unwrap_or(JobKind::Message);
+
+  if req.run_at.is_none() && req.content.is_none() && req.note.is_none() {
+      return Err(ApiError::BadRequest("Nothing to update".into()));
+  }
+  if req.content.is_some() && kind != JobKind::Message {
+      return Err(ApiError::BadRequest("Only scheduled messages have content".into()));
+  }
+  if req.note.is_some() && kind != JobKind::Reminder {
+      return Err(ApiError::BadRequest("Only reminders have a note".into()));
+  }
+  if let Some(run_at) = req.run_at {
+      check_run_at(run_at)?;
+  }
+  let content = req.content.map(|c| c.trim().to_string());
+  if content.as_ref().is_some_and(|c| c.is_empty()) {
+      return Err(ApiError::BadRequest("Message content cannot be empty".into()));
+  }
+
+  job_repo::update_pending(&state.db, &job.id, &job.user_id, req.run_at, content, req.note).await?;
+
+  let job = job_repo::find_by_id(&state.db, &job.id)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("Scheduled item not found".into()))?;
+  if JobStatus::parse(&job.status) != Some(JobStatus::Pending) {
+      return Err(ApiError::BadRequest("This item is already being delivered".into()));
+  }
+  to_response(job)
+}
+
+pub async fn cancel_job(state: &AppState, user_id: Uuid, job_id: Uuid) -> Result<(), ApiError> {
+  let job = find_own_pending(state, user_id, job_id).await?;
+
+  if !job_repo::cancel(&state.db, &job.id, &job.user_id).await? {
+      return Err(ApiError::BadRequest("This item is already being delivered".into()));
+  }
+  Ok(())
+}
+
+// Background task delivering due scheduled messages and reminders. Several instances may run it;
+// each job is claimed with a conditional update before it is touched.
+pub fn spawn_scheduler(state: SharedState) -> tokio::task::JoinHandle<()> {
+  tokio::spawn(async move {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = run_due(state.as_ref()).await {
+            tracing::error!(error = %e, "Scheduler run failed");
+        }
+    }
+  }
+  .instrument(tracing::info_span!("scheduler")))
+}
+
+async fn run_due(state: &AppState) -> Result<(), ApiError> {
+  let now = Utc::now();
+  let due = job_repo::list_claimable(&state.db, now, BATCH_SIZE).await?;
+
+  for job in due {
+      let lease = now + chrono::Duration::seconds(CLAIM_LEASE_SECS);
+      if !job_repo::claim(&state.db, &job.id, now, lease).await? {
+          continue;
+      }
+      let attempts = job.attempts + 1;
+
+      match run_job(state, &job).await {
+          Ok(()) => job_repo::finish(&state.db, &job.id, JobStatus::Done, None).await?,
+          Err(e) if is_retryable(&e) && attempts < MAX_ATTEMPTS => {
+              let retry_at = Utc::now() + chrono::Duration::seconds(30 * i64::from(attempts));
+              job_repo::reschedule(&state.db, &job.id, retry_at, truncate(&e.client_message(), 500)).await?;
+          }
+          Err(e) => {
+              job_repo::finish(&state.db, &job.id, JobStatus::Failed, Some(truncate(&e.client_message(), 500))).await?;
+          }
+      }
+  }
+
+  Ok(())
+}
+
+async fn run_job(state: &AppState, job: &ScheduledJobModel) -> Result<(), ApiError> {
+  let room_id = Uuid::parse_str(&job.room_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?;
+  let user_id = Uuid::parse_str(&job.user_id)
+      .map_err(|_| ApiError::InternalServerError("Invalid user id".into()))?;
+  // Nothing goes out for banned, suspended or departing accounts.
+  let user = user_repo::find_by_id(&state.db, &job.user_id)
+      .await?
+      .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+  admin::ensure_active(&user)?;
+
+  match JobKind::parse(&job.kind) {
+    Some(JobKind::Message) => deliver_message(state, job, room_id, user_id).await,
+    Some(JobKind::Reminder) => deliver_reminder(state, job, room_id, user_id).await,
+    None => Err(ApiError::BadRequest(format!("Unknown job kind {}", job.kind))),
+  }
+}
+
+// The message reuses the job id, so a job retried after a crash finds it instead of posting again.
+async fn deliver_message(state: &AppState, job: &ScheduledJobModel, room_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  let message_id = Uuid::parse_str(&job.id)
+      .map_err(|_| ApiError::InternalServerError("Invalid job id".into()))?;
+  if MessageEntity::find_by_id(job.id.clone()).one(&state.db).await?.is_some() {
+      return Ok(());
+  }
+
+  let content = job.content.clone().unwrap_or_default();
+  let format = job.format.as_deref().and_then(MessageFormat::parse).unwrap_or_default();
+  match chat::send_message_with_id(state, message_id, room_id, user_id, content, format).await {
+      Ok(message) => {
+          let _ = state.chat_tx.send(WsOutboundMessage::MessageCreated(message));
+          Ok(())
+      }
+      // Another instance that still held an expired claim may have stored it first.
+      Err(_) if MessageEntity::find_by_id(job.id.clone()).one(&state.db).await?.is_some() => Ok(()),
+      Err(e) => Err(e),
+  }
+}
+
+async fn deliver_reminder(state: &AppState, job: &ScheduledJobModel, room_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+  chat::ensure_membership(state, room_id, user_id).await?;
+
+  let message = chat::load_messages(state, job.message_id.clone().into_iter().collect())
+      .await?
+      .pop()
+      .ok_or_else(|| ApiError::NotFound("The message no longer exists".into()))?;
+
+  let content = match &job.note {
+      Some(note) => format!("Reminder: {}", note),
+      None => format!("Reminder: \"{}\"", truncate(&message.content, REMINDER_EXCERPT_CHARS)),
+  };
+  let _ = state.chat_tx.send(WsOutboundMessage::Ephemeral(EphemeralDto { room_id, user_id, content }));
+  notification::enqueue_reminder(state, user_id, &message).await
+}
+
+// Database hiccups and slow mode may clear up; membership, mutes and filters won't on their own.
+fn is_retryable(error: &ApiError) -> bool {
+  matches!(error, ApiError::InternalServerError(_) | ApiError::TooManyRequests(_))
+}
+
+fn check_run_at(run_at: chrono::DateTime<Utc>) -> Result<(), ApiError> {
+  let now = Utc::now();
+  if run_at <= now {
+      return Err(ApiError::BadRequest("The time must be in the future".into()));
+  }
+  if run_at > now + chrono::Duration::days(MAX_DAYS_AHEAD) {
+      return Err(ApiError::BadRequest(format!("The time must be within {} days", MAX_DAYS_AHEAD)));
+  }
+  Ok(())
+}
+
+async fn find_own_pending(state: &AppState, user_id: Uuid, job_id: Uuid) -> Result<ScheduledJobModel, ApiError> {
+  let job = job_repo::find_by_id(&state.db, &job_id.to_string())
+      .await?
+      .filter(|job| job.user_id == user_id.to_string())
+      .ok_or_else(|| ApiError::NotFound("Scheduled item not found".into()))?;
+  if JobStatus::parse(&job.status) != Some(JobStatus::Pending) {
+      return Err(ApiError::BadRequest("Only pending items can be changed".into()));
+  }
+  Ok(job)
+}
+
+fn new_job(
+  kind: JobKind,
+  user_id: Uuid,
+  room_id: String,
+  run_at: chrono::DateTime<Utc>,
+  fill: impl FnOnce(&mut ScheduledJobModel),
+) -> ScheduledJobModel {
+  let mut job = ScheduledJobModel {
+      id: Uuid::new_v4().to_string(),
+      kind: kind.as_str().to_string(),
+      user_id: user_id.to_string(),
+      room_id,
+      message_id: None,
+      content: None,
+      format: None,
+      note: None,
+      run_at,
+      status: JobStatus::Pending.as_str().to_string(),
+      attempts: 0,
+      locked_until: None,
+      last_error: None,
+      created_at: Utc::now(),
+      completed_at: None,
+  };
+  fill(&mut job);
+  job
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+  value.chars().take(max_chars).collect()
+}
+
+fn to_response(job: ScheduledJobModel) -> Result<ScheduledJobResponse, ApiError> {
+  Ok(ScheduledJobResponse {
+      id: Uuid::parse_str(&job.id)
+          .map_err(|_| ApiError::InternalServerError("Invalid job id".into()))?,
+      kind: JobKind::parse(&job.kind).unwrap_or(JobKind::Message),
+      room_id: Uuid::parse_str(&job.room_id)
+          .map_err(|_| ApiError::InternalServerError("Invalid room id".into()))?,
+      message_id: job.message_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+      content: job.content,
+      format: job.format.as_deref().and_then(MessageFormat::parse),
+      note: job.note,
+      run_at: job.run_at,
+      status: JobStatus::parse(&job.status).unwrap_or(JobStatus::Pending),
+      last_error: job.last_error,
+      created_at: job.created_at,
+      completed_at: job.completed_at,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    dtos::{admin::{AccountStatus, UserRole}, room::RoomRole},
+    entities::{message::Model as MessageModel, message_link_preview::Model as MessageLinkPreviewModel, poll::Model as PollModel},
+    test_support::{self, rows_affected},
+  };
+
+  fn due_message(user_id: Uuid) -> ScheduledJobModel {
+    new_job(JobKind::Message, user_id, Uuid::new_v4().to_string(), Utc::now(), |job| {
+      job.content = Some("good morning".into());
+      job.format = Some(MessageFormat::Plain.as_str().into());
+    })
+  }
+
+  fn last_update(statements: &[String]) -> &String {
+    statements.iter().rev().find(|s| s.contains("UPDATE")).unwrap()
+  }
+
+  #[tokio::test]
+  async fn a_job_claimed_elsewhere_is_not_run() {
+    let job = due_message(Uuid::new_v4());
+    let db = test_support::mock_db()
+        .append_query_results([vec![job]])
+        .append_exec_results([rows_affected(0)])
+        .into_connection();
+    let state = test_support::state(db);
+    let mut events = state.chat_tx.subscribe();
+
+    run_due(&state).await.unwrap();
+    let statements = test_support::statements(state);
+    // The listing and the lost claim, nothing else.
+    assert_eq!(statements.len(), 2, "{:?}", statements);
+    assert!(statements[1].contains("`status` = ? AND `scheduled_jobs`.`run_at` <= ?"), "{}", statements[1]);
+    assert!(events.try_recv().is_err());
+  }
+
+  #[tokio::test]
+  async fn a_retried_job_does_not_post_its_message_twice() {
+    let user_id = Uuid::new_v4();
+    let job = due_message(user_id);
+    // A previous attempt stored the message (under the job's id) and then crashed.
+    let stored = MessageModel { id: job.id.clone(), ..test_support::message_row(&job.room_id, user_id, "good morning") };
+    let db = test_support::mock_db()
+        .append_query_results([vec![job]])
+        .append_query_results([vec![test_support::user(user_id, UserRole::User)]])
+        .append_query_results([vec![stored]])
+        .append_exec_results([rows_affected(1), rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+    let mut events = state.chat_tx.subscribe();
+
+    run_due(&state).await.unwrap();
+    let statements = test_support::statements(state);
+    assert!(!statements.iter().any(|s| s.contains("INSERT")));
+    assert!(last_update(&statements).contains("\"done\""));
+    assert!(events.try_recv().is_err());
+  }
+
+  #[tokio::test]
+  async fn jobs_of_inactive_accounts_fail_without_retrying() {
+    let user_id = Uuid::new_v4();
+    let mut banned = test_support::user(user_id, UserRole::User);
+    banned.status = AccountStatus::Banned.as_str().into();
+    let db = test_support::mock_db()
+        .append_query_results([vec![due_message(user_id)]])
+        .append_query_results([vec![banned]])
+        .append_exec_results([rows_affected(1), rows_affected(1)])
+        .into_connection();
+    let state = test_support::state(db);
+
+    run_due(&state).await.unwrap();
+    assert!(last_update(&test_support::statements(state)).contains("\"failed\""));
+  }
+
+  #[tokio::test]
+  async fn reminders_go_only_to_their_owner() {
+    let user_id = Uuid::new_v4();
+    let message = test_support::message_row(&Uuid::new_v4().to_string(), Uuid::new_v4(), "ship the release");
+    let job = new_job(JobKind::Reminder, user_id, message.room_id.clone(), Utc::now(), |job| {
+      job.message_id = Some(message.id.clone());
+    });
+    let db = test_support::mock_db()
+        .append_query_results([vec![test_support::member(&message.room_id, user_id, RoomRole::Member)]])
+        .append_query_results([vec![message]])
+        .append_query_results([Vec::<MessageLinkPreviewModel>::new()])
+        .append_query_results([Vec::<PollModel>::new()])
+        .into_connection();
+    let state = test_support::state(db);
+    let mut events = state.chat_tx.subscribe();
+    // Online, so no push notification is queued on top.
+    let _socket = state.presence.connect(user_id);
+
+    let room_id = Uuid::parse_str(&job.room_id).unwrap();
+    deliver_reminder(&state, &job, room_id, user_id).await.unwrap();
+    match events.try_recv().unwrap() {
+        WsOutboundMessage::Ephemeral(reminder) => {
+            assert_eq!(reminder.user_id, user_id);
+            assert_eq!(reminder.content, "Reminder: \"ship the release\"");
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+  }
+
+  #[test]
+  fn run_times_must_be_in_the_next_year() {
+    assert!(check_run_at(Utc::now() - chrono::Duration::minutes(1)).is_err());
+    assert!(check_run_at(Utc::now() + chrono::Duration::minutes(1)).is_ok());
+    assert!(check_run_at(Utc::now() + chrono::Duration::days(MAX_DAYS_AHEAD + 1)).is_err());
+  }
+
+  #[test]
+  fn only_transient_errors_are_retried() {
+    assert!(is_retryable(&ApiError::InternalServerError("db".into())));
+    assert!(is_retryable(&ApiError::TooManyRequests("slow mode".into())));
+    assert!(!is_retryable(&ApiError::Forbidden("muted".into())));
+    assert!(!is_retryable(&ApiError::BadRequest("filtered".into())));
+  }
+}
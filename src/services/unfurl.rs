@@ -0,0 +1,372 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use scraper::{Html, Selector};
+use sha2::{Digest, Sha256};
+use tokio::sync::{Semaphore, broadcast::error::RecvError};
+use tracing::Instrument;
+use url::Url;
+
+use crate::{
+  database::SharedState,
+  dtos::chat::{MessageDto, WsOutboundMessage},
+  entities::link_preview::Model as LinkPreviewModel,
+  repositories::link_preview as link_preview_repo,
+  response::ApiError,
+  services::{chat, outbound},
+};
+
+pub const STATUS_OK: &str = "ok";
+pub const STATUS_FAILED: &str = "failed";
+
+const MAX_URLS_PER_MESSAGE: usize = 3;
+const MAX_CONCURRENT_FETCHES: usize = 8;
+const MAX_REDIRECTS: usize = 3;
+const MAX_BODY_BYTES: usize = 512 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+// Cached previews (including failures) are refetched after this long.
+const CACHE_TTL_HOURS: i64 = 24;
+
+// Background task fetching link previews for new messages and pushing them as `message.updated`.
+pub fn spawn_unfurler(state: SharedState) -> tokio::task::JoinHandle<()> {
+  tokio::spawn(async move {
+    let allow_private = state.config.unfurl.allow_private_networks;
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+
+    let mut rx = state.chat_tx.subscribe();
+    loop {
+        let message = match rx.recv().await {
+            Ok(WsOutboundMessage::MessageCreated(message)) => message,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "Link unfurler fell behind and skipped messages");
+                state.metrics.events_dropped("unfurler", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        let urls = extract_urls(&message.content);
+        if urls.is_empty() {
+            continue;
+        }
+
+        let state = state.clone();
+        let permits = permits.clone();
+        tokio::spawn(async move {
+          let Ok(_permit) = permits.acquire_owned().await else {
+              return;
+          };
+          if let Err(e) = unfurl_message(&state, message, urls, allow_private).await {
+              tracing::warn!(error = %e, "Link unfurl failed");
+          }
+        }
+        .in_current_span());
+    }
+  }
+  .instrument(tracing::info_span!("unfurler")))
+}
+
+async fn unfurl_message(
+  state: &SharedState,
+  mut message: MessageDto,
+  urls: Vec<Url>,
+  allow_private: bool,
+) -> Result<(), ApiError> {
+  let message_id = message.id.to_string();
+  let mut found = false;
+
+  for (position, url) in urls.into_iter().enumerate() {
+      let preview = cached_or_fetch(state, url, allow_private).await?;
+      link_preview_repo::attach_to_message(&state.db, &message_id, &preview.id, position as i32).await?;
+      found |= preview.status == STATUS_OK;
+  }
+
+  if !found {
+      return Ok(());
+  }
+
+  chat::attach_previews(state, std::slice::from_mut(&mut message)).await?;
+  let _ = state.chat_tx.send(WsOutboundMessage::MessageUpdated(message));
+  Ok(())
+}
+
+async fn cached_or_fetch(
+  state: &SharedState,
+  url: Url,
+  allow_private: bool,
+) -> Result<LinkPreviewModel, ApiError> {
+  let id = hex::encode(Sha256::digest(url.as_str().as_bytes()));
+
+  if let Some(cached) = link_preview_repo::find_by_id(&state.db, &id).await?
+      && Utc::now() - cached.fetched_at < chrono::Duration::hours(CACHE_TTL_HOURS)
+  {
+      return Ok(cached);
+  }
+
+  let preview = match fetch_preview(&url, allow_private).await {
+      Ok(page) => LinkPreviewModel {
+          id,
+          url: url.to_string(),
+          title: page.title,
+          description: page.description,
+          image_url: page.image_url,
+          site_name: page.site_name,
+          status: STATUS_OK.to_string(),
+          fetched_at: Utc::now(),
+      },
+      Err(e) => {
+          tracing::debug!(%url, error = %e, "Failed to fetch link preview");
+          LinkPreviewModel {
+              id,
+              url: url.to_string(),
+              title: None,
+              description: None,
+              image_url: None,
+              site_name: None,
+              status: STATUS_FAILED.to_string(),
+              fetched_at: Utc::now(),
+          }
+      }
+  };
+
+  Ok(link_preview_repo::upsert(&state.db, preview).await?)
+}
+
+// Find up to `MAX_URLS_PER_MESSAGE` distinct http(s) links in the message text.
+pub fn extract_urls(content: &str) -> Vec<Url> {
+  find_urls(content, MAX_URLS_PER_MESSAGE)
+}
+
+pub fn find_urls(content: &str, limit: usize) -> Vec<Url> {
+  let mut urls: Vec<Url> = Vec::new();
+  let mut rest = content;
+
+  while urls.len() < limit {
+      let start = match (rest.find("http://"), rest.find("https://")) {
+          (Some(a), Some(b)) => a.min(b),
+          (Some(a), None) | (None, Some(a)) => a,
+          (None, None) => break,
+      };
+      let candidate = &rest[start..];
+      let end = candidate
+          .find(|c: char| c.is_whitespace() || "<>()[]\"'".contains(c))
+          .unwrap_or(candidate.len());
+      let raw = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+      rest = &candidate[end.max(1)..];
+
+      if let Ok(url) = Url::parse(raw)
+          && url.host().is_some()
+          && !urls.contains(&url)
+      {
+          urls.push(url);
+      }
+  }
+
+  urls
+}
+
+#[derive(Debug, Default)]
+struct PageMeta {
+  title: Option<String>,
+  description: Option<String>,
+  image_url: Option<String>,
+  site_name: Option<String>,
+}
+
+async fn fetch_preview(url: &Url, allow_private: bool) -> anyhow::Result<PageMeta> {
+  let mut current = url.clone();
+
+  for _ in 0..=MAX_REDIRECTS {
+      let client = outbound::pinned_client(&current, allow_private)
+          .await?
+          .timeout(FETCH_TIMEOUT)
+          .user_agent("chat-app-unfurler/0.1")
+          .build()?;
+      let mut response = client.get(current.clone()).send().await?;
+
+      if response.status().is_redirection() {
+          let location = response
+              .headers()
+              .get(reqwest::header::LOCATION)
+              .and_then(|v| v.to_str().ok())
+              .ok_or_else(|| anyhow::anyhow!("redirect without location"))?;
+          current = current.join(location)?;
+          continue;
+      }
+
+      if !response.status().is_success() {
+          anyhow::bail!("unexpected status {}", response.status());
+      }
+
+      let is_html = response
+          .headers()
+          .get(reqwest::header::CONTENT_TYPE)
+          .and_then(|v| v.to_str().ok())
+          .is_some_and(|v| v.trim_start().to_ascii_lowercase().starts_with("text/html"));
+      if !is_html {
+          anyhow::bail!("not an html page");
+      }
+
+      // Read at most MAX_BODY_BYTES; anything past that is ignored.
+      let mut body = Vec::new();
+      while let Some(chunk) = response.chunk().await? {
+          let remaining = MAX_BODY_BYTES - body.len();
+          body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+          if body.len() >= MAX_BODY_BYTES {
+              break;
+          }
+      }
+
+      return Ok(parse_meta(&String::from_utf8_lossy(&body), &current));
+  }
+
+  anyhow::bail!("too many redirects")
+}
+
+fn parse_meta(html: &str, page_url: &Url) -> PageMeta {
+  let document = Html::parse_document(html);
+
+  let meta = |attr: &str, name: &str| -> Option<String> {
+      let selector = Selector::parse(&format!("meta[{}=\"{}\"]", attr, name)).ok()?;
+      document
+          .select(&selector)
+          .filter_map(|el| el.value().attr("content"))
+          .map(|v| v.trim().to_string())
+          .find(|v| !v.is_empty())
+  };
+  let title_tag = || -> Option<String> {
+      let selector = Selector::parse("title").ok()?;
+      let title = document.select(&selector).next()?.text().collect::<String>();
+      let title = title.trim();
+      (!title.is_empty()).then(|| title.to_string())
+  };
+
+  let image_url = meta("property", "og:image")
+      .and_then(|src| page_url.join(&src).ok())
+      .filter(|url| matches!(url.scheme(), "http" | "https"))
+      .map(|url| url.to_string());
+
+  PageMeta {
+      title: meta("property", "og:title").or_else(title_tag).map(|v| truncate(v, 512)),
+      description: meta("property", "og:description")
+          .or_else(|| meta("name", "description"))
+          .map(|v| truncate(v, 1024)),
+      image_url,
+      site_name: meta("property", "og:site_name").map(|v| truncate(v, 255)),
+  }
+}
+
+fn truncate(value: String, max_chars: usize) -> String {
+  match value.char_indices().nth(max_chars) {
+      Some((idx, _)) => value[..idx].to_string(),
+      None => value,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::{matchers::{method, path}, Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+
+  const PAGE: &str = r#"<html><head>
+    <title>Fallback title</title>
+    <meta property="og:title" content="Example page">
+    <meta name="description" content="A page about examples">
+    <meta property="og:image" content="/cover.png">
+    <meta property="og:site_name" content="Example">
+  </head><body></body></html>"#;
+
+  async fn server_with_page() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(PAGE, "text/html; charset=utf-8"))
+        .mount(&server)
+        .await;
+    server
+  }
+
+  #[tokio::test]
+  async fn fetches_preview_when_private_networks_are_allowed() {
+    let server = server_with_page().await;
+    let url = Url::parse(&format!("{}/page", server.uri())).unwrap();
+
+    let meta = fetch_preview(&url, true).await.unwrap();
+
+    assert_eq!(meta.title.as_deref(), Some("Example page"));
+    assert_eq!(meta.description.as_deref(), Some("A page about examples"));
+    assert_eq!(meta.image_url, Some(format!("{}/cover.png", server.uri())));
+    assert_eq!(meta.site_name.as_deref(), Some("Example"));
+  }
+
+  #[tokio::test]
+  async fn refuses_local_server_by_default() {
+    let server = server_with_page().await;
+    let url = Url::parse(&format!("{}/page", server.uri())).unwrap();
+
+    assert!(fetch_preview(&url, false).await.is_err());
+    assert!(server.received_requests().await.unwrap().is_empty());
+  }
+
+  #[tokio::test]
+  async fn refuses_local_server_behind_ipv6_forms() {
+    let server = server_with_page().await;
+    let port = server.address().port();
+
+    for host in ["[::ffff:127.0.0.1]", "[::127.0.0.1]", "[2002:7f00:1::]", "[64:ff9b::7f00:1]"] {
+        let url = Url::parse(&format!("http://{}:{}/page", host, port)).unwrap();
+        assert!(fetch_preview(&url, false).await.is_err(), "{} should be refused", url);
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+  }
+
+  #[tokio::test]
+  async fn follows_a_bounded_number_of_redirects() {
+    let server = server_with_page().await;
+    Mock::given(method("GET"))
+        .and(path("/short"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", "/page"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/loop"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", "/loop"))
+        .mount(&server)
+        .await;
+
+    let short = Url::parse(&format!("{}/short", server.uri())).unwrap();
+    assert_eq!(fetch_preview(&short, true).await.unwrap().title.as_deref(), Some("Example page"));
+
+    let looping = Url::parse(&format!("{}/loop", server.uri())).unwrap();
+    assert!(fetch_preview(&looping, true).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn skips_non_html_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("{}", "application/json"))
+        .mount(&server)
+        .await;
+    let url = Url::parse(&server.uri()).unwrap();
+
+    assert!(fetch_preview(&url, true).await.is_err());
+  }
+
+  #[test]
+  fn extracts_distinct_links_without_trailing_punctuation() {
+    let urls = extract_urls("see https://a.example/x, (http://b.example) and https://a.example/x. https://c.example https://d.example");
+    let urls: Vec<&str> = urls.iter().map(Url::as_str).collect();
+    assert_eq!(urls, ["https://a.example/x", "http://b.example/", "https://c.example/"]);
+  }
+
+  #[test]
+  fn ignores_image_urls_with_unsafe_schemes() {
+    let page_url = Url::parse("https://example.com/").unwrap();
+    let meta = parse_meta(r#"<meta property="og:image" content="javascript:alert(1)"><title>t</title>"#, &page_url);
+    assert_eq!(meta.image_url, None);
+    assert_eq!(meta.title.as_deref(), Some("t"));
+  }
+}
@@ -1,17 +1,19 @@
-use sea_orm::EntityTrait;
+use std::collections::HashSet;
+
 use uuid::Uuid;
 
 use crate::{
     database::AppState,
-    dtos::room::UserInfo,
-    entities::user::Entity as UserEntity,
+    dtos::{
+        room::UserInfo,
+        user::{BlockUserRequest, BlockedUserResponse},
+    },
+    repositories::{user as user_repo, user_block as block_repo},
     response::ApiError,
 };
 
 pub async fn list_all_users(state: &AppState) -> Result<Vec<UserInfo>, ApiError> {
-    let users = UserEntity::find()
-        .all(&state.db)
-        .await?;
+    let users = user_repo::list_all(&state.db).await?;
 
     let mut user_infos = Vec::new();
     for user in users {
@@ -24,4 +26,147 @@ pub async fn list_all_users(state: &AppState) -> Result<Vec<UserInfo>, ApiError>
     }
 
     Ok(user_infos)
-}
\ No newline at end of file
+}
+
+pub async fn list_blocks(state: &AppState, user_id: Uuid) -> Result<Vec<BlockedUserResponse>, ApiError> {
+    let blocks = block_repo::list_by_blocker(&state.db, &user_id.to_string()).await?;
+
+    let mut responses = Vec::new();
+    for block in blocks {
+        // Skip users deleted since; their rows go away with them.
+        let Some(user) = user_repo::find_by_id(&state.db, &block.blocked_id).await? else {
+            continue;
+        };
+        responses.push(BlockedUserResponse {
+            user: UserInfo {
+                id: Uuid::parse_str(&user.id)
+                    .map_err(|_| ApiError::InternalServerError("Invalid user id".into()))?,
+                username: user.username,
+                email: user.email,
+            },
+            blocked_at: block.created_at,
+        });
+    }
+
+    Ok(responses)
+}
+
+pub async fn block_user(
+    state: &AppState,
+    user_id: Uuid,
+    req: BlockUserRequest,
+) -> Result<BlockedUserResponse, ApiError> {
+    if req.user_id == user_id {
+        return Err(ApiError::BadRequest("You cannot block yourself".into()));
+    }
+    let user = user_repo::find_by_id(&state.db, &req.user_id.to_string())
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+
+    let blocker_id = user_id.to_string();
+    let block = match block_repo::find(&state.db, &blocker_id, &user.id).await? {
+        Some(block) => block,
+        None => block_repo::insert(&state.db, &blocker_id, &user.id).await?,
+    };
+    state.presence.blocks_changed(user_id);
+
+    Ok(BlockedUserResponse {
+        user: UserInfo {
+            id: req.user_id,
+            username: user.username,
+            email: user.email,
+        },
+        blocked_at: block.created_at,
+    })
+}
+
+pub async fn unblock_user(state: &AppState, user_id: Uuid, blocked_id: Uuid) -> Result<(), ApiError> {
+    if !block_repo::delete(&state.db, &user_id.to_string(), &blocked_id.to_string()).await? {
+        return Err(ApiError::NotFound("User is not blocked".into()));
+    }
+    state.presence.blocks_changed(user_id);
+    Ok(())
+}
+
+pub async fn has_blocked(state: &AppState, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, ApiError> {
+    Ok(block_repo::find(&state.db, &blocker_id.to_string(), &blocked_id.to_string())
+        .await?
+        .is_some())
+}
+
+// Everyone the user has blocked, for hiding their messages.
+pub async fn blocked_ids(state: &AppState, user_id: Uuid) -> Result<HashSet<Uuid>, ApiError> {
+    Ok(block_repo::list_by_blocker(&state.db, &user_id.to_string())
+        .await?
+        .into_iter()
+        .filter_map(|block| Uuid::parse_str(&block.blocked_id).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{
+        dtos::admin::UserRole,
+        entities::user_block::Model as UserBlockModel,
+        test_support::{self, rows_affected},
+    };
+
+    fn block(blocker_id: Uuid, blocked_id: Uuid) -> UserBlockModel {
+        UserBlockModel {
+            blocker_id: blocker_id.to_string(),
+            blocked_id: blocked_id.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn cannot_block_yourself() {
+        let state = test_support::state(test_support::mock_db().into_connection());
+        let user_id = Uuid::new_v4();
+        let result = block_user(&state, user_id, BlockUserRequest { user_id }).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn blocking_twice_keeps_one_row_and_tells_live_sockets() {
+        let (user_id, target_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let db = test_support::mock_db()
+            .append_query_results([vec![test_support::user(target_id, UserRole::User)]])
+            .append_query_results([vec![block(user_id, target_id)]])
+            .into_connection();
+        let state = test_support::state(db);
+        let mut changes = state.presence.subscribe_block_changes();
+
+        let response = block_user(&state, user_id, BlockUserRequest { user_id: target_id }).await.unwrap();
+        assert_eq!(response.user.id, target_id);
+        assert_eq!(changes.try_recv().unwrap(), user_id);
+        assert!(!test_support::statements(state).iter().any(|s| s.contains("INSERT")));
+    }
+
+    #[tokio::test]
+    async fn unblocking_someone_not_blocked_is_not_found() {
+        let db = test_support::mock_db().append_exec_results([rows_affected(0)]).into_connection();
+        let state = test_support::state(db);
+        let mut changes = state.presence.subscribe_block_changes();
+
+        let result = unblock_user(&state, Uuid::new_v4(), Uuid::new_v4()).await;
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn blocked_ids_skip_malformed_rows() {
+        let (user_id, target_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut malformed = block(user_id, target_id);
+        malformed.blocked_id = "not-a-uuid".into();
+        let db = test_support::mock_db()
+            .append_query_results([vec![block(user_id, target_id), malformed]])
+            .into_connection();
+        let state = test_support::state(db);
+
+        assert_eq!(blocked_ids(&state, user_id).await.unwrap(), HashSet::from([target_id]));
+    }
+}
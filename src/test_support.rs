@@ -0,0 +1,128 @@
+// Fixtures shared by unit tests: an app state over sea-orm's mock database.
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use sea_orm::{DatabaseBackend, DatabaseConnection, MockDatabase, MockExecResult};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+  config::Config,
+  database::AppState,
+  dtos::{
+    admin::{AccountStatus, UserRole},
+    chat::{MessageDto, MessageFormat},
+    room::RoomRole,
+  },
+  entities::{
+    message::Model as MessageModel, room::Model as RoomModel, room_member::Model as RoomMemberModel,
+    user::Model as UserModel,
+  },
+  metrics::Metrics,
+  security::JwtManager,
+  services::{command::CommandRegistry, filter::FilterCache, mailer, presence::Presence},
+};
+
+pub const JWT_SECRET: &str = "unit-test-signing-secret-0123456789abcdef";
+
+pub fn mock_db() -> MockDatabase {
+  MockDatabase::new(DatabaseBackend::MySql)
+}
+
+pub fn state(db: DatabaseConnection) -> AppState {
+  state_with_config(db, Config::default())
+}
+
+pub fn state_with_config(db: DatabaseConnection, config: Config) -> AppState {
+  let (chat_tx, _chat_rx) = broadcast::channel(config.limits.event_buffer);
+  AppState {
+      db,
+      jwt: JwtManager::new(&[], Some(JWT_SECRET), Duration::from_secs(3600)).expect("test JWT manager"),
+      chat_tx,
+      presence: Presence::default(),
+      commands: Arc::new(CommandRegistry::with_builtins()),
+      mailer: mailer::from_config(&config.mail).expect("test mailer"),
+      oidc: None,
+      filters: FilterCache::default(),
+      config: Arc::new(config),
+      metrics: Arc::new(Metrics::new().expect("test metrics")),
+  }
+}
+
+pub fn rows_affected(rows_affected: u64) -> MockExecResult {
+  MockExecResult { last_insert_id: 0, rows_affected }
+}
+
+// Statements the mock database saw, rendered for substring checks.
+pub fn statements(state: AppState) -> Vec<String> {
+  state.db.into_transaction_log().iter().map(|t| format!("{:?}", t)).collect()
+}
+
+pub fn user(id: Uuid, role: UserRole) -> UserModel {
+  UserModel {
+    id: id.to_string(),
+    username: format!("user-{}", &id.simple().to_string()[..8]),
+    email: format!("{}@example.com", id.simple()),
+    password: String::new(),
+    created_at: Utc::now(),
+    email_verified_at: Some(Utc::now()),
+    role: role.as_str().to_string(),
+    status: AccountStatus::Active.as_str().to_string(),
+    suspended_until: None,
+    status_reason: None,
+    delete_after: None,
+  }
+}
+
+pub fn room(is_direct: bool) -> RoomModel {
+  RoomModel {
+    id: Uuid::new_v4().to_string(),
+    name: "general".into(),
+    topic: None,
+    slow_mode_seconds: 0,
+    retention_max_age_days: None,
+    retention_max_messages: None,
+    legal_hold: false,
+    is_direct,
+    created_at: Utc::now(),
+  }
+}
+
+pub fn member(room_id: &str, user_id: Uuid, role: RoomRole) -> RoomMemberModel {
+  RoomMemberModel {
+    room_id: room_id.to_string(),
+    user_id: user_id.to_string(),
+    joined_at: Utc::now(),
+    notify_level: "mentions".into(),
+    notifications_muted: false,
+    role: role.as_str().to_string(),
+  }
+}
+
+pub fn message(room_id: Uuid, sender_id: Uuid, content: &str) -> MessageDto {
+  MessageDto {
+    id: Uuid::new_v4(),
+    room_id,
+    sender_id,
+    content: content.to_string(),
+    format: MessageFormat::Plain,
+    html: None,
+    thread_id: None,
+    previews: Vec::new(),
+    poll: None,
+    created_at: Utc::now(),
+  }
+}
+
+pub fn message_row(room_id: &str, sender_id: Uuid, content: &str) -> MessageModel {
+  MessageModel {
+    id: Uuid::new_v4().to_string(),
+    room_id: room_id.to_string(),
+    sender_id: sender_id.to_string(),
+    content: content.to_string(),
+    format: MessageFormat::Plain.as_str().to_string(),
+    rendered_html: None,
+    thread_id: None,
+    created_at: Utc::now(),
+  }
+}